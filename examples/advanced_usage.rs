@@ -1,5 +1,6 @@
 //! Erweiterte Verwendung mit Statistiken und Utils
 
+use rust_project_complete::OperationType;
 use rust_project_complete::modules::core::{Statistics, MathUtils};
 use rust_project_complete::modules::utils::*;
 
@@ -9,9 +10,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     // Statistiken
     let mut stats = Statistics::new();
-    stats.add_operation("add", 10.0);
-    stats.add_operation("multiply", 20.0);
-    stats.add_operation("subtract", 5.0);
+    stats.add_operation(OperationType::Add, 10.0);
+    stats.add_operation(OperationType::Multiply, 20.0);
+    stats.add_operation(OperationType::Subtract, 5.0);
     
     println!("Statistiken:");
     println!("Total: {}", stats.total_operations);
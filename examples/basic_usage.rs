@@ -10,9 +10,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     // Grundlegende Operationen
     println!("Grundlegende Operationen:");
-    println!("5 + 3 = {}", calc.add(5.0, 3.0));
-    println!("10 - 4 = {}", calc.subtract(10.0, 4.0));
-    println!("6 * 7 = {}", calc.multiply(6.0, 7.0));
+    println!("5 + 3 = {}", calc.add(5.0, 3.0)?);
+    println!("10 - 4 = {}", calc.subtract(10.0, 4.0)?);
+    println!("6 * 7 = {}", calc.multiply(6.0, 7.0)?);
     println!("15 / 3 = {}", calc.divide(15.0, 3.0)?);
     
     // Fakultät
@@ -21,9 +21,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     // Verlauf anzeigen
     println!("\nVerlauf ({} Operationen):", calc.history_count());
-    for (i, op) in calc.get_history().iter().enumerate() {
-        println!("{}. {} = {}", i + 1, op.operation_type, op.result);
-    }
+    let mut i = 0;
+    calc.scan_history(&mut |op| {
+        i += 1;
+        println!("{}. {} = {}", i, op.operation_type, op.result);
+    })?;
     
     Ok(())
 }
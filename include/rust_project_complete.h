@@ -0,0 +1,183 @@
+#ifndef RUST_PROJECT_COMPLETE_H
+#define RUST_PROJECT_COMPLETE_H
+
+/* Automatisch erzeugt mit cbindgen, nicht von Hand bearbeiten. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Ergebnis eines Aufrufs
+ */
+typedef enum CalcStatus {
+  CALC_STATUS_OK = 0,
+  CALC_STATUS_DIVISION_BY_ZERO = 1,
+  CALC_STATUS_OVERFLOW = 2,
+  CALC_STATUS_INVALID_INPUT = 3,
+  CALC_STATUS_IO_ERROR = 4,
+  CALC_STATUS_PARSE_ERROR = 5,
+  CALC_STATUS_CANCELLED = 6,
+  CALC_STATUS_AUDIT_VIOLATION = 7,
+  /**
+   * Ein Zeiger war `NULL` oder eine Zeichenkette kein gültiges UTF-8
+   */
+  CALC_STATUS_INVALID_ARGUMENT = 100,
+  /**
+   * Ein interner Fehler hat die Operation abgebrochen
+   */
+  CALC_STATUS_PANIC = 101,
+} CalcStatus;
+
+/**
+ * Art einer Operation im Verlauf
+ */
+typedef enum CalcOperationType {
+  CALC_OPERATION_TYPE_ADD = 0,
+  CALC_OPERATION_TYPE_SUBTRACT = 1,
+  CALC_OPERATION_TYPE_MULTIPLY = 2,
+  CALC_OPERATION_TYPE_DIVIDE = 3,
+  /**
+   * Registrierte Operation; der Name ist über die C-Schnittstelle nicht verfügbar
+   */
+  CALC_OPERATION_TYPE_CUSTOM = 4,
+} CalcOperationType;
+
+/**
+ * Undurchsichtiges Handle auf einen Calculator mit Verlauf im Arbeitsspeicher
+ */
+typedef struct CalcCalculator CalcCalculator;
+
+/**
+ * Eintrag im Verlauf
+ */
+typedef struct CalcOperation {
+  uint64_t id;
+  enum CalcOperationType operation_type;
+  double operands[2];
+  double result;
+  uint64_t timestamp;
+} CalcOperation;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Erstellt einen neuen Calculator; freigeben mit [`calc_free`]
+ */
+struct CalcCalculator *calc_new(void);
+
+/**
+ * Gibt einen Calculator frei; `NULL` wird ignoriert
+ *
+ * # Safety
+ *
+ * `calc` muss von [`calc_new`] stammen und darf danach nicht mehr verwendet werden.
+ */
+void calc_free(struct CalcCalculator *calc);
+
+/**
+ * Addiert zwei Zahlen und schreibt das Ergebnis nach `result`
+ *
+ * # Safety
+ *
+ * `calc` muss ein gültiges Handle und `result` ein gültiger Zeiger sein.
+ */
+enum CalcStatus calc_add(struct CalcCalculator *calc, double a, double b, double *result);
+
+/**
+ * Subtrahiert zwei Zahlen und schreibt das Ergebnis nach `result`
+ *
+ * # Safety
+ *
+ * `calc` muss ein gültiges Handle und `result` ein gültiger Zeiger sein.
+ */
+enum CalcStatus calc_subtract(struct CalcCalculator *calc, double a, double b, double *result);
+
+/**
+ * Multipliziert zwei Zahlen und schreibt das Ergebnis nach `result`
+ *
+ * # Safety
+ *
+ * `calc` muss ein gültiges Handle und `result` ein gültiger Zeiger sein.
+ */
+enum CalcStatus calc_multiply(struct CalcCalculator *calc, double a, double b, double *result);
+
+/**
+ * Dividiert zwei Zahlen und schreibt das Ergebnis nach `result`
+ *
+ * # Safety
+ *
+ * `calc` muss ein gültiges Handle und `result` ein gültiger Zeiger sein.
+ */
+enum CalcStatus calc_divide(struct CalcCalculator *calc, double a, double b, double *result);
+
+/**
+ * Berechnet die Fakultät von `n`
+ *
+ * # Safety
+ *
+ * `calc` muss ein gültiges Handle und `result` ein gültiger Zeiger sein.
+ */
+enum CalcStatus calc_factorial(struct CalcCalculator *calc, uint64_t n, uint64_t *result);
+
+/**
+ * Wertet einen arithmetischen Ausdruck (UTF-8) aus
+ *
+ * # Safety
+ *
+ * `calc` muss ein gültiges Handle, `expression` eine nullterminierte
+ * Zeichenkette und `result` ein gültiger Zeiger sein.
+ */
+enum CalcStatus calc_evaluate(struct CalcCalculator *calc, const char *expression, double *result);
+
+/**
+ * Gibt die Anzahl der Operationen im Verlauf zurück; 0 bei `NULL`
+ *
+ * # Safety
+ *
+ * `calc` muss `NULL` oder ein gültiges Handle sein.
+ */
+size_t calc_history_count(const struct CalcCalculator *calc);
+
+/**
+ * Schreibt den Verlaufseintrag an Position `index` (ab 0) nach `entry`
+ *
+ * # Safety
+ *
+ * `calc` muss ein gültiges Handle und `entry` ein gültiger Zeiger sein.
+ */
+enum CalcStatus calc_history_entry(struct CalcCalculator *calc,
+                                   size_t index,
+                                   struct CalcOperation *entry);
+
+/**
+ * Löscht den Verlauf
+ *
+ * # Safety
+ *
+ * `calc` muss ein gültiges Handle sein.
+ */
+enum CalcStatus calc_clear_history(struct CalcCalculator *calc);
+
+/**
+ * Meldung des letzten Fehlers im aktuellen Thread oder `NULL`
+ *
+ * Der Zeiger bleibt bis zum nächsten Aufruf im selben Thread gültig und darf
+ * nicht freigegeben werden.
+ */
+const char *calc_last_error_message(void);
+
+/**
+ * Kurzer, gleichbleibender Name eines Status, z. B. `"division_by_zero"`
+ */
+const char *calc_status_name(enum CalcStatus status);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* RUST_PROJECT_COMPLETE_H */
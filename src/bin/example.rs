@@ -9,11 +9,15 @@ fn main() {
     println!("========================");
     
     // Einige Berechnungen
-    let result1 = calc.add(10.0, 5.0);
-    println!("10 + 5 = {}", result1);
+    match calc.add(10.0, 5.0) {
+        Ok(result) => println!("10 + 5 = {}", result),
+        Err(e) => eprintln!("Fehler: {}", e),
+    }
     
-    let result2 = calc.multiply(3.0, 4.0);
-    println!("3 * 4 = {}", result2);
+    match calc.multiply(3.0, 4.0) {
+        Ok(result) => println!("3 * 4 = {}", result),
+        Err(e) => eprintln!("Fehler: {}", e),
+    }
     
     match calc.divide(20.0, 4.0) {
         Ok(result) => println!("20 / 4 = {}", result),
@@ -27,7 +31,11 @@ fn main() {
     
     println!("\nVerlauf: {} Operationen", calc.history_count());
     
-    for (i, op) in calc.get_history().iter().enumerate() {
-        println!("{}. {} = {}", i + 1, op.operation_type, op.result);
+    let mut i = 0;
+    if let Err(e) = calc.scan_history(&mut |op| {
+        i += 1;
+        println!("{}. {} = {}", i, op.operation_type, op.result);
+    }) {
+        eprintln!("Fehler: {}", e);
     }
 }
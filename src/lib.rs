@@ -7,48 +7,233 @@
 //! ```rust
 //! use rust_project_complete::Calculator;
 //! 
-//! let calc = Calculator::new();
-//! let result = calc.add(2, 3);
-//! assert_eq!(result, 5);
+//! let mut calc = Calculator::new();
+//! let result = calc.add(2.0, 3.0).unwrap();
+//! assert_eq!(result, 5.0);
 //! ```
 
 pub mod modules;
 
+use modules::core::Statistics;
 use modules::error::ProjectError;
-use serde::{Deserialize, Serialize};
+use modules::expression::Expression;
+use modules::history::{HistoryQuery, HistoryStore, MemoryHistoryStore};
+use modules::i18n::{Argument, Locale, Message};
+use modules::logging;
+use modules::numeric::{lossless, NumericPolicy};
+use modules::observer::{CalculatorObserver, HistoryEvent, ObserverHandle, Observers};
+use modules::operations::{
+    BuiltIn, ExpressionOperator, Factorial, OperationDefinition, OperationRegistry, Operator,
+};
+use modules::utils;
+use serde::ser::SerializeSeq;
+use serde::{Deserialize, Serialize, Serializer};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::fmt;
+use std::sync::Arc;
+use tracing::field;
 
 /// Hauptstruktur für mathematische Operationen
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Der Verlauf liegt standardmäßig im Arbeitsspeicher und kann mit
+/// [`Calculator::with_store`] in einen anderen [`HistoryStore`] verlagert werden.
+/// Gelesen wird er mit [`Calculator::scan_history`] oder seitenweise mit
+/// [`Calculator::query_history`]; auch das Serialisieren liest ihn Eintrag für
+/// Eintrag aus dem Speicher.
+#[derive(Debug, Deserialize)]
+#[serde(try_from = "CalculatorData")]
 pub struct Calculator {
+    history: Box<dyn HistoryStore>,
+    variables: BTreeMap<String, f64>,
+    next_id: u64,
+    statistics: Statistics,
+    observers: Observers,
+    operations: OperationRegistry,
+    definitions: Vec<OperationDefinition>,
+    numeric_policy: NumericPolicy,
+}
+
+/// Serialisierte Form des Calculators; die Statistiken werden beim Laden neu berechnet
+#[derive(Deserialize)]
+struct CalculatorData {
     history: Vec<Operation>,
+    #[serde(default, with = "lossless::map")]
+    variables: BTreeMap<String, f64>,
+    #[serde(default)]
+    operations: Vec<OperationDefinition>,
+}
+
+#[derive(Serialize)]
+struct CalculatorDataRef<'a> {
+    history: HistorySeq<'a>,
+    #[serde(with = "lossless::map")]
+    variables: &'a BTreeMap<String, f64>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    operations: &'a [OperationDefinition],
+}
+
+/// Schreibt den Verlauf Eintrag für Eintrag aus dem Speicher, ohne ihn zu sammeln
+struct HistorySeq<'a>(&'a dyn HistoryStore);
+
+impl Serialize for HistorySeq<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        let mut failed = None;
+        self.0
+            .scan(&mut |op| {
+                if failed.is_none() {
+                    failed = seq.serialize_element(op).err();
+                }
+            })
+            .map_err(serde::ser::Error::custom)?;
+        match failed {
+            Some(error) => Err(error),
+            None => seq.end(),
+        }
+    }
+}
+
+impl Serialize for Calculator {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        CalculatorDataRef {
+            history: HistorySeq(self.history.as_ref()),
+            variables: &self.variables,
+            operations: &self.definitions,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl TryFrom<CalculatorData> for Calculator {
+    type Error = ProjectError;
+
+    fn try_from(mut data: CalculatorData) -> Result<Self, Self::Error> {
+        // Verläufe ohne IDs (ältere Dateien) werden fortlaufend nummeriert
+        if data.history.iter().any(|op| op.id == 0) {
+            for (index, op) in data.history.iter_mut().enumerate() {
+                op.id = index as u64 + 1;
+            }
+        }
+
+        let mut calculator = Self {
+            next_id: data.history.last().map_or(1, |op| op.id + 1),
+            statistics: Statistics::from_history(&data.history),
+            history: Box::new(MemoryHistoryStore::from(data.history)),
+            variables: data.variables,
+            observers: Observers::default(),
+            operations: OperationRegistry::new(),
+            definitions: Vec::new(),
+            numeric_policy: NumericPolicy::default(),
+        };
+        // In der gespeicherten Reihenfolge, da Definitionen aufeinander aufbauen können
+        for definition in data.operations {
+            calculator.define_operation(definition)?;
+        }
+        Ok(calculator)
+    }
 }
 
 /// Repräsentiert eine mathematische Operation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Operation {
+    /// Fortlaufende, eindeutige Nummer innerhalb eines Calculators (ab 1)
+    #[serde(default)]
+    pub id: u64,
     pub operation_type: OperationType,
+    #[serde(with = "lossless::vec")]
     pub operands: Vec<f64>,
+    /// NaN und ±Unendlich werden als `"NaN"`, `"inf"` und `"-inf"` gespeichert
+    #[serde(with = "lossless")]
     pub result: f64,
     pub timestamp: u64,
 }
 
 /// Arten von mathematischen Operationen
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Grundrechenarten werden als `"Add"` usw. serialisiert, registrierte
+/// Operationen (siehe [`OperationRegistry`]) unter ihrem Namen.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum OperationType {
     Add,
     Subtract,
     Multiply,
     Divide,
+    /// Registrierte Operation mit ihrem Namen
+    Custom(String),
+}
+
+impl OperationType {
+    /// Name der Operation im [`OperationRegistry`], z. B. `"add"`
+    pub fn name(&self) -> &str {
+        match self {
+            OperationType::Add => "add",
+            OperationType::Subtract => "subtract",
+            OperationType::Multiply => "multiply",
+            OperationType::Divide => "divide",
+            OperationType::Custom(name) => name,
+        }
+    }
+
+    /// Bezeichnung wie bei `Display`, z. B. `Subtraktion`; eigene Operationen behalten ihren Namen
+    pub fn label(&self) -> Message {
+        match self {
+            OperationType::Add => Message::new("operation_type.add", []),
+            OperationType::Subtract => Message::new("operation_type.subtract", []),
+            OperationType::Multiply => Message::new("operation_type.multiply", []),
+            OperationType::Divide => Message::new("operation_type.divide", []),
+            OperationType::Custom(name) => Message::text(name),
+        }
+    }
+
+    /// Bezeichnung in der gewünschten Sprache, siehe [`OperationType::label`]
+    pub fn localized(&self, locale: Locale) -> String {
+        self.label().render(locale)
+    }
+
+    /// Bestimmt die Art aus einem Namen; Grundrechenarten auch in der Schreibweise `"Add"`
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "add" | "Add" => OperationType::Add,
+            "subtract" | "Subtract" => OperationType::Subtract,
+            "multiply" | "Multiply" => OperationType::Multiply,
+            "divide" | "Divide" => OperationType::Divide,
+            other => OperationType::Custom(other.to_string()),
+        }
+    }
+}
+
+impl Serialize for OperationType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let name = match self {
+            OperationType::Add => "Add",
+            OperationType::Subtract => "Subtract",
+            OperationType::Multiply => "Multiply",
+            OperationType::Divide => "Divide",
+            OperationType::Custom(name) => name,
+        };
+        serializer.serialize_str(name)
+    }
+}
+
+impl<'de> Deserialize<'de> for OperationType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self::from_name(&String::deserialize(deserializer)?))
+    }
 }
 
 impl fmt::Display for OperationType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            OperationType::Add => write!(f, "Addition"),
-            OperationType::Subtract => write!(f, "Subtraktion"),
-            OperationType::Multiply => write!(f, "Multiplikation"),
-            OperationType::Divide => write!(f, "Division"),
+        self.label().fmt(f)
+    }
+}
+
+/// Als Bezeichnung, die nur bei Grundrechenarten übersetzt wird
+impl From<&OperationType> for Argument {
+    fn from(operation_type: &OperationType) -> Self {
+        match operation_type {
+            OperationType::Custom(name) => Argument::Value(name.clone()),
+            builtin => Argument::Message(builtin.label()),
         }
     }
 }
@@ -66,10 +251,131 @@ impl Calculator {
     /// ```
     pub fn new() -> Self {
         Self {
-            history: Vec::new(),
+            history: Box::new(MemoryHistoryStore::new()),
+            variables: BTreeMap::new(),
+            next_id: 1,
+            statistics: Statistics::new(),
+            observers: Observers::default(),
+            operations: OperationRegistry::new(),
+            definitions: Vec::new(),
+            numeric_policy: NumericPolicy::default(),
         }
     }
 
+    /// Erstellt einen Calculator, dessen Verlauf in `store` liegt
+    ///
+    /// Ein bereits gefüllter Speicher wird einmal durchlaufen, um Statistiken und
+    /// die nächste Operations-ID zu bestimmen.
+    ///
+    /// # Beispiel
+    ///
+    /// ```rust
+    /// use rust_project_complete::modules::history::FileHistoryStore;
+    /// use rust_project_complete::Calculator;
+    ///
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let path = dir.path().join("history.jsonl");
+    /// let store = FileHistoryStore::open(path.to_str().unwrap()).unwrap();
+    ///
+    /// let mut calc = Calculator::with_store(Box::new(store)).unwrap();
+    /// calc.add(1.0, 2.0).unwrap();
+    /// assert_eq!(calc.history_count(), 1);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn der Speicher nicht gelesen werden kann.
+    pub fn with_store(store: Box<dyn HistoryStore>) -> Result<Self, ProjectError> {
+        let mut statistics = Statistics::new();
+        let mut last_id = 0;
+        store.scan(&mut |op| {
+            statistics.record(op);
+            last_id = last_id.max(op.id);
+        })?;
+
+        Ok(Self {
+            history: store,
+            variables: BTreeMap::new(),
+            next_id: last_id + 1,
+            statistics,
+            observers: Observers::default(),
+            operations: OperationRegistry::new(),
+            definitions: Vec::new(),
+            numeric_policy: NumericPolicy::default(),
+        })
+    }
+
+    /// Erstellt eine unabhängige Kopie mit Verlauf im Arbeitsspeicher
+    ///
+    /// Variablen und registrierte Operationen werden übernommen, Beobachter nicht.
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn der Verlauf nicht gelesen werden kann.
+    pub fn snapshot(&self) -> Result<Self, ProjectError> {
+        let mut operations = Vec::with_capacity(self.history.len());
+        self.history.scan(&mut |op| operations.push(op.clone()))?;
+        Ok(Self {
+            history: Box::new(MemoryHistoryStore::from(operations)),
+            variables: self.variables.clone(),
+            next_id: self.next_id,
+            statistics: self.statistics.clone(),
+            observers: Observers::default(),
+            operations: self.operations.clone(),
+            definitions: self.definitions.clone(),
+            numeric_policy: self.numeric_policy,
+        })
+    }
+
+    /// Lädt einen gespeicherten Calculator aus einer JSON-Datei
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn die Datei nicht gelesen oder geparst werden kann.
+    pub fn load_from_file(file_path: &str) -> Result<Self, ProjectError> {
+        let content = utils::read_file_content(file_path)?;
+        utils::parse_json(&content, file_path)
+    }
+
+    /// Speichert den Calculator als JSON-Datei
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn die Datei nicht geschrieben werden kann.
+    pub fn save_to_file(&self, file_path: &str) -> Result<(), ProjectError> {
+        let content = serde_json::to_string_pretty(self)?;
+        utils::write_file_content(file_path, &content)
+    }
+
+    /// Registriert einen Beobachter für Operationen und Verlaufsänderungen
+    ///
+    /// # Beispiel
+    ///
+    /// ```rust
+    /// use rust_project_complete::Calculator;
+    /// use rust_project_complete::modules::observer::OperandRangePolicy;
+    /// use std::sync::Arc;
+    ///
+    /// let mut calc = Calculator::new();
+    /// let handle = calc.add_observer(Arc::new(OperandRangePolicy::new(0.0, 10.0)));
+    /// assert!(calc.add(20.0, 1.0).is_err());
+    /// assert!(calc.remove_observer(handle));
+    /// assert!(calc.add(20.0, 1.0).is_ok());
+    /// ```
+    pub fn add_observer(&mut self, observer: Arc<dyn CalculatorObserver>) -> ObserverHandle {
+        self.observers.add(observer)
+    }
+
+    /// Entfernt einen Beobachter; gibt `false` zurück, wenn das Handle unbekannt ist
+    pub fn remove_observer(&mut self, handle: ObserverHandle) -> bool {
+        self.observers.remove(handle)
+    }
+
+    /// Gibt die Anzahl der registrierten Beobachter zurück
+    pub fn observer_count(&self) -> usize {
+        self.observers.len()
+    }
+
     /// Addiert zwei Zahlen
     /// 
     /// # Beispiel
@@ -78,58 +384,163 @@ impl Calculator {
     /// use rust_project_complete::Calculator;
     /// 
     /// let mut calc = Calculator::new();
-    /// let result = calc.add(2.0, 3.0);
+    /// let result = calc.add(2.0, 3.0).unwrap();
     /// assert_eq!(result, 5.0);
     /// ```
-    pub fn add(&mut self, a: f64, b: f64) -> f64 {
-        let result = a + b;
-        self.add_to_history(OperationType::Add, vec![a, b], result);
-        result
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn ein Beobachter die Operation ablehnt.
+    pub fn add(&mut self, a: f64, b: f64) -> Result<f64, ProjectError> {
+        self.execute(&BuiltIn::ADD, &[a, b])
     }
 
     /// Subtrahiert zwei Zahlen
-    pub fn subtract(&mut self, a: f64, b: f64) -> f64 {
-        let result = a - b;
-        self.add_to_history(OperationType::Subtract, vec![a, b], result);
-        result
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn ein Beobachter die Operation ablehnt.
+    pub fn subtract(&mut self, a: f64, b: f64) -> Result<f64, ProjectError> {
+        self.execute(&BuiltIn::SUBTRACT, &[a, b])
     }
 
     /// Multipliziert zwei Zahlen
-    pub fn multiply(&mut self, a: f64, b: f64) -> f64 {
-        let result = a * b;
-        self.add_to_history(OperationType::Multiply, vec![a, b], result);
-        result
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn ein Beobachter die Operation ablehnt.
+    pub fn multiply(&mut self, a: f64, b: f64) -> Result<f64, ProjectError> {
+        self.execute(&BuiltIn::MULTIPLY, &[a, b])
     }
 
     /// Dividiert zwei Zahlen
     /// 
     /// # Errors
     /// 
-    /// Gibt einen Fehler zurück, wenn durch Null geteilt wird.
+    /// Gibt einen Fehler zurück, wenn durch Null geteilt wird oder ein
+    /// Beobachter die Operation ablehnt.
     pub fn divide(&mut self, a: f64, b: f64) -> Result<f64, ProjectError> {
-        if b == 0.0 {
-            return Err(ProjectError::DivisionByZero);
-        }
-        let result = a / b;
-        self.add_to_history(OperationType::Divide, vec![a, b], result);
-        Ok(result)
+        self.execute(&BuiltIn::DIVIDE, &[a, b])
+    }
+
+    /// Registriert eine eigene Operation
+    ///
+    /// Sie kann danach über [`Calculator::apply`] und in Ausdrücken verwendet
+    /// werden und erscheint im Verlauf und in den Statistiken.
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`OperationRegistry::register`].
+    pub fn register_operation(&mut self, operator: Arc<dyn Operator>) -> Result<(), ProjectError> {
+        self.operations.register(operator)
+    }
+
+    /// Definiert eine Operation als Ausdruck über ihre Parameter
+    ///
+    /// Anders als mit [`Calculator::register_operation`] wird die Definition
+    /// mitgespeichert und beim Laden wieder registriert.
+    ///
+    /// # Beispiel
+    ///
+    /// ```rust
+    /// use rust_project_complete::Calculator;
+    ///
+    /// let mut calc = Calculator::new();
+    /// calc.define_operation("mean(a, b) = (a + b) / 2".parse().unwrap()).unwrap();
+    /// assert_eq!(calc.apply("mean", &[1.0, 4.0]).unwrap(), 2.5);
+    ///
+    /// let json = serde_json::to_string(&calc).unwrap();
+    /// let mut loaded: Calculator = serde_json::from_str(&json).unwrap();
+    /// assert_eq!(loaded.evaluate("mean(2, 4)").unwrap(), 3.0);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Gibt [`ProjectError::InvalidInput`] oder [`ProjectError::ParseError`]
+    /// zurück, wenn die Definition ungültig ist, der Rumpf Unbekanntes
+    /// verwendet oder der Name bereits vergeben ist.
+    pub fn define_operation(&mut self, definition: OperationDefinition) -> Result<(), ProjectError> {
+        let operator = ExpressionOperator::new(&definition, &self.operations)?;
+        self.operations.register(Arc::new(operator))?;
+        self.definitions.push(definition);
+        Ok(())
+    }
+
+    /// Gibt die mit [`Calculator::define_operation`] definierten Operationen zurück
+    pub fn definitions(&self) -> &[OperationDefinition] {
+        &self.definitions
+    }
+
+    /// Gibt die verfügbaren Operationen zurück
+    pub fn operations(&self) -> &OperationRegistry {
+        &self.operations
+    }
+
+    /// Ersetzt die verfügbaren Operationen, z. B. durch die eines anderen Calculators
+    pub fn set_operations(&mut self, operations: OperationRegistry) {
+        self.operations = operations;
+    }
+
+    /// Gibt die Richtlinie für NaN und ±Unendlich zurück
+    pub fn numeric_policy(&self) -> NumericPolicy {
+        self.numeric_policy
+    }
+
+    /// Legt fest, wie NaN und ±Unendlich behandelt werden, siehe [`NumericPolicy`]
+    pub fn set_numeric_policy(&mut self, policy: NumericPolicy) {
+        self.numeric_policy = policy;
+    }
+
+    /// Führt eine Operation aus dem [`OperationRegistry`] nach Namen aus
+    ///
+    /// # Beispiel
+    ///
+    /// ```rust
+    /// use rust_project_complete::Calculator;
+    ///
+    /// let mut calc = Calculator::new();
+    /// assert_eq!(calc.apply("multiply", &[6.0, 7.0]).unwrap(), 42.0);
+    /// assert!(calc.apply("multiply", &[6.0]).is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn die Operation unbekannt ist, die Anzahl
+    /// der Operanden nicht passt oder die Operation fehlschlägt.
+    pub fn apply(&mut self, name: &str, operands: &[f64]) -> Result<f64, ProjectError> {
+        let operator = self
+            .operations
+            .get(name)
+            .cloned()
+            .ok_or_else(|| {
+                ProjectError::invalid_input(Message::new("operation.unknown", [name.into()]))
+                    .with_operation(name, operands)
+            })?;
+        self.execute(operator.as_ref(), operands)
     }
 
     /// Berechnet die Fakultät einer Zahl
-    /// 
+    ///
+    /// Wird wie die übrigen Operationen unter dem Namen `factorial` im Verlauf
+    /// festgehalten und den Beobachtern gemeldet.
+    ///
+    /// # Beispiel
+    ///
+    /// ```rust
+    /// use rust_project_complete::Calculator;
+    ///
+    /// let mut calc = Calculator::new();
+    /// assert_eq!(calc.factorial(5).unwrap(), 120);
+    /// assert_eq!(calc.history_count(), 1);
+    /// ```
+    ///
     /// # Errors
-    /// 
-    /// Gibt einen Fehler zurück, wenn die Zahl negativ ist.
-    pub fn factorial(&self, n: u64) -> Result<u64, ProjectError> {
-        if n > 20 {
-            return Err(ProjectError::OverflowError("Factorial too large".to_string()));
-        }
-        
-        let mut result = 1;
-        for i in 1..=n {
-            result *= i;
-        }
-        Ok(result)
+    ///
+    /// Gibt einen Fehler zurück, wenn das Ergebnis nicht in `u64` passt
+    /// (`n > 20`) oder ein Beobachter die Operation ablehnt.
+    pub fn factorial(&mut self, n: u64) -> Result<u64, ProjectError> {
+        self.execute(&Factorial, &[n as f64])
+            .map(|result| result as u64)
     }
 
     /// Gibt die Anzahl der Operationen im Verlauf zurück
@@ -138,30 +549,234 @@ impl Calculator {
     }
 
     /// Gibt den Verlauf der Operationen zurück
-    pub fn get_history(&self) -> &[Operation] {
-        &self.history
+    ///
+    /// Lädt bei Datei- und SQLite-Speichern den gesamten Verlauf in den
+    /// Arbeitsspeicher.
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn der Verlaufsspeicher nicht gelesen werden kann.
+    #[deprecated(note = "lädt den gesamten Verlauf; `scan_history` oder `query_history` verwenden")]
+    pub fn get_history(&self) -> Result<Cow<'_, [Operation]>, ProjectError> {
+        self.history.operations()
+    }
+
+    /// Übergibt alle Operationen der Reihe nach an `visit`, ohne sie gesammelt zu laden
+    ///
+    /// # Beispiel
+    ///
+    /// ```rust
+    /// use rust_project_complete::Calculator;
+    ///
+    /// let mut calc = Calculator::new();
+    /// calc.add(1.0, 2.0).unwrap();
+    /// calc.multiply(3.0, 4.0).unwrap();
+    ///
+    /// let mut total = 0.0;
+    /// calc.scan_history(&mut |op| total += op.result).unwrap();
+    /// assert_eq!(total, 15.0);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn der Verlaufsspeicher nicht gelesen werden kann.
+    pub fn scan_history(&self, visit: &mut dyn FnMut(&Operation)) -> Result<(), ProjectError> {
+        self.history.scan(visit)
+    }
+
+    /// Gibt die Operationen zurück, die `query` erfüllen
+    ///
+    /// Die Filter werden vom [`HistoryStore`] ausgewertet, sodass z. B. SQLite
+    /// nur die passenden Einträge lädt. Mit [`HistoryQuery::offset`] und
+    /// [`HistoryQuery::limit`] wird der Verlauf seitenweise gelesen.
+    ///
+    /// # Beispiel
+    ///
+    /// ```rust
+    /// use rust_project_complete::modules::history::HistoryQuery;
+    /// use rust_project_complete::Calculator;
+    ///
+    /// let mut calc = Calculator::new();
+    /// for i in 0..10 {
+    ///     calc.add(i as f64, 1.0).unwrap();
+    /// }
+    /// let page = calc.query_history(&HistoryQuery::new().offset(4).limit(3)).unwrap();
+    /// let ids: Vec<u64> = page.iter().map(|op| op.id).collect();
+    /// assert_eq!(ids, vec![5, 6, 7]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn der Verlaufsspeicher nicht gelesen werden kann.
+    pub fn query_history(&self, query: &HistoryQuery) -> Result<Vec<Operation>, ProjectError> {
+        self.history.query(query)
+    }
+
+    /// Gibt die laufend aus dem Verlauf berechneten Statistiken zurück
+    ///
+    /// # Beispiel
+    ///
+    /// ```rust
+    /// use rust_project_complete::{Calculator, OperationType};
+    ///
+    /// let mut calc = Calculator::new();
+    /// calc.add(2.0, 3.0).unwrap();
+    /// calc.add(4.0, 4.0).unwrap();
+    /// let stats = calc.statistics();
+    /// assert_eq!(stats.get_operation_count(&OperationType::Add), 2);
+    /// assert_eq!(stats.max_result, 8.0);
+    /// ```
+    pub fn statistics(&self) -> &Statistics {
+        &self.statistics
     }
 
     /// Löscht den Verlauf
-    pub fn clear_history(&mut self) {
-        self.history.clear();
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn der Verlaufsspeicher nicht geleert werden kann.
+    pub fn clear_history(&mut self) -> Result<(), ProjectError> {
+        self.history.clear()?;
+        self.statistics.clear();
+        self.observers.history_changed(&HistoryEvent::Cleared);
+        Ok(())
+    }
+
+    /// Setzt eine Variable und gibt den vorherigen Wert zurück
+    ///
+    /// # Beispiel
+    ///
+    /// ```rust
+    /// use rust_project_complete::Calculator;
+    ///
+    /// let mut calc = Calculator::new();
+    /// calc.set_variable("budget", 1500.0);
+    /// assert_eq!(calc.get_variable("budget"), Some(1500.0));
+    /// ```
+    pub fn set_variable(&mut self, name: &str, value: f64) -> Option<f64> {
+        self.variables.insert(name.to_string(), value)
+    }
+
+    /// Gibt den Wert einer Variable zurück
+    pub fn get_variable(&self, name: &str) -> Option<f64> {
+        self.variables.get(name).copied()
+    }
+
+    /// Entfernt eine Variable und gibt ihren Wert zurück
+    pub fn remove_variable(&mut self, name: &str) -> Option<f64> {
+        self.variables.remove(name)
+    }
+
+    /// Gibt alle Variablen sortiert nach Namen zurück
+    pub fn variables(&self) -> &BTreeMap<String, f64> {
+        &self.variables
+    }
+
+    /// Wertet einen arithmetischen Ausdruck aus
+    ///
+    /// Jede enthaltene Grundrechenart wird im Verlauf festgehalten.
+    ///
+    /// # Beispiel
+    ///
+    /// ```rust
+    /// use rust_project_complete::Calculator;
+    ///
+    /// let mut calc = Calculator::new();
+    /// calc.set_variable("miete", 900.0);
+    /// assert_eq!(calc.evaluate("miete * 12 + 100").unwrap(), 10900.0);
+    /// assert_eq!(calc.history_count(), 2);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn der Ausdruck ungültig ist oder eine
+    /// Operation fehlschlägt.
+    pub fn evaluate(&mut self, expression: &str) -> Result<f64, ProjectError> {
+        let result = Expression::parse_with(expression, &self.operations)?.evaluate(self)?;
+        // Ausdrücke ohne Operation, z. B. `1e400`, prüft `execute` nicht
+        self.numeric_policy
+            .check_result("evaluate", &[], result)
+            .map_err(|e| e.with_operation("evaluate", &[]))?;
+        Ok(result)
+    }
+
+    /// Führt eine Operation aus und benachrichtigt die Beobachter
+    fn execute(&mut self, operator: &dyn Operator, operands: &[f64]) -> Result<f64, ProjectError> {
+        let op_type = operator.operation_type();
+        let span = tracing::debug_span!(
+            "operation",
+            correlation_id = %logging::correlation_id(),
+            operation = op_type.name(),
+            operands = ?operands,
+            operation_id = field::Empty,
+            result = field::Empty,
+            error = field::Empty,
+        );
+        let _entered = span.enter();
+        let policy = self.numeric_policy;
+        let outcome = self
+            .observers
+            .before_operation(&op_type, operands)
+            .and_then(|_| {
+                if operands.len() != operator.arity() {
+                    return Err(ProjectError::invalid_input(Message::new(
+                        "operation.arity",
+                        [operator.name().into(), operator.arity().into(), operands.len().into()],
+                    )));
+                }
+                operator.validate(operands)?;
+                policy.check_operands(operator.name(), operands)?;
+                let result = operator.evaluate(operands)?;
+                policy.check_result(operator.name(), operands, result)?;
+                Ok(result)
+            })
+            .and_then(|result| {
+                self.add_to_history(op_type.clone(), operands.to_vec(), result)?;
+                Ok(result)
+            });
+
+        match outcome {
+            Ok(result) => {
+                span.record("operation_id", self.next_id - 1);
+                span.record("result", result);
+                Ok(result)
+            }
+            Err(error) => {
+                let error = error.with_operation(operator.name(), operands);
+                span.record("error", field::display(&error));
+                self.observers.operation_failed(&op_type, operands, &error);
+                Err(error)
+            }
+        }
     }
 
     /// Fügt eine Operation zum Verlauf hinzu
-    fn add_to_history(&mut self, op_type: OperationType, operands: Vec<f64>, result: f64) {
+    fn add_to_history(
+        &mut self,
+        op_type: OperationType,
+        operands: Vec<f64>,
+        result: f64,
+    ) -> Result<(), ProjectError> {
         let timestamp = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_secs();
         
         let operation = Operation {
+            id: self.next_id,
             operation_type: op_type,
             operands,
             result,
             timestamp,
         };
         
-        self.history.push(operation);
+        self.history.append(&operation)?;
+        self.next_id += 1;
+        self.statistics.record(&operation);
+
+        self.observers.after_operation(&operation);
+        self.observers.history_changed(&HistoryEvent::Appended(&operation));
+        Ok(())
     }
 }
 
@@ -185,7 +800,7 @@ mod tests {
     #[test]
     fn test_add() {
         let mut calc = Calculator::new();
-        let result = calc.add(2.0, 3.0);
+        let result = calc.add(2.0, 3.0).unwrap();
         assert_eq!(result, 5.0);
         assert_eq!(calc.history_count(), 1);
     }
@@ -193,7 +808,7 @@ mod tests {
     #[test]
     fn test_subtract() {
         let mut calc = Calculator::new();
-        let result = calc.subtract(5.0, 3.0);
+        let result = calc.subtract(5.0, 3.0).unwrap();
         assert_eq!(result, 2.0);
         assert_eq!(calc.history_count(), 1);
     }
@@ -201,7 +816,7 @@ mod tests {
     #[test]
     fn test_multiply() {
         let mut calc = Calculator::new();
-        let result = calc.multiply(4.0, 3.0);
+        let result = calc.multiply(4.0, 3.0).unwrap();
         assert_eq!(result, 12.0);
         assert_eq!(calc.history_count(), 1);
     }
@@ -224,7 +839,7 @@ mod tests {
 
     #[test]
     fn test_factorial() {
-        let calc = Calculator::new();
+        let mut calc = Calculator::new();
         assert_eq!(calc.factorial(0).unwrap(), 1);
         assert_eq!(calc.factorial(1).unwrap(), 1);
         assert_eq!(calc.factorial(5).unwrap(), 120);
@@ -232,22 +847,258 @@ mod tests {
 
     #[test]
     fn test_factorial_overflow() {
-        let calc = Calculator::new();
+        let mut calc = Calculator::new();
         let result = calc.factorial(25);
-        assert!(result.is_err());
+        assert_eq!(
+            result,
+            Err(ProjectError::overflow("Factorial too large").with_operation("factorial", &[25.0]))
+        );
+        assert_eq!(calc.history_count(), 0);
     }
 
     #[test]
     fn test_clear_history() {
         let mut calc = Calculator::new();
-        calc.add(1.0, 2.0);
-        calc.add(3.0, 4.0);
+        calc.add(1.0, 2.0).unwrap();
+        calc.add(3.0, 4.0).unwrap();
         assert_eq!(calc.history_count(), 2);
         
-        calc.clear_history();
+        calc.clear_history().unwrap();
+        assert_eq!(calc.history_count(), 0);
+        assert!(calc.statistics().is_empty());
+    }
+
+    #[test]
+    fn test_statistics_follow_history() {
+        let mut calc = Calculator::new();
+        calc.add(1.0, 2.0).unwrap();
+        calc.subtract(10.0, 4.0).unwrap();
+        calc.add(5.0, 5.0).unwrap();
+        assert!(calc.divide(1.0, 0.0).is_err());
+
+        let stats = calc.statistics();
+        assert_eq!(stats.total_operations, 3);
+        assert_eq!(stats.get_operation_count(&OperationType::Add), 2);
+        assert_eq!(stats.get_operation_count(&OperationType::Subtract), 1);
+        assert_eq!(stats.get_operation_count(&OperationType::Divide), 0);
+
+        let add = stats.get_operation_statistics(&OperationType::Add).unwrap();
+        assert_eq!(add.min_result, 3.0);
+        assert_eq!(add.max_result, 10.0);
+        assert_eq!(add.average_result, 6.5);
+    }
+
+    #[test]
+    fn test_statistics_rebuilt_after_deserialization() {
+        let mut calc = Calculator::new();
+        calc.multiply(2.0, 3.0).unwrap();
+        calc.divide(9.0, 3.0).unwrap();
+
+        let json = serde_json::to_string(&calc).unwrap();
+        assert!(!json.contains("statistics"));
+
+        let restored: Calculator = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.statistics().total_operations, 2);
+        assert_eq!(restored.statistics().get_operation_count(&OperationType::Divide), 1);
+    }
+
+    #[test]
+    fn test_operation_ids_are_sequential() {
+        let mut calc = Calculator::new();
+        calc.add(1.0, 1.0).unwrap();
+        calc.add(2.0, 2.0).unwrap();
+        calc.clear_history().unwrap();
+        calc.add(3.0, 3.0).unwrap();
+
+        assert_eq!(calc.query_history(&HistoryQuery::new()).unwrap()[0].id, 3);
+
+        let mut restored: Calculator =
+            serde_json::from_str(&serde_json::to_string(&calc).unwrap()).unwrap();
+        restored.add(4.0, 4.0).unwrap();
+        let ids: Vec<u64> = restored.query_history(&HistoryQuery::new()).unwrap().iter().map(|op| op.id).collect();
+        assert_eq!(ids, vec![3, 4]);
+    }
+
+    #[test]
+    fn test_history_without_ids_is_renumbered() {
+        let json = r#"{"history":[
+            {"operation_type":"Add","operands":[1.0,2.0],"result":3.0,"timestamp":0},
+            {"operation_type":"Add","operands":[2.0,2.0],"result":4.0,"timestamp":0}
+        ]}"#;
+        let mut calc: Calculator = serde_json::from_str(json).unwrap();
+        calc.add(1.0, 1.0).unwrap();
+
+        let ids: Vec<u64> = calc.query_history(&HistoryQuery::new()).unwrap().iter().map(|op| op.id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_custom_operation_round_trip() {
+        use modules::operations::FnOperator;
+
+        let mut calc = Calculator::new();
+        calc.register_operation(Arc::new(FnOperator::new("hypot", 2, |x| {
+            Ok(x[0].hypot(x[1]))
+        })))
+        .unwrap();
+        assert_eq!(calc.apply("hypot", &[3.0, 4.0]).unwrap(), 5.0);
+        calc.apply("add", &[1.0, 1.0]).unwrap();
+
+        let hypot = OperationType::Custom("hypot".to_string());
+        assert_eq!(calc.statistics().get_operation_count(&hypot), 1);
+
+        let json = serde_json::to_string(&calc).unwrap();
+        assert!(json.contains(r#""operation_type":"hypot""#));
+        assert!(json.contains(r#""operation_type":"Add""#));
+
+        // Der Verlauf bleibt lesbar, auch ohne dass die Operation registriert ist
+        let restored: Calculator = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.query_history(&HistoryQuery::new()).unwrap()[0].operation_type, hypot);
+        assert_eq!(restored.statistics().get_operation_count(&hypot), 1);
+        assert_eq!(restored.statistics().get_operation_count(&OperationType::Add), 1);
+    }
+
+    #[test]
+    fn test_numeric_policy() {
+        let mut calc = Calculator::new();
+        assert!(calc.add(f64::NAN, 1.0).unwrap().is_nan());
+        assert_eq!(calc.numeric_policy(), NumericPolicy::Permissive);
+
+        calc.set_numeric_policy(NumericPolicy::Strict);
+        let error = calc.add(f64::NAN, 1.0).unwrap_err();
+        assert!(matches!(error, ProjectError::InvalidInput { .. }));
+        assert_eq!(error.operation().map(|op| op.name.as_str()), Some("add"));
+        let error = calc.multiply(1e200, 1e200).unwrap_err();
+        assert_eq!(
+            error,
+            ProjectError::overflow(Message::new("numeric.result_not_finite", [f64::INFINITY.into()]))
+                .with_operation("multiply", &[1e200, 1e200])
+        );
+        assert!(matches!(calc.evaluate("2 * 1e400"), Err(ProjectError::InvalidInput { .. })));
+        assert!(matches!(calc.evaluate("1e400"), Err(ProjectError::OverflowError { .. })));
+        assert_eq!(calc.add(1.0, 2.0), Ok(3.0));
+        // Abgelehnte Operationen erscheinen weder im Verlauf noch in den Statistiken
+        assert_eq!(calc.history_count(), 2);
+        assert_eq!(calc.snapshot().unwrap().numeric_policy(), NumericPolicy::Strict);
+
+        calc.set_numeric_policy(NumericPolicy::Warn);
+        assert_eq!(calc.multiply(1e200, 1e200), Ok(f64::INFINITY));
+        assert_eq!(calc.history_count(), 3);
+    }
+
+    #[test]
+    fn test_apply_errors() {
+        let mut calc = Calculator::new();
+        assert!(matches!(calc.apply("wurzel", &[4.0]), Err(ProjectError::InvalidInput { .. })));
+        assert!(matches!(calc.apply("add", &[1.0]), Err(ProjectError::InvalidInput { .. })));
+        let error = calc.apply("wurzel", &[4.0]).unwrap_err();
+        assert_eq!(error.operation().map(|op| op.name.as_str()), Some("wurzel"));
+        assert_eq!(calc.apply("Divide", &[1.0, 0.0]), Err(ProjectError::division_by_zero().with_operation("divide", &[1.0, 0.0])));
         assert_eq!(calc.history_count(), 0);
     }
 
+    #[test]
+    fn test_variables() {
+        let mut calc = Calculator::new();
+        assert_eq!(calc.set_variable("x", 1.0), None);
+        assert_eq!(calc.set_variable("x", 2.0), Some(1.0));
+        calc.set_variable("a", 3.0);
+
+        let names: Vec<&String> = calc.variables().keys().collect();
+        assert_eq!(names, vec!["a", "x"]);
+
+        let restored: Calculator =
+            serde_json::from_str(&serde_json::to_string(&calc).unwrap()).unwrap();
+        assert_eq!(restored.get_variable("x"), Some(2.0));
+
+        assert_eq!(calc.remove_variable("x"), Some(2.0));
+        assert_eq!(calc.get_variable("x"), None);
+    }
+
+    #[test]
+    fn test_save_and_load_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("calc.json");
+        let path = path.to_str().unwrap();
+
+        let mut calc = Calculator::new();
+        calc.add(1.0, 1.0).unwrap();
+        calc.save_to_file(path).unwrap();
+
+        let loaded = Calculator::load_from_file(path).unwrap();
+        assert_eq!(loaded.history_count(), 1);
+        assert_eq!(loaded.statistics().get_operation_count(&OperationType::Add), 1);
+    }
+
+    #[test]
+    fn test_serialize_reads_history_from_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let store = modules::history::FileHistoryStore::open(path.to_str().unwrap()).unwrap();
+        let mut calc = Calculator::with_store(Box::new(store)).unwrap();
+        calc.add(1.0, 2.0).unwrap();
+        calc.divide(9.0, 3.0).unwrap();
+
+        let json = serde_json::to_value(&calc).unwrap();
+        assert_eq!(json["history"].as_array().unwrap().len(), 2);
+        let copy: Calculator = serde_json::from_value(json).unwrap();
+        assert_eq!(
+            copy.query_history(&HistoryQuery::new()).unwrap(),
+            calc.query_history(&HistoryQuery::new()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_non_finite_values_survive_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("calc.json");
+        let path = path.to_str().unwrap();
+
+        let mut calc = Calculator::new();
+        calc.multiply(1e200, 1e200).unwrap();
+        calc.subtract(f64::INFINITY, f64::INFINITY).unwrap();
+        calc.set_variable("grenze", f64::NEG_INFINITY);
+        calc.save_to_file(path).unwrap();
+
+        let loaded = Calculator::load_from_file(path).unwrap();
+        let history = loaded.query_history(&HistoryQuery::new()).unwrap();
+        assert_eq!(history[0].result, f64::INFINITY);
+        assert_eq!(history[1].operands, vec![f64::INFINITY, f64::INFINITY]);
+        assert!(history[1].result.is_nan());
+        assert_eq!(loaded.get_variable("grenze"), Some(f64::NEG_INFINITY));
+        assert_eq!(loaded.statistics().max_result, f64::INFINITY);
+    }
+
+    #[test]
+    fn test_defined_operations_survive_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("calc.json");
+        let path = path.to_str().unwrap();
+
+        let mut calc = Calculator::new();
+        calc.define_operation("mean(a, b) = (a + b) / 2".parse().unwrap()).unwrap();
+        calc.define_operation("spread(a, b) = mean(a, b) - a".parse().unwrap()).unwrap();
+        assert!(calc.define_operation("mean(x) = x".parse().unwrap()).is_err());
+        assert_eq!(calc.definitions().len(), 2);
+        calc.apply("spread", &[2.0, 6.0]).unwrap();
+        calc.save_to_file(path).unwrap();
+
+        let mut loaded = Calculator::load_from_file(path).unwrap();
+        assert_eq!(loaded.definitions(), calc.definitions());
+        assert_eq!(loaded.apply("spread", &[2.0, 6.0]).unwrap(), 2.0);
+        assert_eq!(loaded.evaluate("mean(1, 2)").unwrap(), 1.5);
+        assert_eq!(
+            loaded
+                .statistics()
+                .get_operation_count(&OperationType::Custom("spread".to_string())),
+            2
+        );
+
+        // Ohne Definitionen bleibt das Dateiformat unverändert
+        let json = serde_json::to_value(Calculator::new()).unwrap();
+        assert!(json.get("operations").is_none());
+    }
+
     #[test]
     fn test_default() {
         let calc = Calculator::default();
@@ -261,8 +1112,8 @@ mod tests {
             let mut calc1 = Calculator::new();
             let mut calc2 = Calculator::new();
             
-            let result1 = calc1.add(a, b);
-            let result2 = calc2.add(b, a);
+            let result1 = calc1.add(a, b).unwrap();
+            let result2 = calc2.add(b, a).unwrap();
             
             assert!((result1 - result2).abs() < f64::EPSILON);
         }
@@ -272,8 +1123,8 @@ mod tests {
             let mut calc1 = Calculator::new();
             let mut calc2 = Calculator::new();
             
-            let result1 = calc1.multiply(a, b);
-            let result2 = calc2.multiply(b, a);
+            let result1 = calc1.multiply(a, b).unwrap();
+            let result2 = calc2.multiply(b, a).unwrap();
             
             assert!((result1 - result2).abs() < f64::EPSILON);
         }
@@ -1,15 +1,132 @@
 //! Hauptprogramm
 
-use rust_project_complete::{Calculator, OperationType};
-use clap::{Parser, Subcommand};
+use rust_project_complete::modules::audit::{verify_audit_log, AuditHistoryStore};
+use rust_project_complete::modules::batch::{Batch, BatchFormat};
+use rust_project_complete::modules::core::{MathUtils, Statistics};
+#[cfg(unix)]
+use rust_project_complete::modules::daemon::{Daemon, DaemonClient, Persist};
+#[cfg(unix)]
+use rust_project_complete::modules::numeric::lossless::Lossless;
+use rust_project_complete::modules::error::ProjectError;
+#[cfg(unix)]
+use rust_project_complete::modules::error::NOT_RUNNING_EXIT_CODE;
+use rust_project_complete::modules::history::HistoryQuery;
+use rust_project_complete::modules::i18n::{Locale, Message};
+use rust_project_complete::modules::logging::{self, LogFormat};
+use rust_project_complete::modules::numeric::NumericPolicy;
+use rust_project_complete::modules::operations::{OperationDefinition, OperationRegistry};
+use rust_project_complete::modules::output::{self, OutputFormat, Report};
+use rust_project_complete::modules::session::{Session, SessionManager};
+use rust_project_complete::modules::rpc::RpcServer;
+use rust_project_complete::modules::shared::SharedCalculator;
+use rust_project_complete::modules::utils;
+#[cfg(feature = "server")]
+use rust_project_complete::modules::{async_api::AsyncCalculator, server};
+#[cfg(feature = "tui")]
+use rust_project_complete::modules::tui;
+use rust_project_complete::{Calculator, Operation, OperationType};
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand};
+use clap_complete::engine::{ArgValueCandidates, CompletionCandidate};
+use clap_complete::env::Shells;
+use clap_complete::{CompleteEnv, Shell};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, IsTerminal, Write};
 use std::process;
+#[cfg(unix)]
+use std::time::{Duration, Instant};
 
 #[derive(Parser)]
 #[command(name = "rust_project_complete")]
 #[command(about = "Ein vollständiger Rust-Calculator mit Tests")]
 struct Cli {
+    /// JSON-Datei, in der der Verlauf zwischen Aufrufen gespeichert wird
+    #[arg(long, global = true, value_name = "DATEI", conflicts_with = "session")]
+    history: Option<String>,
+
+    /// Audit-Protokoll, in das jede Operation verkettet geschrieben wird
+    #[arg(
+        long,
+        global = true,
+        value_name = "DATEI",
+        conflicts_with_all = ["history", "session"]
+    )]
+    audit_log: Option<String>,
+
+    /// Name der Sitzung, in der gerechnet wird
+    #[arg(long, global = true, value_name = "NAME", add = ArgValueCandidates::new(session_names))]
+    session: Option<String>,
+
+    /// Verzeichnis der gespeicherten Sitzungen
+    #[arg(
+        long,
+        global = true,
+        value_name = "VERZEICHNIS",
+        env = "RUST_PROJECT_COMPLETE_SESSION_DIR"
+    )]
+    session_dir: Option<String>,
+
+    /// Unix-Socket des Daemons
+    #[cfg(unix)]
+    #[arg(
+        long,
+        global = true,
+        value_name = "PFAD",
+        env = "RUST_PROJECT_COMPLETE_SOCKET"
+    )]
+    socket: Option<String>,
+
+    /// Spricht JSON-RPC 2.0 über stdin/stdout, bis die Eingabe endet
+    #[arg(long)]
+    stdio_rpc: bool,
+
+    /// Format der Protokollierung auf stderr (pretty oder json); Filter über RUST_LOG
+    #[arg(
+        long,
+        global = true,
+        value_name = "FORMAT",
+        default_value_t = LogFormat::Pretty,
+        env = "RUST_PROJECT_COMPLETE_LOG_FORMAT"
+    )]
+    log_format: LogFormat,
+
+    /// Format der Ausgabe (text, json, csv oder table); Fehler erscheinen im selben Format auf stderr
+    #[arg(
+        long,
+        global = true,
+        value_name = "FORMAT",
+        default_value_t = OutputFormat::Text,
+        env = "RUST_PROJECT_COMPLETE_OUTPUT_FORMAT"
+    )]
+    output_format: OutputFormat,
+
+    /// Sprache der Ausgabe (de oder en); sonst aus der Sitzung oder LANG
+    #[arg(
+        long,
+        global = true,
+        value_name = "SPRACHE",
+        env = "RUST_PROJECT_COMPLETE_LANG"
+    )]
+    lang: Option<Locale>,
+
+    /// Umgang mit NaN und Unendlich (strict, warn oder permissive); sonst aus der Sitzung
+    ///
+    /// `strict` lehnt nicht endliche Operanden und Ergebnisse ab, `warn`
+    /// protokolliert sie, `permissive` rechnet ohne Prüfung weiter (Standard).
+    #[arg(
+        long,
+        global = true,
+        value_name = "RICHTLINIE",
+        env = "RUST_PROJECT_COMPLETE_NUMERIC_POLICY"
+    )]
+    numeric_policy: Option<NumericPolicy>,
+
+    /// Tatsächlich verwendete Sprache, siehe `resolve_locale`
+    #[arg(skip)]
+    locale: Locale,
+
     #[command(subcommand)]
-    command: Commands,
+    command: Option<Commands>,
 }
 
 #[derive(Subcommand)]
@@ -47,49 +164,1215 @@ enum Commands {
         /// Zahl für Fakultät
         n: u64,
     },
-    /// Interaktiver Modus
+    /// Führt eine Operation nach Namen aus, z. B. `apply divide 1 4`
+    Apply {
+        /// Name der Operation, siehe `operations`
+        #[arg(add = ArgValueCandidates::new(operation_names))]
+        name: String,
+        /// Operanden
+        #[arg(allow_negative_numbers = true)]
+        operands: Vec<f64>,
+    },
+    /// Definiert eine Operation als Ausdruck, z. B. `define "mean(a, b) = (a + b) / 2"`
+    ///
+    /// Die Definition wird mit dem Verlauf oder der Sitzung gespeichert und
+    /// steht danach für `apply`, `eval` und `operations` zur Verfügung.
+    Define {
+        /// Definition; mehrere Argumente werden mit Leerzeichen verbunden
+        #[arg(required = true, allow_hyphen_values = true)]
+        definition: Vec<String>,
+    },
+    /// Wertet einen Ausdruck aus, z. B. `eval "2 * (3 + 4)"`
+    ///
+    /// Erlaubt sind Zahlen, `+ - * /`, Klammern und Operationen als Funktion,
+    /// z. B. `add(1, 2)`. Fehler zeigen die Stelle im Ausdruck.
+    Eval {
+        /// Ausdruck; mehrere Argumente werden mit Leerzeichen verbunden
+        #[arg(required = true, allow_hyphen_values = true)]
+        expression: Vec<String>,
+    },
+    /// Listet die verfügbaren Operationen auf
+    Operations,
+    /// Größter gemeinsamer Teiler
+    Gcd {
+        /// Erste Zahl
+        a: u64,
+        /// Zweite Zahl
+        b: u64,
+    },
+    /// Kleinstes gemeinsames Vielfaches
+    Lcm {
+        /// Erste Zahl
+        a: u64,
+        /// Zweite Zahl
+        b: u64,
+    },
+    /// Prüft, ob eine Zahl eine Primzahl ist
+    IsPrime {
+        /// Zu prüfende Zahl
+        n: u64,
+    },
+    /// N-te Fibonacci-Zahl
+    Fibonacci {
+        /// Position in der Folge, beginnend bei 0
+        n: u64,
+    },
+    /// Fakultät ohne Größenbeschränkung
+    BigFactorial {
+        /// Zahl für Fakultät
+        n: u64,
+    },
+    /// Durchschnitt einer Liste von Zahlen
+    Average(NumberList),
+    /// Standardabweichung einer Liste von Zahlen
+    Stddev(NumberList),
+    /// Kleinste Zahl einer Liste
+    Min(NumberList),
+    /// Größte Zahl einer Liste
+    Max(NumberList),
+    /// Rechnet Grad Celsius in Grad Fahrenheit um
+    CelsiusToFahrenheit {
+        /// Temperatur in Grad Celsius
+        #[arg(allow_negative_numbers = true)]
+        celsius: f64,
+    },
+    /// Rechnet Grad Fahrenheit in Grad Celsius um
+    FahrenheitToCelsius {
+        /// Temperatur in Grad Fahrenheit
+        #[arg(allow_negative_numbers = true)]
+        fahrenheit: f64,
+    },
+    /// Zeigt den gespeicherten Verlauf
+    History {
+        /// Zeigt nur Operationen dieser Art, z. B. `divide`
+        #[arg(long, value_name = "NAME", add = ArgValueCandidates::new(operation_names))]
+        operation: Option<String>,
+        /// Höchstens so viele Operationen
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// Zeigt Statistiken über den gespeicherten Verlauf
+    Stats,
+    /// Führt Operationen aus einer CSV- oder JSONL-Datei aus
+    ///
+    /// Erwartet Zeilen wie `divide,1,0` bzw. `{"op":"divide","a":1,"b":0}` und
+    /// schreibt je Zeile ein Ergebnis oder einen Fehler im selben Format.
+    Batch {
+        /// Eingabedatei; ohne Angabe oder mit `-` wird stdin gelesen
+        input: Option<String>,
+        /// Schreibt die Ergebnisse in eine Datei statt nach stdout
+        #[arg(short, long, value_name = "DATEI")]
+        output: Option<String>,
+        /// Format von Ein- und Ausgabe (csv oder jsonl); sonst nach Dateiendung, Standard csv
+        #[arg(long, value_name = "FORMAT")]
+        format: Option<BatchFormat>,
+        /// Anzahl paralleler Threads
+        #[arg(short, long, default_value_t = 1)]
+        jobs: usize,
+    },
+    /// Verwaltet benannte Sitzungen
+    Sessions {
+        #[command(subcommand)]
+        command: SessionCommands,
+    },
+    /// Prüft Audit-Protokolle
+    Audit {
+        #[command(subcommand)]
+        command: AuditCommands,
+    },
+    /// Verwaltet den Hintergrunddienst mit gemeinsamem Verlauf
+    ///
+    /// Läuft der Daemon, werden Rechenbefehle ohne --history, --session oder
+    /// --audit-log an ihn weitergeleitet.
+    #[cfg(unix)]
+    Daemon {
+        #[command(subcommand)]
+        command: DaemonCommands,
+    },
+    /// Startet die HTTP-JSON-Schnittstelle
+    #[cfg(feature = "server")]
+    Serve {
+        /// Adresse, auf der der Server lauscht
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        address: String,
+    },
+    /// Gibt ein Skript zur Vervollständigung in der Shell aus
+    ///
+    /// Das Skript fragt das Programm bei jeder Vervollständigung und schlägt
+    /// so auch gespeicherte Sitzungen und Operationen vor. Beispiel für bash:
+    /// `source <(rust_project_complete completions bash)`
+    Completions {
+        /// Shell, für die das Skript erzeugt wird
+        shell: Shell,
+        /// Eigenständiges Skript ohne Aufrufe des Programms, z. B. für Pakete;
+        /// schlägt keine Sitzungen und Operationen vor
+        #[arg(long = "static")]
+        static_script: bool,
+    },
+    /// Erzeugt Handbuchseiten im roff-Format
+    Man {
+        /// Schreibt je Befehl eine Seite in dieses Verzeichnis, statt die
+        /// Hauptseite nach stdout auszugeben
+        #[arg(long, value_name = "VERZEICHNIS")]
+        dir: Option<String>,
+    },
+    /// Vollbild-Oberfläche im Terminal mit Verlauf, Statistiken und Variablen
+    ///
+    /// Enter wertet die Eingabe aus, `name = ausdruck` belegt eine Variable.
+    /// Mit ↑/↓ wird ein Eintrag im Verlauf gewählt und mit Tab sein Ergebnis
+    /// übernommen; Esc beendet die Oberfläche. Nur verfügbar, wenn das
+    /// Programm mit dem Feature `tui` gebaut wurde.
+    #[command(visible_alias = "tui")]
     Interactive,
 }
 
+/// Zahlen aus Argumenten, einer Datei oder stdin
+#[derive(Args)]
+struct NumberList {
+    /// Zahlen; ohne Angabe werden sie aus --file oder stdin gelesen
+    #[arg(allow_negative_numbers = true)]
+    numbers: Vec<f64>,
+    /// Datei mit Zahlen, getrennt durch Leerraum, Kommas oder Semikolons; `-` für stdin
+    #[arg(long, value_name = "DATEI", conflicts_with = "numbers")]
+    file: Option<String>,
+}
+
+impl NumberList {
+    fn read(&self) -> Result<Vec<f64>, ProjectError> {
+        if !self.numbers.is_empty() {
+            return Ok(self.numbers.clone());
+        }
+        let input = match self.file.as_deref().filter(|path| *path != "-") {
+            Some(path) => utils::read_file_content(path)?,
+            None => io::read_to_string(io::stdin().lock())?,
+        };
+        utils::parse_numbers(&input)
+    }
+}
+
+#[derive(Subcommand)]
+enum AuditCommands {
+    /// Prüft, ob ein Audit-Protokoll unverändert ist
+    Verify {
+        /// Pfad des Audit-Protokolls
+        file: String,
+    },
+}
+
+#[cfg(unix)]
+#[derive(Subcommand)]
+enum DaemonCommands {
+    /// Startet den Daemon im Hintergrund
+    Start,
+    /// Beendet den Daemon und speichert den Verlauf
+    Stop,
+    /// Zeigt, ob der Daemon läuft
+    Status,
+    /// Führt den Daemon im Vordergrund aus, z. B. unter systemd
+    Run,
+}
+
+#[derive(Subcommand)]
+enum SessionCommands {
+    /// Listet alle Sitzungen auf
+    List,
+    /// Erstellt eine neue Sitzung
+    Create {
+        /// Name der Sitzung
+        name: String,
+    },
+    /// Benennt eine Sitzung um
+    Rename {
+        /// Bisheriger Name
+        #[arg(add = ArgValueCandidates::new(session_names))]
+        old_name: String,
+        /// Neuer Name
+        new_name: String,
+    },
+    /// Kopiert eine Sitzung unter neuem Namen
+    Fork {
+        /// Name der Quelle
+        #[arg(add = ArgValueCandidates::new(session_names))]
+        source: String,
+        /// Name der Kopie
+        target: String,
+    },
+    /// Löscht eine Sitzung
+    Delete {
+        /// Name der Sitzung
+        #[arg(add = ArgValueCandidates::new(session_names))]
+        name: String,
+    },
+}
+
+/// Ort, an dem der Calculator zwischen zwei Aufrufen gespeichert wird
+enum Store {
+    Memory(Calculator),
+    /// Jede Operation wird sofort ins Protokoll geschrieben
+    Audit(Calculator),
+    HistoryFile {
+        path: String,
+        calculator: Calculator,
+    },
+    Session {
+        manager: SessionManager,
+        session: Session,
+    },
+}
+
+impl Store {
+    /// Öffnet den Speicherort und setzt die Richtlinie aus `--numeric-policy` oder der Sitzung
+    fn open(cli: &Cli) -> Result<Self, ProjectError> {
+        let mut store = Self::open_location(cli)?;
+        let policy = match &store {
+            Store::Session { session, .. } => cli.numeric_policy.or(session.config.numeric_policy),
+            _ => cli.numeric_policy,
+        };
+        store.calculator().set_numeric_policy(policy.unwrap_or_default());
+        Ok(store)
+    }
+
+    fn open_location(cli: &Cli) -> Result<Self, ProjectError> {
+        if let Some(name) = &cli.session {
+            let manager = session_manager(cli)?;
+            let session = manager.open_or_create(name)?;
+            return Ok(Store::Session { manager, session });
+        }
+        if let Some(path) = &cli.audit_log {
+            let store = AuditHistoryStore::open(path)?;
+            return Ok(Store::Audit(Calculator::with_store(Box::new(store))?));
+        }
+
+        match &cli.history {
+            Some(path) => Self::open_history_file(path),
+            None => Ok(Store::Memory(Calculator::new())),
+        }
+    }
+
+    fn open_history_file(path: &str) -> Result<Self, ProjectError> {
+        let calculator = if utils::file_exists(path) {
+            Calculator::load_from_file(path)?
+        } else {
+            Calculator::new()
+        };
+        Ok(Store::HistoryFile {
+            path: path.to_string(),
+            calculator,
+        })
+    }
+
+    /// Prüft, ob ausdrücklich ein Speicherort gewählt wurde
+    fn is_explicit(cli: &Cli) -> bool {
+        cli.history.is_some() || cli.session.is_some() || cli.audit_log.is_some()
+    }
+
+    fn calculator(&mut self) -> &mut Calculator {
+        match self {
+            Store::Memory(calculator) | Store::Audit(calculator) => calculator,
+            Store::HistoryFile { calculator, .. } => calculator,
+            Store::Session { session, .. } => &mut session.calculator,
+        }
+    }
+
+    /// Stellt den Calculator für einen langlebigen Betrieb gemeinsam nutzbar bereit
+    ///
+    /// Danach wird der Zustand zurückübernommen, damit `save` ihn speichert.
+    fn with_shared<R>(
+        &mut self,
+        f: impl FnOnce(SharedCalculator) -> Result<R, ProjectError>,
+    ) -> Result<R, ProjectError> {
+        let calc = self.calculator();
+        let shared = SharedCalculator::from(std::mem::take(calc));
+        let result = f(shared.clone());
+        *calc = shared.write(std::mem::take);
+        result
+    }
+
+    /// Speichert einen vom Daemon genutzten Calculator an diesem Ort
+    #[cfg(unix)]
+    fn persistence(&self) -> Box<Persist> {
+        match self {
+            // Das Audit-Protokoll schreibt jede Operation ohnehin sofort
+            Store::Memory(_) | Store::Audit(_) => Box::new(|_| Ok(())),
+            Store::HistoryFile { path, .. } => {
+                let path = path.clone();
+                Box::new(move |calculator| calculator.save_to_file(&path))
+            }
+            Store::Session { manager, session } => {
+                let (manager, name, config) =
+                    (manager.clone(), session.name.clone(), session.config.clone());
+                Box::new(move |calculator| manager.save_parts(&name, &config, calculator))
+            }
+        }
+    }
+
+    fn save(&self) -> Result<(), ProjectError> {
+        match self {
+            Store::Memory(_) | Store::Audit(_) => Ok(()),
+            Store::HistoryFile { path, calculator } => calculator.save_to_file(path),
+            Store::Session { manager, session } => manager.save(session),
+        }
+    }
+}
+
+/// Führt die Rechenbefehle lokal oder im Daemon aus
+trait Backend {
+    fn add(&mut self, a: f64, b: f64) -> Result<f64, ProjectError>;
+    fn subtract(&mut self, a: f64, b: f64) -> Result<f64, ProjectError>;
+    fn multiply(&mut self, a: f64, b: f64) -> Result<f64, ProjectError>;
+    fn divide(&mut self, a: f64, b: f64) -> Result<f64, ProjectError>;
+    fn factorial(&mut self, n: u64) -> Result<u64, ProjectError>;
+    fn apply(&mut self, name: &str, operands: &[f64]) -> Result<f64, ProjectError>;
+    fn evaluate(&mut self, expression: &str) -> Result<f64, ProjectError>;
+    fn define(&mut self, definition: &OperationDefinition) -> Result<(), ProjectError>;
+    fn operations(&mut self) -> Result<Vec<OperationSummary>, ProjectError>;
+    fn history(&mut self, query: &HistoryQuery) -> Result<Vec<Operation>, ProjectError>;
+    fn statistics(&mut self) -> Result<Statistics, ProjectError>;
+}
+
+/// Operation, wie sie `operations` auflistet
+#[derive(serde::Deserialize)]
+struct OperationSummary {
+    name: String,
+    arity: usize,
+    symbol: Option<String>,
+}
+
+impl Backend for Calculator {
+    fn add(&mut self, a: f64, b: f64) -> Result<f64, ProjectError> {
+        Calculator::add(self, a, b)
+    }
+
+    fn subtract(&mut self, a: f64, b: f64) -> Result<f64, ProjectError> {
+        Calculator::subtract(self, a, b)
+    }
+
+    fn multiply(&mut self, a: f64, b: f64) -> Result<f64, ProjectError> {
+        Calculator::multiply(self, a, b)
+    }
+
+    fn divide(&mut self, a: f64, b: f64) -> Result<f64, ProjectError> {
+        Calculator::divide(self, a, b)
+    }
+
+    fn factorial(&mut self, n: u64) -> Result<u64, ProjectError> {
+        Calculator::factorial(self, n)
+    }
+
+    fn apply(&mut self, name: &str, operands: &[f64]) -> Result<f64, ProjectError> {
+        Calculator::apply(self, name, operands)
+    }
+
+    fn evaluate(&mut self, expression: &str) -> Result<f64, ProjectError> {
+        Calculator::evaluate(self, expression)
+    }
+
+    fn define(&mut self, definition: &OperationDefinition) -> Result<(), ProjectError> {
+        self.define_operation(definition.clone())
+    }
+
+    fn operations(&mut self) -> Result<Vec<OperationSummary>, ProjectError> {
+        let operations = Calculator::operations(self).iter().map(|operator| OperationSummary {
+            name: operator.name().to_string(),
+            arity: operator.arity(),
+            symbol: operator.symbol().map(str::to_string),
+        });
+        Ok(operations.collect())
+    }
+
+    fn history(&mut self, query: &HistoryQuery) -> Result<Vec<Operation>, ProjectError> {
+        self.query_history(query)
+    }
+
+    fn statistics(&mut self) -> Result<Statistics, ProjectError> {
+        Ok(Calculator::statistics(self).clone())
+    }
+}
+
+#[cfg(unix)]
+impl Backend for DaemonClient {
+    fn add(&mut self, a: f64, b: f64) -> Result<f64, ProjectError> {
+        self.call_number("add", &[a, b])
+    }
+
+    fn subtract(&mut self, a: f64, b: f64) -> Result<f64, ProjectError> {
+        self.call_number("subtract", &[a, b])
+    }
+
+    fn multiply(&mut self, a: f64, b: f64) -> Result<f64, ProjectError> {
+        self.call_number("multiply", &[a, b])
+    }
+
+    fn divide(&mut self, a: f64, b: f64) -> Result<f64, ProjectError> {
+        self.call_number("divide", &[a, b])
+    }
+
+    fn factorial(&mut self, n: u64) -> Result<u64, ProjectError> {
+        self.call("factorial", serde_json::json!([n]))
+    }
+
+    fn apply(&mut self, name: &str, operands: &[f64]) -> Result<f64, ProjectError> {
+        let Lossless(result) = self.call("apply", serde_json::json!([name, lossless(operands)]))?;
+        Ok(result)
+    }
+
+    fn evaluate(&mut self, expression: &str) -> Result<f64, ProjectError> {
+        let Lossless(result) = self.call("evaluate", serde_json::json!([expression]))?;
+        Ok(result)
+    }
+
+    fn define(&mut self, definition: &OperationDefinition) -> Result<(), ProjectError> {
+        let params = serde_json::json!([definition.to_string()]);
+        self.call::<serde_json::Value>("define_operation", params).map(|_| ())
+    }
+
+    fn operations(&mut self) -> Result<Vec<OperationSummary>, ProjectError> {
+        self.call("operations", serde_json::Value::Null)
+    }
+
+    fn history(&mut self, query: &HistoryQuery) -> Result<Vec<Operation>, ProjectError> {
+        self.call("query_history", serde_json::to_value(query)?)
+    }
+
+    fn statistics(&mut self) -> Result<Statistics, ProjectError> {
+        self.call("statistics", serde_json::Value::Null)
+    }
+}
+
+fn session_manager(cli: &Cli) -> Result<SessionManager, ProjectError> {
+    match &cli.session_dir {
+        Some(dir) => SessionManager::new(dir),
+        None => SessionManager::new(&SessionManager::default_directory()),
+    }
+}
+
+/// Umgebungsvariable, mit der die Shell das Programm zur Vervollständigung aufruft
+const COMPLETION_VAR: &str = "RUST_PROJECT_COMPLETE_COMPLETION";
+
 fn main() {
-    env_logger::init();
-    
-    let cli = Cli::parse();
-    let mut calc = Calculator::new();
-    
-    match cli.command {
+    CompleteEnv::with_factory(Cli::command)
+        .var(COMPLETION_VAR)
+        .complete();
+
+    // Farben nur im Terminal, abschaltbar nach https://no-color.org
+    let color = io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+    let matches = Cli::command()
+        .try_get_matches()
+        .unwrap_or_else(|error| exit_usage(error, color));
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|error| exit_usage(error, color));
+    cli.locale = resolve_locale(&cli);
+    let (format, locale) = (cli.output_format, cli.locale);
+    if let Err(e) = logging::init(cli.log_format) {
+        eprint!("{}", output::render_error(format, locale, &e, color));
+    }
+
+    let usage_error = match (&cli.command, cli.stdio_rpc) {
+        (None, false) => Some((ErrorKind::MissingSubcommand, "cli.no_command")),
+        (Some(_), true) => Some((ErrorKind::ArgumentConflict, "cli.stdio_rpc_with_command")),
+        (Some(Commands::Interactive), false) if !cfg!(feature = "tui") => {
+            Some((ErrorKind::InvalidSubcommand, "cli.tui_unavailable"))
+        }
+        _ => None,
+    };
+    if let Some((kind, key)) = usage_error {
+        let message = Message::new(key, []).render(locale);
+        exit_usage(Cli::command().error(kind, message), color);
+    }
+
+    let command = matches.subcommand_name().unwrap_or("stdio-rpc");
+    let result = logging::with_correlation_id(&utils::generate_id(), || {
+        let span = tracing::info_span!(
+            "command",
+            correlation_id = %logging::correlation_id(),
+            command,
+            arguments = ?std::env::args().skip(1).collect::<Vec<_>>(),
+            error = tracing::field::Empty,
+        );
+        let _entered = span.enter();
+        let result = run(cli);
+        if let Err(e) = &result {
+            span.record("error", tracing::field::display(e));
+        }
+        result
+    });
+
+    if let Err(e) = result {
+        eprint!("{}", output::render_error(format, locale, &e, color));
+        process::exit(e.exit_code());
+    }
+}
+
+/// Beendet das Programm mit einem Aufruffehler von clap
+///
+/// Hilfe und Version sowie Fehler im Textformat gibt clap selbst aus; für
+/// `--output-format json` oder `csv` wird der Fehler wie alle anderen über
+/// [`output::render_error`] geschrieben, mit dem Exit-Code von clap.
+fn exit_usage(error: clap::Error, color: bool) -> ! {
+    // Das Format steht auch in einer fehlerhaften Befehlszeile meist schon fest
+    let partial = Cli::command().ignore_errors(true).try_get_matches().unwrap_or_default();
+    fn option<T: Clone + Send + Sync + 'static>(matches: &clap::ArgMatches, id: &str) -> Option<T> {
+        matches.try_get_one::<T>(id).ok().flatten().cloned()
+    }
+    let format: OutputFormat = option(&partial, "output_format").unwrap_or_default();
+    if !error.use_stderr() || matches!(format, OutputFormat::Text | OutputFormat::Table) {
+        error.exit();
+    }
+
+    // Nur die Meldung ohne Hinweise und Usage-Zeile, z. B. "unexpected argument '--x' found"
+    let rendered = error.to_string();
+    let first = rendered.split("\n\n").next().unwrap_or_default();
+    let message = first.strip_prefix("error: ").unwrap_or(first);
+    let message = message.split_whitespace().collect::<Vec<_>>().join(" ");
+    let locale = option(&partial, "lang").or_else(Locale::from_env).unwrap_or_default();
+    let e = ProjectError::invalid_input(message);
+    eprint!("{}", output::render_error(format, locale, &e, color));
+    process::exit(error.exit_code());
+}
+
+fn run(cli: Cli) -> Result<(), ProjectError> {
+    let Some(command) = &cli.command else {
+        let mut store = Store::open(&cli)?;
+        store.with_shared(|shared| {
+            RpcServer::new(shared).locale(cli.locale).run(io::stdin().lock(), io::stdout().lock())
+        })?;
+        return store.save();
+    };
+
+    if let Commands::Sessions { command } = command {
+        print(&cli, run_session_command(&session_manager(&cli)?, command)?);
+        return Ok(());
+    }
+    if let Commands::Completions {
+        shell,
+        static_script,
+    } = command
+    {
+        return write_completions(*shell, *static_script);
+    }
+    if let Commands::Man { dir } = command {
+        let Some(dir) = dir else {
+            clap_mangen::Man::new(Cli::command()).render(&mut io::stdout().lock())?;
+            return Ok(());
+        };
+        utils::create_directory(dir)?;
+        clap_mangen::generate_to(Cli::command(), dir)?;
+        print(&cli, Report::message(Message::new("cli.man_written", [dir.into()])));
+        return Ok(());
+    }
+    if let Commands::Audit {
+        command: AuditCommands::Verify { file },
+    } = command
+    {
+        let report = verify_audit_log(file)?;
+        let text = Message::lines([
+            Message::new("audit.unchanged", [report.entries.into()]),
+            Message::new("audit.last_hash", [(&report.last_hash).into()]),
+        ]);
+        let value = serde_json::json!({"entries": report.entries, "last_hash": report.last_hash});
+        print(&cli, Report::new(text, value).columns(&["entries", "last_hash"]));
+        return Ok(());
+    }
+
+    #[cfg(unix)]
+    if let Commands::Daemon { command } = command {
+        return run_daemon_command(&cli, command);
+    }
+    if let Some(report) = math(command)? {
+        print(&cli, report);
+        return Ok(());
+    }
+    if let Commands::Batch {
+        input,
+        output,
+        format,
+        jobs,
+    } = command
+    {
+        let input = input.as_deref().filter(|path| *path != "-");
+        let format = format
+            .or_else(|| input.and_then(BatchFormat::from_path))
+            .unwrap_or(BatchFormat::Csv);
+        return run_batch(&cli, Batch::new(format).jobs(*jobs), input, output.as_deref());
+    }
+
+    // Der Daemon rechnet mit seiner eigenen Richtlinie, daher nur ohne --numeric-policy
+    #[cfg(unix)]
+    if is_calculation(command) && !Store::is_explicit(&cli) && cli.numeric_policy.is_none() {
+        if let Some(mut client) = DaemonClient::connect(&socket_path(&cli))? {
+            print(&cli, execute(&mut client, command)?);
+            return Ok(());
+        }
+    }
+
+    let mut store = Store::open(&cli)?;
+
+    #[cfg(feature = "server")]
+    if let Commands::Serve { address } = command {
+        store.with_shared(|shared| run_server(&cli, address, shared))?;
+        return store.save();
+    }
+    #[cfg(feature = "tui")]
+    if let Commands::Interactive = command {
+        tui::run(store.calculator(), cli.locale)?;
+        return store.save();
+    }
+    if let (Commands::Define { .. }, Store::Memory(_) | Store::Audit(_)) = (command, &store) {
+        return Err(ProjectError::invalid_input(Message::new("cli.define_needs_store", [])));
+    }
+
+    let report = execute(store.calculator(), command)?;
+    print(&cli, report);
+    store.save()
+}
+
+/// Schreibt ein Ergebnis im gewählten Format und in der gewählten Sprache nach stdout
+fn print(cli: &Cli, report: Report) {
+    print!("{}", report.translate(cli.locale).render(cli.output_format));
+}
+
+/// Sprache aus `--lang`, der Konfiguration der Sitzung oder der Umgebung
+fn resolve_locale(cli: &Cli) -> Locale {
+    let from_session = || {
+        let manager = session_manager(cli).ok()?;
+        manager.open(cli.session.as_deref()?).ok()?.config.locale
+    };
+    cli.lang
+        .or_else(from_session)
+        .or_else(Locale::from_env)
+        .unwrap_or_default()
+}
+
+/// Befehle, die auch ein laufender Daemon ausführen kann
+#[cfg(unix)]
+fn is_calculation(command: &Commands) -> bool {
+    matches!(
+        command,
+        Commands::Add { .. }
+            | Commands::Subtract { .. }
+            | Commands::Multiply { .. }
+            | Commands::Divide { .. }
+            | Commands::Factorial { .. }
+            | Commands::Apply { .. }
+            | Commands::Define { .. }
+            | Commands::Operations
+            | Commands::Eval { .. }
+            | Commands::History { .. }
+            | Commands::Stats
+    )
+}
+
+fn execute(calc: &mut dyn Backend, command: &Commands) -> Result<Report, ProjectError> {
+    let report = match *command {
         Commands::Add { a, b } => {
-            let result = calc.add(a, b);
-            println!("{} + {} = {}", a, b, result);
+            let result = calc.add(a, b)?;
+            calculation(format!("{} + {} = {}", a, b, result), "add", &[a, b], result)
         }
         Commands::Subtract { a, b } => {
-            let result = calc.subtract(a, b);
-            println!("{} - {} = {}", a, b, result);
+            let result = calc.subtract(a, b)?;
+            calculation(format!("{} - {} = {}", a, b, result), "subtract", &[a, b], result)
         }
         Commands::Multiply { a, b } => {
-            let result = calc.multiply(a, b);
-            println!("{} * {} = {}", a, b, result);
+            let result = calc.multiply(a, b)?;
+            calculation(format!("{} * {} = {}", a, b, result), "multiply", &[a, b], result)
         }
         Commands::Divide { a, b } => {
-            match calc.divide(a, b) {
-                Ok(result) => println!("{} / {} = {}", a, b, result),
-                Err(e) => {
-                    eprintln!("Fehler: {}", e);
-                    process::exit(1);
-                }
-            }
+            let result = calc.divide(a, b)?;
+            calculation(format!("{} / {} = {}", a, b, result), "divide", &[a, b], result)
         }
         Commands::Factorial { n } => {
-            match calc.factorial(n) {
-                Ok(result) => println!("{}! = {}", n, result),
-                Err(e) => {
-                    eprintln!("Fehler: {}", e);
-                    process::exit(1);
+            let result = calc.factorial(n)?;
+            let value = serde_json::json!({"operation": "factorial", "operands": [n], "result": result});
+            Report::new(format!("{}! = {}", n, result), value)
+                .columns(&["operation", "operands", "result"])
+        }
+        Commands::Apply {
+            ref name,
+            ref operands,
+        } => {
+            let result = calc.apply(name, operands)?;
+            let text = format!("{}({}) = {}", name, join(operands), result);
+            calculation(text, name, operands, result)
+        }
+        Commands::Define { ref definition } => {
+            let definition: OperationDefinition = definition.join(" ").parse()?;
+            calc.define(&definition)?;
+            let value = serde_json::json!({
+                "name": definition.name,
+                "parameters": definition.parameters,
+                "body": definition.body,
+            });
+            Report::new(Message::new("definition.defined", [definition.to_string().into()]), value)
+                .columns(&["name", "parameters", "body"])
+        }
+        Commands::Operations => {
+            let mut lines = Vec::new();
+            let mut operations = Vec::new();
+            for operator in calc.operations()? {
+                let (name, arity) = ((&operator.name).into(), operator.arity.into());
+                lines.push(match &operator.symbol {
+                    Some(symbol) => {
+                        Message::new("operations.entry_with_symbol", [name, arity, symbol.into()])
+                    }
+                    None => Message::new("operations.entry", [name, arity]),
+                });
+                operations.push(serde_json::json!({
+                    "name": operator.name,
+                    "arity": operator.arity,
+                    "symbol": operator.symbol,
+                }));
+            }
+            Report::new(Message::lines(lines), operations.into()).columns(&["name", "arity", "symbol"])
+        }
+        Commands::Eval { ref expression } => {
+            let expression = expression.join(" ");
+            let result = calc.evaluate(&expression)?;
+            let value = serde_json::json!({"expression": expression, "result": Lossless(result)});
+            Report::new(format!("{} = {}", expression, result), value).columns(&["expression", "result"])
+        }
+        Commands::History {
+            ref operation,
+            limit,
+        } => {
+            let mut query = HistoryQuery::new();
+            if let Some(name) = operation {
+                query = query.operation_type(OperationType::from_name(name));
+            }
+            if let Some(limit) = limit {
+                query = query.limit(limit);
+            }
+            history_report(&calc.history(&query)?)
+        }
+        Commands::Stats => statistics_report(&calc.statistics()?)?,
+        _ => unreachable!("Verwaltungsbefehle werden vorab behandelt"),
+    };
+    Ok(report)
+}
+
+/// Schreibt das Vervollständigungsskript für `shell` nach stdout
+fn write_completions(shell: Shell, static_script: bool) -> Result<(), ProjectError> {
+    let mut command = Cli::command();
+    let name = command.get_name().to_string();
+    let mut out = io::stdout().lock();
+    if static_script {
+        // `generate` bricht bei Schreibfehlern ab, daher erst in einen Puffer
+        let mut script = Vec::new();
+        clap_complete::generate(shell, &mut command, name, &mut script);
+        out.write_all(&script)?;
+        return Ok(());
+    }
+
+    let shells = Shells::builtins();
+    let completer = shells
+        .completer(&shell.to_string())
+        .ok_or_else(|| {
+            ProjectError::invalid_input(Message::new("cli.unknown_shell", [shell.to_string().into()]))
+        })?;
+    let program = std::env::current_exe()?;
+    completer.write_registration(COMPLETION_VAR, &name, &name, &program.to_string_lossy(), &mut out)?;
+    Ok(())
+}
+
+/// Gespeicherte Sitzungen für die Vervollständigung
+///
+/// Die Kandidaten sehen die übrigen Argumente nicht, daher gilt nur
+/// `RUST_PROJECT_COMPLETE_SESSION_DIR` und nicht `--session-dir`.
+fn session_names() -> Vec<CompletionCandidate> {
+    let directory = std::env::var("RUST_PROJECT_COMPLETE_SESSION_DIR")
+        .unwrap_or_else(|_| SessionManager::default_directory());
+    SessionManager::new(&directory)
+        .and_then(|manager| manager.list())
+        .unwrap_or_default()
+        .into_iter()
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// Namen der Operationen für die Vervollständigung
+fn operation_names() -> Vec<CompletionCandidate> {
+    OperationRegistry::new()
+        .iter()
+        .map(|operator| CompletionCandidate::new(operator.name()))
+        .collect()
+}
+
+/// Hilfsfunktionen aus `MathUtils` und `utils`, die keinen Verlauf benötigen
+fn math(command: &Commands) -> Result<Option<Report>, ProjectError> {
+    let report = match command {
+        Commands::Gcd { a, b } => {
+            let result = MathUtils::gcd(*a, *b);
+            let text = Message::new("math.gcd", [(*a).into(), (*b).into(), result.into()]);
+            integer("gcd", &[*a, *b], result, text)
+        }
+        Commands::Lcm { a, b } => {
+            let result = MathUtils::lcm(*a, *b)?;
+            let text = Message::new("math.lcm", [(*a).into(), (*b).into(), result.into()]);
+            integer("lcm", &[*a, *b], result, text)
+        }
+        Commands::IsPrime { n } => {
+            let result = MathUtils::is_prime(*n);
+            let key = if result { "math.prime" } else { "math.not_prime" };
+            let text = Message::new(key, [(*n).into()]);
+            integer("is_prime", &[*n], result, text)
+        }
+        Commands::Fibonacci { n } => {
+            let result = MathUtils::fibonacci(*n)?;
+            integer("fibonacci", &[*n], result, format!("fibonacci({}) = {}", n, result).into())
+        }
+        Commands::BigFactorial { n } => {
+            let result = MathUtils::big_factorial(*n);
+            let text = format!("{}! = {}", n, result);
+            integer("big_factorial", &[*n], result, text.into())
+        }
+        Commands::Average(list) => {
+            aggregate(list, "average", "statistics.average", utils::calculate_average)?
+        }
+        Commands::Stddev(list) => aggregate(
+            list,
+            "standard_deviation",
+            "statistics.standard_deviation",
+            utils::calculate_standard_deviation,
+        )?,
+        Commands::Min(list) => aggregate(list, "minimum", "statistics.minimum", utils::find_minimum)?,
+        Commands::Max(list) => aggregate(list, "maximum", "statistics.maximum", utils::find_maximum)?,
+        Commands::CelsiusToFahrenheit { celsius } => {
+            let result = utils::celsius_to_fahrenheit(*celsius);
+            let text = format!("{} °C = {} °F", celsius, result);
+            calculation(text, "celsius_to_fahrenheit", &[*celsius], result)
+        }
+        Commands::FahrenheitToCelsius { fahrenheit } => {
+            let result = utils::fahrenheit_to_celsius(*fahrenheit);
+            let text = format!("{} °F = {} °C", fahrenheit, result);
+            calculation(text, "fahrenheit_to_celsius", &[*fahrenheit], result)
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(report))
+}
+
+/// Ergebnis einer Funktion über ganze Zahlen; `result` kann auch Wahrheitswert oder Text sein
+fn integer(operation: &str, operands: &[u64], result: impl serde::Serialize, text: Message) -> Report {
+    let value = serde_json::json!({"operation": operation, "operands": operands, "result": result});
+    Report::new(text, value).columns(&["operation", "operands", "result"])
+}
+
+/// Kennzahl einer Liste; in JSON mit der Anzahl statt aller Zahlen
+///
+/// `label` ist der Schlüssel der Meldung, z. B. `statistics.average`.
+fn aggregate(
+    list: &NumberList,
+    operation: &str,
+    label: &'static str,
+    f: fn(&[f64]) -> Result<f64, ProjectError>,
+) -> Result<Report, ProjectError> {
+    let numbers = list.read()?;
+    let result = f(&numbers)?;
+    let value =
+        serde_json::json!({"operation": operation, "count": numbers.len(), "result": Lossless(result)});
+    let report = Report::new(Message::new(label, [result.into()]), value);
+    Ok(report.columns(&["operation", "count", "result"]))
+}
+
+/// Ergebnis einer einzelnen Rechnung
+fn calculation(text: String, operation: &str, operands: &[f64], result: f64) -> Report {
+    let value = serde_json::json!({
+        "operation": operation,
+        "operands": lossless(operands),
+        "result": Lossless(result),
+    });
+    Report::new(text, value).columns(&["operation", "operands", "result"])
+}
+
+/// Zahlen für JSON, CSV und Tabellen; NaN und ±Unendlich als `"NaN"`, `"inf"` und `"-inf"`
+fn lossless(values: &[f64]) -> Vec<Lossless> {
+    values.iter().copied().map(Lossless).collect()
+}
+
+fn join(operands: &[f64]) -> String {
+    let operands: Vec<String> = operands.iter().map(f64::to_string).collect();
+    operands.join(", ")
+}
+
+fn history_report(history: &[Operation]) -> Report {
+    let text = if history.is_empty() {
+        Message::new("history.empty", [])
+    } else {
+        Message::lines(history.iter().map(|op| {
+            let name = op.operation_type.name();
+            Message::text(format!("{}: {}({}) = {}", op.id, name, join(&op.operands), op.result))
+        }))
+    };
+    let value = history
+        .iter()
+        .map(|op| {
+            serde_json::json!({
+                "id": op.id,
+                "operation": op.operation_type.name(),
+                "operands": lossless(&op.operands),
+                "result": Lossless(op.result),
+                "timestamp": op.timestamp,
+            })
+        })
+        .collect();
+    Report::new(text, value).columns(&["id", "operation", "operands", "result", "timestamp"])
+}
+
+/// Führt einen Stapellauf aus; mit gewähltem Speicherort wird der Verlauf aufgezeichnet
+fn run_batch(
+    cli: &Cli,
+    batch: Batch,
+    input: Option<&str>,
+    output: Option<&str>,
+) -> Result<(), ProjectError> {
+    let reader: Box<dyn BufRead> = match input {
+        Some(path) => Box::new(BufReader::new(File::open(path).map_err(|e| ProjectError::io(e, path))?)),
+        None => Box::new(io::stdin().lock()),
+    };
+    let writer: Box<dyn Write> = match output {
+        Some(path) => Box::new(BufWriter::new(File::create(path).map_err(|e| ProjectError::io(e, path))?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+
+    let summary = if Store::is_explicit(cli) {
+        let mut store = Store::open(cli)?;
+        let summary = store.with_shared(|shared| batch.calculator(shared).run(reader, writer))?;
+        store.save()?;
+        summary
+    } else {
+        batch
+            .numeric_policy(cli.numeric_policy.unwrap_or_default())
+            .run(reader, writer)?
+    };
+    let text = Message::new("batch.summary", [summary.records.into(), summary.errors.into()]);
+    let value = serde_json::json!({"records": summary.records, "errors": summary.errors});
+    // stdout gehört den Ergebnissen, die Zusammenfassung geht nach stderr
+    let report = Report::new(text, value).columns(&["records", "errors"]);
+    eprint!("{}", report.translate(cli.locale).render(cli.output_format));
+    Ok(())
+}
+
+#[cfg(unix)]
+fn socket_path(cli: &Cli) -> String {
+    cli.socket.clone().unwrap_or_else(Daemon::default_socket_path)
+}
+
+#[cfg(unix)]
+fn run_daemon_command(cli: &Cli, command: &DaemonCommands) -> Result<(), ProjectError> {
+    let socket = socket_path(cli);
+    match command {
+        DaemonCommands::Start => {
+            if DaemonClient::connect(&socket)?.is_some() {
+                print(cli, Report::message(Message::new("daemon.already_running", [(&socket).into()])));
+                return Ok(());
+            }
+            let mut child = spawn_daemon(cli, &socket)?;
+            let deadline = Instant::now() + Duration::from_secs(10);
+            loop {
+                if let Some(mut client) = DaemonClient::connect(&socket)? {
+                    let status = client.status()?;
+                    let text =
+                        Message::new("daemon.started", [status.pid.into(), (&socket).into()]);
+                    print(cli, Report::message(text));
+                    return Ok(());
                 }
+                let kind = if child.try_wait()?.is_some() {
+                    io::ErrorKind::Other
+                } else if Instant::now() > deadline {
+                    io::ErrorKind::TimedOut
+                } else {
+                    std::thread::sleep(Duration::from_millis(50));
+                    continue;
+                };
+                let message = Message::new("daemon.start_failed", []);
+                let error = io::Error::new(kind, message.render(cli.locale));
+                return Err(ProjectError::io(error, &socket));
+            }
+        }
+        DaemonCommands::Stop => {
+            let Some(mut client) = DaemonClient::connect(&socket)? else {
+                print(cli, Report::message(Message::new("daemon.not_running", [])));
+                return Ok(());
+            };
+            client.shutdown()?;
+            // Der Socket verschwindet erst, nachdem der Verlauf gespeichert ist
+            let deadline = Instant::now() + Duration::from_secs(10);
+            while utils::file_exists(&socket) && Instant::now() < deadline {
+                std::thread::sleep(Duration::from_millis(50));
             }
+            print(cli, Report::message(Message::new("daemon.stopped", [])));
+        }
+        DaemonCommands::Status => match DaemonClient::connect(&socket)? {
+            Some(mut client) => {
+                let status = client.status()?;
+                let text = Message::lines([
+                    Message::new("daemon.status", [status.pid.into(), (&status.socket).into()]),
+                    Message::new("daemon.uptime", [status.uptime_seconds.into()]),
+                    Message::new("history.count", [status.history_count.into()]),
+                ]);
+                let mut value = serde_json::to_value(&status)?;
+                value["running"] = true.into();
+                let report = Report::new(text, value).columns(&[
+                    "running",
+                    "pid",
+                    "socket",
+                    "uptime_seconds",
+                    "history_count",
+                ]);
+                print(cli, report);
+            }
+            None => {
+                let value = serde_json::json!({"running": false});
+                let text = Message::new("daemon.not_running", []);
+                print(cli, Report::new(text, value).columns(&["running"]));
+                process::exit(NOT_RUNNING_EXIT_CODE);
+            }
+        },
+        DaemonCommands::Run => {
+            let mut store = if Store::is_explicit(cli) {
+                Store::open(cli)?
+            } else {
+                let mut store = Store::open_history_file(&default_daemon_history())?;
+                store.calculator().set_numeric_policy(cli.numeric_policy.unwrap_or_default());
+                store
+            };
+            let daemon = Daemon::bind(&socket)?
+                .persist_with(store.persistence())
+                .locale(cli.locale);
+            print(cli, Report::message(Message::new("daemon.running", [(&socket).into()])));
+            store.with_shared(|shared| {
+                tokio::runtime::Runtime::new()?.block_on(daemon.run(shared))
+            })?;
+            store.save()?;
+            drop(daemon);
+        }
+    }
+    Ok(())
+}
+
+/// Startet `daemon run` als eigenständigen Hintergrundprozess
+#[cfg(unix)]
+fn spawn_daemon(cli: &Cli, socket: &str) -> Result<process::Child, ProjectError> {
+    use std::os::unix::process::CommandExt;
+
+    let mut command = process::Command::new(std::env::current_exe()?);
+    command.args(["--socket", socket]);
+    for (flag, value) in [
+        ("--history", &cli.history),
+        ("--session", &cli.session),
+        ("--session-dir", &cli.session_dir),
+        ("--audit-log", &cli.audit_log),
+    ] {
+        if let Some(value) = value {
+            command.args([flag, value]);
         }
-        Commands::Interactive => {
-            println!("Interaktiver Modus - Implementierung folgt...");
+    }
+    if let Some(policy) = cli.numeric_policy {
+        command.args(["--numeric-policy", &policy.to_string()]);
+    }
+    command.args(["--lang", &cli.locale.to_string()]);
+    Ok(command
+        .args(["daemon", "run"])
+        .stdin(process::Stdio::null())
+        .stdout(process::Stdio::null())
+        .stderr(process::Stdio::null())
+        // Eigene Prozessgruppe, damit Strg+C im Terminal den Daemon nicht beendet
+        .process_group(0)
+        .spawn()?)
+}
+
+/// Verlaufsdatei des Daemons, wenn kein Speicherort angegeben ist
+#[cfg(unix)]
+fn default_daemon_history() -> String {
+    let base = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    let dir = std::path::Path::new(&base).join(".rust_project_complete");
+    let _ = std::fs::create_dir_all(&dir);
+    dir.join("daemon-history.json").to_string_lossy().into_owned()
+}
+
+#[cfg(feature = "server")]
+fn run_server(cli: &Cli, address: &str, calculator: SharedCalculator) -> Result<(), ProjectError> {
+    tokio::runtime::Runtime::new()?.block_on(async {
+        let listener = tokio::net::TcpListener::bind(address).await?;
+        let address = format!("http://{}", listener.local_addr()?);
+        print(cli, Report::message(Message::new("server.running", [address.into()])));
+        server::serve(listener, AsyncCalculator::from(calculator), async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await
+    })
+}
+
+fn run_session_command(
+    manager: &SessionManager,
+    command: &SessionCommands,
+) -> Result<Report, ProjectError> {
+    let report = match command {
+        SessionCommands::List => {
+            let names = manager.list()?;
+            let text = if names.is_empty() {
+                Message::new("session.none", [])
+            } else {
+                Message::text(names.join("\n"))
+            };
+            let value = names
+                .iter()
+                .map(|name| serde_json::json!({"name": name}))
+                .collect();
+            Report::new(text, value).columns(&["name"])
         }
+        SessionCommands::Create { name } => {
+            manager.create(name)?;
+            Report::message(Message::new("session.created", [name.into()]))
+        }
+        SessionCommands::Rename { old_name, new_name } => {
+            manager.rename(old_name, new_name)?;
+            Report::message(Message::new("session.renamed", [old_name.into(), new_name.into()]))
+        }
+        SessionCommands::Fork { source, target } => {
+            manager.fork(source, target)?;
+            Report::message(Message::new("session.copied", [source.into(), target.into()]))
+        }
+        SessionCommands::Delete { name } => {
+            manager.delete(name)?;
+            Report::message(Message::new("session.deleted", [name.into()]))
+        }
+    };
+    Ok(report)
+}
+
+/// Statistiken; CSV und Tabellen enthalten eine Zeile je Operationsart
+fn statistics_report(stats: &Statistics) -> Result<Report, ProjectError> {
+    let columns = ["operation", "count", "min_result", "max_result", "average_result"];
+    let value = serde_json::to_value(stats)?;
+    if stats.is_empty() {
+        let text = Message::new("history.empty", []);
+        return Ok(Report::new(text, value).columns(&columns).rows(Vec::new()));
+    }
+
+    let mut lines = vec![
+        Message::new("statistics.total", [stats.total_operations.into()]),
+        Message::new("statistics.average", [stats.average_result.into()]),
+        Message::new("statistics.minimum", [stats.min_result.into()]),
+        Message::new("statistics.maximum", [stats.max_result.into()]),
+    ];
+    let mut rows = Vec::new();
+    for (operation_type, op_stats) in stats.operations() {
+        lines.push(Message::new(
+            "statistics.operation",
+            [
+                operation_type.into(),
+                op_stats.count.into(),
+                op_stats.min_result.into(),
+                op_stats.max_result.into(),
+                op_stats.average_result.into(),
+            ],
+        ));
+        rows.push(serde_json::json!({
+            "operation": operation_type.name(),
+            "count": op_stats.count,
+            "min_result": Lossless(op_stats.min_result),
+            "max_result": Lossless(op_stats.max_result),
+            "average_result": Lossless(op_stats.average_result),
+        }));
     }
+    Ok(Report::new(Message::lines(lines), value).columns(&columns).rows(rows))
 }
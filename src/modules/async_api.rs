@@ -0,0 +1,443 @@
+//! Asynchrone Schnittstelle für den Einsatz in tokio-Anwendungen
+
+use crate::modules::core::{MathUtils, Statistics};
+use crate::modules::error::ProjectError;
+use crate::modules::history::HistoryQuery;
+use crate::modules::observer::{CalculatorObserver, ObserverHandle};
+use crate::modules::shared::SharedCalculator;
+use crate::modules::utils;
+use crate::{Calculator, Operation};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Token, mit dem eine laufende Berechnung abgebrochen werden kann
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Erstellt ein neues, nicht abgebrochenes Token
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fordert den Abbruch aller Berechnungen an, die dieses Token verwenden
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Prüft, ob der Abbruch angefordert wurde
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Bricht die zugehörige Berechnung ab, wenn das wartende Future verworfen wird
+struct CancelOnDrop(CancellationToken);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
+/// Asynchroner Calculator auf Basis von [`SharedCalculator`]
+///
+/// Operationen, die die Sperre des [`SharedCalculator`] brauchen, und
+/// aufwendige Berechnungen laufen im Blocking-Pool von tokio, Dateizugriffe
+/// über `tokio::fs`, damit der Executor auch bei Konkurrenz um die Sperre
+/// nicht blockiert wird.
+///
+/// # Beispiel
+///
+/// ```rust
+/// use rust_project_complete::modules::async_api::{AsyncCalculator, CancellationToken};
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let calc = AsyncCalculator::new();
+/// assert_eq!(calc.add(2.0, 3.0).await.unwrap(), 5.0);
+///
+/// let token = CancellationToken::new();
+/// assert!(calc.is_prime(97, &token).await.unwrap());
+/// # });
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AsyncCalculator {
+    inner: SharedCalculator,
+}
+
+impl From<Calculator> for AsyncCalculator {
+    fn from(calculator: Calculator) -> Self {
+        Self {
+            inner: SharedCalculator::from(calculator),
+        }
+    }
+}
+
+impl From<SharedCalculator> for AsyncCalculator {
+    fn from(inner: SharedCalculator) -> Self {
+        Self { inner }
+    }
+}
+
+impl AsyncCalculator {
+    /// Erstellt einen neuen, leeren asynchronen Calculator
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lädt einen gespeicherten Calculator aus einer JSON-Datei
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn die Datei nicht gelesen oder geparst werden kann.
+    pub async fn load_history(file_path: &str) -> Result<Self, ProjectError> {
+        let content = read_file_content(file_path).await?;
+        let calculator: Calculator = utils::parse_json(&content, file_path)?;
+        Ok(Self::from(calculator))
+    }
+
+    /// Speichert den aktuellen Verlauf als JSON-Datei
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn die Datei nicht geschrieben werden kann.
+    pub async fn save_history(&self, file_path: &str) -> Result<(), ProjectError> {
+        let content = self
+            .locked(|calc| Ok(calc.read(serde_json::to_string_pretty)?))
+            .await?;
+        write_file_content(file_path, &content).await
+    }
+
+    /// Gibt den zugrunde liegenden [`SharedCalculator`] zurück
+    pub fn shared(&self) -> &SharedCalculator {
+        &self.inner
+    }
+
+    /// Addiert zwei Zahlen
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`Calculator::add`].
+    pub async fn add(&self, a: f64, b: f64) -> Result<f64, ProjectError> {
+        self.locked(move |calc| calc.add(a, b)).await
+    }
+
+    /// Subtrahiert zwei Zahlen
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`Calculator::subtract`].
+    pub async fn subtract(&self, a: f64, b: f64) -> Result<f64, ProjectError> {
+        self.locked(move |calc| calc.subtract(a, b)).await
+    }
+
+    /// Multipliziert zwei Zahlen
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`Calculator::multiply`].
+    pub async fn multiply(&self, a: f64, b: f64) -> Result<f64, ProjectError> {
+        self.locked(move |calc| calc.multiply(a, b)).await
+    }
+
+    /// Dividiert zwei Zahlen
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`Calculator::divide`].
+    pub async fn divide(&self, a: f64, b: f64) -> Result<f64, ProjectError> {
+        self.locked(move |calc| calc.divide(a, b)).await
+    }
+
+    /// Berechnet die Fakultät einer Zahl
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`Calculator::factorial`].
+    pub async fn factorial(&self, n: u64) -> Result<u64, ProjectError> {
+        self.locked(move |calc| calc.factorial(n)).await
+    }
+
+    /// Wertet einen arithmetischen Ausdruck aus
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`Calculator::evaluate`].
+    pub async fn evaluate(&self, expression: &str) -> Result<f64, ProjectError> {
+        let expression = expression.to_string();
+        self.locked(move |calc| calc.evaluate(&expression)).await
+    }
+
+    /// Berechnet die Fakultät beliebig großer Zahlen im Blocking-Pool
+    ///
+    /// # Errors
+    ///
+    /// Gibt [`ProjectError::Cancelled`] zurück, wenn `token` abgebrochen wurde.
+    pub async fn big_factorial(
+        &self,
+        n: u64,
+        token: &CancellationToken,
+    ) -> Result<String, ProjectError> {
+        run_blocking(token, move |is_cancelled| {
+            MathUtils::big_factorial_cancellable(n, is_cancelled)
+        })
+        .await
+    }
+
+    /// Prüft im Blocking-Pool, ob eine Zahl eine Primzahl ist
+    ///
+    /// # Errors
+    ///
+    /// Gibt [`ProjectError::Cancelled`] zurück, wenn `token` abgebrochen wurde.
+    pub async fn is_prime(&self, n: u64, token: &CancellationToken) -> Result<bool, ProjectError> {
+        run_blocking(token, move |is_cancelled| {
+            MathUtils::is_prime_cancellable(n, is_cancelled)
+        })
+        .await
+    }
+
+    /// Führt `f` im Blocking-Pool aus, wo das Warten auf die Sperre keinen
+    /// Worker-Thread des Executors belegt
+    async fn locked<T, F>(&self, f: F) -> Result<T, ProjectError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&SharedCalculator) -> Result<T, ProjectError> + Send + 'static,
+    {
+        let inner = self.inner.clone();
+        match tokio::task::spawn_blocking(move || f(&inner)).await {
+            Ok(result) => result,
+            Err(error) if error.is_panic() => std::panic::resume_unwind(error.into_panic()),
+            Err(_) => Err(ProjectError::Cancelled),
+        }
+    }
+
+    /// Gibt die Anzahl der Operationen im Verlauf zurück
+    ///
+    /// # Errors
+    ///
+    /// Gibt [`ProjectError::Cancelled`] zurück, wenn die Laufzeit beendet wird.
+    pub async fn history_count(&self) -> Result<usize, ProjectError> {
+        self.locked(|calc| Ok(calc.history_count())).await
+    }
+
+    /// Gibt eine Momentaufnahme des Verlaufs zurück
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`Calculator::get_history`].
+    #[deprecated(note = "lädt den gesamten Verlauf; `query_history` verwenden")]
+    pub fn get_history(&self) -> Result<Vec<Operation>, ProjectError> {
+        self.inner.query_history(&HistoryQuery::new())
+    }
+
+    /// Gibt die Operationen zurück, die `query` erfüllen
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`Calculator::query_history`].
+    pub async fn query_history(&self, query: &HistoryQuery) -> Result<Vec<Operation>, ProjectError> {
+        let query = query.clone();
+        self.locked(move |calc| calc.query_history(&query)).await
+    }
+
+    /// Gibt eine Momentaufnahme der Statistiken zurück
+    ///
+    /// # Errors
+    ///
+    /// Gibt [`ProjectError::Cancelled`] zurück, wenn die Laufzeit beendet wird.
+    pub async fn statistics(&self) -> Result<Statistics, ProjectError> {
+        self.locked(|calc| Ok(calc.statistics())).await
+    }
+
+    /// Löscht den Verlauf
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`Calculator::clear_history`].
+    pub async fn clear_history(&self) -> Result<(), ProjectError> {
+        self.locked(|calc| calc.clear_history()).await
+    }
+
+    /// Registriert einen Beobachter
+    pub fn add_observer(&self, observer: Arc<dyn CalculatorObserver>) -> ObserverHandle {
+        self.inner.add_observer(observer)
+    }
+
+    /// Entfernt einen Beobachter
+    pub fn remove_observer(&self, handle: ObserverHandle) -> bool {
+        self.inner.remove_observer(handle)
+    }
+}
+
+/// Liest eine Datei asynchron und gibt den Inhalt zurück
+///
+/// # Errors
+///
+/// Gibt einen Fehler zurück, wenn die Datei nicht gelesen werden kann.
+pub async fn read_file_content(file_path: &str) -> Result<String, ProjectError> {
+    tokio::fs::read_to_string(file_path)
+        .await
+        .map_err(|e| ProjectError::io(e, file_path))
+}
+
+/// Schreibt Inhalt asynchron in eine Datei
+///
+/// # Errors
+///
+/// Gibt einen Fehler zurück, wenn die Datei nicht geschrieben werden kann.
+pub async fn write_file_content(file_path: &str, content: &str) -> Result<(), ProjectError> {
+    tokio::fs::write(file_path, content)
+        .await
+        .map_err(|e| ProjectError::io(e, file_path))
+}
+
+/// Führt eine abbrechbare Berechnung im Blocking-Pool aus
+///
+/// Die Berechnung endet, sobald `token` abgebrochen oder das wartende Future
+/// verworfen wird.
+async fn run_blocking<T, F>(token: &CancellationToken, compute: F) -> Result<T, ProjectError>
+where
+    T: Send + 'static,
+    F: FnOnce(&dyn Fn() -> bool) -> Result<T, ProjectError> + Send + 'static,
+{
+    let caller = token.clone();
+    let dropped = CancellationToken::new();
+    let _guard = CancelOnDrop(dropped.clone());
+
+    let handle = tokio::task::spawn_blocking(move || {
+        compute(&|| caller.is_cancelled() || dropped.is_cancelled())
+    });
+
+    match handle.await {
+        Ok(result) => result,
+        Err(error) if error.is_panic() => std::panic::resume_unwind(error.into_panic()),
+        Err(_) => Err(ProjectError::Cancelled),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const LARGEST_U64_PRIME: u64 = 18_446_744_073_709_551_557;
+
+    #[tokio::test]
+    async fn test_async_operations() {
+        let calc = AsyncCalculator::new();
+        assert_eq!(calc.add(2.0, 3.0).await.unwrap(), 5.0);
+        assert_eq!(calc.subtract(5.0, 3.0).await.unwrap(), 2.0);
+        assert_eq!(calc.multiply(4.0, 3.0).await.unwrap(), 12.0);
+        assert_eq!(calc.divide(1.0, 0.0).await, Err(ProjectError::division_by_zero().with_operation("divide", &[1.0, 0.0])));
+        assert_eq!(calc.factorial(5).await.unwrap(), 120);
+        assert_eq!(calc.history_count().await.unwrap(), 4);
+
+        let divisions = HistoryQuery::new().operation_type(crate::OperationType::Divide);
+        assert!(calc.query_history(&divisions).await.unwrap().is_empty());
+        calc.clear_history().await.unwrap();
+        assert!(calc.statistics().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_waiting_for_lock_does_not_block_executor() {
+        let calc = AsyncCalculator::new();
+        let (locked, is_locked) = std::sync::mpsc::channel();
+        let holder = calc.shared().clone();
+        let holder = std::thread::spawn(move || {
+            holder.write(|_| {
+                locked.send(()).unwrap();
+                std::thread::sleep(Duration::from_millis(300));
+            })
+        });
+        is_locked.recv().unwrap();
+
+        // Im einzigen Worker-Thread von `#[tokio::test]` könnte der Timer nicht
+        // auslösen, solange `add` synchron auf die Sperre wartet
+        let result = tokio::time::timeout(Duration::from_millis(50), calc.add(1.0, 2.0)).await;
+        assert!(result.is_err());
+        let result = tokio::time::timeout(Duration::from_millis(50), calc.history_count()).await;
+        assert!(result.is_err());
+
+        holder.join().unwrap();
+        assert_eq!(calc.add(1.0, 2.0).await.unwrap(), 3.0);
+    }
+
+    #[tokio::test]
+    async fn test_save_and_load_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.json");
+        let path = path.to_str().unwrap();
+
+        let calc = AsyncCalculator::new();
+        calc.add(1.0, 2.0).await.unwrap();
+        calc.multiply(3.0, 4.0).await.unwrap();
+        calc.save_history(path).await.unwrap();
+
+        let loaded = AsyncCalculator::load_history(path).await.unwrap();
+        assert_eq!(loaded.history_count().await.unwrap(), 2);
+        assert_eq!(loaded.statistics().await.unwrap().max_result, 12.0);
+    }
+
+    #[tokio::test]
+    async fn test_async_file_io() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("content.txt");
+        let path = path.to_str().unwrap();
+
+        write_file_content(path, "Inhalt").await.unwrap();
+        assert_eq!(read_file_content(path).await.unwrap(), "Inhalt");
+        assert!(matches!(
+            read_file_content(dir.path().join("fehlt").to_str().unwrap()).await,
+            Err(ProjectError::IoError { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_blocking_computations() {
+        let calc = AsyncCalculator::new();
+        let token = CancellationToken::new();
+
+        assert!(calc.is_prime(7919, &token).await.unwrap());
+        assert!(!calc.is_prime(7917, &token).await.unwrap());
+        assert_eq!(calc.big_factorial(25, &token).await.unwrap(), "15511210043330985984000000");
+    }
+
+    #[tokio::test]
+    async fn test_cancel_running_prime_check() {
+        let calc = AsyncCalculator::new();
+        let token = CancellationToken::new();
+
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            canceller.cancel();
+        });
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(10),
+            calc.is_prime(LARGEST_U64_PRIME, &token),
+        )
+        .await
+        .expect("Abbruch wurde nicht rechtzeitig bemerkt");
+        assert_eq!(result, Err(ProjectError::Cancelled));
+    }
+
+    #[tokio::test]
+    async fn test_dropping_future_cancels_computation() {
+        let calc = AsyncCalculator::new();
+        let token = CancellationToken::new();
+
+        let result = tokio::time::timeout(
+            Duration::from_millis(20),
+            calc.big_factorial(1_000_000, &token),
+        )
+        .await;
+
+        // Die Laufzeit wartet beim Beenden auf den Blocking-Pool; ohne Abbruch
+        // würde dieser Test daher nicht enden.
+        assert!(result.is_err());
+        assert!(!token.is_cancelled());
+    }
+}
@@ -0,0 +1,408 @@
+//! Manipulationssicheres Audit-Protokoll der Operationen
+
+use crate::modules::error::ProjectError;
+use crate::modules::history::HistoryStore;
+use crate::modules::i18n::Message;
+use crate::Operation;
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// Vorgänger-Hash des ersten Eintrags
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Ereignis, das im Audit-Protokoll festgehalten wird
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditEvent {
+    /// Eine Operation wurde ausgeführt
+    Operation(Operation),
+    /// Der Verlauf wurde gelöscht; frühere Einträge bleiben erhalten
+    Cleared,
+}
+
+/// Eine Zeile des Audit-Protokolls
+///
+/// `hash` ist der SHA-256-Hash über `previous_hash`, `sequence` und `event`.
+/// Jede Änderung, Entfernung oder Umsortierung eines Eintrags unterbricht
+/// dadurch die Kette ab dieser Stelle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub sequence: u64,
+    pub event: AuditEvent,
+    pub previous_hash: String,
+    pub hash: String,
+}
+
+impl AuditEntry {
+    /// Erstellt den Eintrag, der auf `previous_hash` folgt
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn das Ereignis nicht serialisiert werden kann.
+    pub fn new(
+        sequence: u64,
+        event: AuditEvent,
+        previous_hash: &str,
+    ) -> Result<Self, ProjectError> {
+        let hash = compute_hash(sequence, &serde_json::to_string(&event)?, previous_hash);
+        Ok(Self {
+            sequence,
+            event,
+            previous_hash: previous_hash.to_string(),
+            hash,
+        })
+    }
+}
+
+/// Ereignis eines Eintrags so, wie es in der Datei steht
+///
+/// Der Hash wird über diese Bytes geprüft, damit Einträge älterer Versionen,
+/// die z. B. ±Unendlich noch als `null` schrieben, gültig bleiben.
+#[derive(Deserialize)]
+struct RawEntry<'a> {
+    #[serde(borrow)]
+    event: &'a RawValue,
+}
+
+/// Ergebnis einer erfolgreichen Prüfung
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditReport {
+    /// Anzahl der geprüften Einträge
+    pub entries: u64,
+    /// Hash des letzten Eintrags
+    ///
+    /// Das Abschneiden am Ende des Protokolls lässt sich nur erkennen, wenn
+    /// dieser Wert zusätzlich an anderer Stelle festgehalten wird.
+    pub last_hash: String,
+}
+
+impl Default for AuditReport {
+    fn default() -> Self {
+        Self {
+            entries: 0,
+            last_hash: GENESIS_HASH.to_string(),
+        }
+    }
+}
+
+/// Prüft die Hash-Kette eines Audit-Protokolls
+///
+/// # Errors
+///
+/// Gibt [`ProjectError::AuditViolation`] mit der Nummer des ersten fehlerhaften
+/// Eintrags zurück, wenn ein Eintrag verändert, entfernt oder umsortiert wurde.
+///
+/// # Beispiel
+///
+/// ```rust
+/// use rust_project_complete::modules::audit::{verify_audit_log, AuditHistoryStore};
+/// use rust_project_complete::Calculator;
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let path = dir.path().join("audit.jsonl");
+/// let path = path.to_str().unwrap();
+///
+/// let mut calc = Calculator::with_store(Box::new(AuditHistoryStore::open(path).unwrap())).unwrap();
+/// calc.add(1.0, 2.0).unwrap();
+///
+/// assert_eq!(verify_audit_log(path).unwrap().entries, 1);
+/// ```
+pub fn verify_audit_log(file_path: &str) -> Result<AuditReport, ProjectError> {
+    read_chain(Path::new(file_path), &mut |_| ())
+}
+
+/// Liest alle Einträge, prüft dabei die Kette und übergibt sie an `visit`
+fn read_chain(
+    path: &Path,
+    visit: &mut dyn FnMut(&AuditEntry),
+) -> Result<AuditReport, ProjectError> {
+    let reader = BufReader::new(File::open(path).map_err(|e| ProjectError::io(e, path))?);
+    let mut report = AuditReport::default();
+
+    for line in reader.lines() {
+        let line = line?;
+        let expected = report.entries + 1;
+        let violation = |reason: Message| ProjectError::AuditViolation {
+            entry: expected,
+            reason,
+        };
+
+        let unreadable =
+            |e: serde_json::Error| violation(Message::new("audit.unreadable", [e.to_string().into()]));
+        let entry: AuditEntry = serde_json::from_str(&line).map_err(unreadable)?;
+        let raw: RawEntry = serde_json::from_str(&line).map_err(unreadable)?;
+        if entry.sequence != expected {
+            return Err(violation(Message::new(
+                "audit.sequence",
+                [expected.into(), entry.sequence.into()],
+            )));
+        }
+        if entry.previous_hash != report.last_hash {
+            return Err(violation(Message::new("audit.previous_hash", [])));
+        }
+        if entry.hash != compute_hash(entry.sequence, raw.event.get(), &entry.previous_hash) {
+            return Err(violation(Message::new("audit.modified", [])));
+        }
+
+        visit(&entry);
+        report.entries = entry.sequence;
+        report.last_hash = entry.hash;
+    }
+    Ok(report)
+}
+
+/// Hash über Vorgänger, Sequenznummer und das Ereignis als kompaktes JSON
+fn compute_hash(sequence: u64, event_json: &str, previous_hash: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(previous_hash.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(sequence.to_string().as_bytes());
+    hasher.update(b"\n");
+    hasher.update(event_json.as_bytes());
+
+    let mut hex = String::with_capacity(64);
+    for byte in hasher.finalize() {
+        let _ = write!(hex, "{:02x}", byte);
+    }
+    hex
+}
+
+/// Verlaufsspeicher, der jede Operation in ein verkettetes Audit-Protokoll schreibt
+///
+/// Das Protokoll wird nur angehängt. Auch [`HistoryStore::clear`] löscht keine
+/// Einträge, sondern vermerkt das Löschen; danach liefert der Speicher nur die
+/// Operationen seit dem letzten Löschen.
+#[derive(Debug)]
+pub struct AuditHistoryStore {
+    path: PathBuf,
+    file: File,
+    last: AuditReport,
+    cleared_at: u64,
+    len: usize,
+}
+
+impl AuditHistoryStore {
+    /// Öffnet oder erstellt ein Audit-Protokoll
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn die Datei nicht geöffnet werden kann oder
+    /// die Kette bereits unterbrochen ist.
+    pub fn open(file_path: &str) -> Result<Self, ProjectError> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(file_path)
+            .map_err(|e| ProjectError::io(e, file_path))?;
+        let path = PathBuf::from(file_path);
+
+        let mut cleared_at = 0;
+        let mut len = 0;
+        let last = read_chain(&path, &mut |entry| match entry.event {
+            AuditEvent::Operation(_) => len += 1,
+            AuditEvent::Cleared => {
+                cleared_at = entry.sequence;
+                len = 0;
+            }
+        })?;
+
+        Ok(Self {
+            path,
+            file,
+            last,
+            cleared_at,
+            len,
+        })
+    }
+
+    /// Gibt den Pfad des Protokolls zurück
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Gibt Anzahl und Hash des letzten geschriebenen Eintrags zurück
+    pub fn head(&self) -> &AuditReport {
+        &self.last
+    }
+
+    fn write_event(&mut self, event: AuditEvent) -> Result<u64, ProjectError> {
+        let entry = AuditEntry::new(self.last.entries + 1, event, &self.last.last_hash)?;
+        let mut line = serde_json::to_string(&entry)?;
+        line.push('\n');
+        self.file.write_all(line.as_bytes())?;
+        self.file.flush()?;
+
+        self.last.entries = entry.sequence;
+        self.last.last_hash = entry.hash;
+        Ok(entry.sequence)
+    }
+}
+
+impl HistoryStore for AuditHistoryStore {
+    fn append(&mut self, operation: &Operation) -> Result<(), ProjectError> {
+        self.write_event(AuditEvent::Operation(operation.clone()))?;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn scan(&self, visit: &mut dyn FnMut(&Operation)) -> Result<(), ProjectError> {
+        read_chain(&self.path, &mut |entry| match &entry.event {
+            AuditEvent::Operation(operation) if entry.sequence > self.cleared_at => {
+                visit(operation)
+            }
+            _ => (),
+        })?;
+        Ok(())
+    }
+
+    fn clear(&mut self) -> Result<(), ProjectError> {
+        self.cleared_at = self.write_event(AuditEvent::Cleared)?;
+        self.len = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Calculator;
+    use crate::modules::history::HistoryQuery;
+    use std::fs;
+
+    fn audited(path: &Path) -> Calculator {
+        let store = AuditHistoryStore::open(path.to_str().unwrap()).unwrap();
+        Calculator::with_store(Box::new(store)).unwrap()
+    }
+
+    fn write_log(path: &Path) -> Vec<String> {
+        let mut calc = audited(path);
+        calc.add(1.0, 2.0).unwrap();
+        calc.multiply(3.0, 4.0).unwrap();
+        calc.divide(10.0, 4.0).unwrap();
+        fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn violation_at(path: &Path) -> u64 {
+        match verify_audit_log(path.to_str().unwrap()) {
+            Err(ProjectError::AuditViolation { entry, .. }) => entry,
+            other => panic!("Expected AuditViolation, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_intact_log_verifies() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        write_log(&path);
+
+        let report = verify_audit_log(path.to_str().unwrap()).unwrap();
+        assert_eq!(report.entries, 3);
+        assert_ne!(report.last_hash, GENESIS_HASH);
+        assert_eq!(audited(&path).history_count(), 3);
+    }
+
+    #[test]
+    fn test_detects_altered_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let mut lines = write_log(&path);
+        lines[1] = lines[1].replace("\"result\":12.0", "\"result\":13.0");
+        fs::write(&path, lines.join("\n")).unwrap();
+
+        assert_eq!(violation_at(&path), 2);
+    }
+
+    #[test]
+    fn test_detects_removed_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let mut lines = write_log(&path);
+        lines.remove(0);
+        fs::write(&path, lines.join("\n")).unwrap();
+
+        assert_eq!(violation_at(&path), 1);
+    }
+
+    #[test]
+    fn test_detects_reordered_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let mut lines = write_log(&path);
+        lines.swap(1, 2);
+        fs::write(&path, lines.join("\n")).unwrap();
+
+        assert_eq!(violation_at(&path), 2);
+    }
+
+    #[test]
+    fn test_broken_log_cannot_be_extended() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let mut lines = write_log(&path);
+        lines[2] = "{}".to_string();
+        fs::write(&path, lines.join("\n")).unwrap();
+
+        assert!(matches!(
+            AuditHistoryStore::open(path.to_str().unwrap()),
+            Err(ProjectError::AuditViolation { entry: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn test_non_finite_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let mut calc = audited(&path);
+        calc.multiply(1e200, -1e200).unwrap();
+
+        assert_eq!(verify_audit_log(path.to_str().unwrap()).unwrap().entries, 1);
+        let history = audited(&path).query_history(&HistoryQuery::new()).unwrap();
+        assert_eq!(history[0].result, f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_verifies_entries_with_null_results() {
+        // So schrieben ältere Versionen ±Unendlich und NaN
+        let event = r#"{"operation":{"id":1,"operation_type":"Multiply","operands":[1e200,1e200],"result":null,"timestamp":0}}"#;
+        let hash = compute_hash(1, event, GENESIS_HASH);
+        let line = format!(
+            r#"{{"sequence":1,"event":{},"previous_hash":"{}","hash":"{}"}}"#,
+            event, GENESIS_HASH, hash
+        );
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        fs::write(&path, line + "\n").unwrap();
+
+        assert_eq!(verify_audit_log(path.to_str().unwrap()).unwrap().entries, 1);
+        assert!(audited(&path).query_history(&HistoryQuery::new()).unwrap()[0].result.is_nan());
+    }
+
+    #[test]
+    fn test_clear_is_recorded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        write_log(&path);
+
+        let mut calc = audited(&path);
+        calc.clear_history().unwrap();
+        calc.subtract(5.0, 1.0).unwrap();
+
+        assert_eq!(verify_audit_log(path.to_str().unwrap()).unwrap().entries, 5);
+        let calc = audited(&path);
+        assert_eq!(calc.history_count(), 1);
+        assert_eq!(calc.query_history(&HistoryQuery::new()).unwrap()[0].id, 4);
+    }
+}
@@ -0,0 +1,561 @@
+//! Stapelverarbeitung von Operationen aus CSV- oder JSONL-Strömen
+
+use crate::modules::diagnostic::Diagnostic;
+use crate::modules::error::ProjectError;
+use crate::modules::i18n::Message;
+use crate::modules::logging;
+use crate::modules::numeric::NumericPolicy;
+use crate::modules::operations::OperationRegistry;
+use crate::modules::shared::SharedCalculator;
+use crate::modules::utils::csv_escape;
+use crate::Calculator;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::{BufRead, Write};
+use std::ops::Range;
+use std::path::Path;
+use std::str::FromStr;
+use std::thread;
+
+/// Kopfzeile der CSV-Ausgabe
+const CSV_HEADER: &str = "line,op,a,b,result,error_code,error_message";
+
+/// Ein- und Ausgabeformat
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchFormat {
+    /// Zeilen `op,a,b`, optional mit Kopfzeile
+    Csv,
+    /// Ein JSON-Objekt `{"op": ..., "a": ..., "b": ...}` je Zeile
+    Jsonl,
+}
+
+impl BatchFormat {
+    /// Leitet das Format aus der Dateiendung ab; `.jsonl` und `.ndjson` sind JSONL
+    pub fn from_path(path: &str) -> Option<Self> {
+        match Path::new(path).extension()?.to_str()? {
+            "csv" => Some(BatchFormat::Csv),
+            "jsonl" | "ndjson" => Some(BatchFormat::Jsonl),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for BatchFormat {
+    type Err = ProjectError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(BatchFormat::Csv),
+            "jsonl" => Ok(BatchFormat::Jsonl),
+            other => Err(ProjectError::invalid_input(Message::new(
+                "batch.unknown_format",
+                [other.into()],
+            ))),
+        }
+    }
+}
+
+/// Operation für Fakultäten; alle anderen Namen werden im [`OperationRegistry`] gesucht
+const FACTORIAL: &str = "factorial";
+
+/// Ergebnis eines Stapellaufs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BatchSummary {
+    /// Anzahl der verarbeiteten Datensätze
+    pub records: u64,
+    /// Davon fehlgeschlagene Datensätze
+    pub errors: u64,
+}
+
+/// Führt Datensätze aus einem Strom aus und schreibt je Datensatz eine Ergebniszeile
+///
+/// Die Eingabe wird in Blöcken gelesen, sodass auch sehr große Dateien nicht
+/// vollständig in den Speicher geladen werden. Mit [`Batch::jobs`] werden die
+/// Datensätze eines Blocks parallel verarbeitet; die Ausgabe behält die
+/// Reihenfolge der Eingabe.
+///
+/// Ohne [`Batch::calculator`] rechnet jeder Block mit einem eigenen, danach
+/// verworfenen [`Calculator`], damit der Verlauf nicht unbegrenzt wächst.
+///
+/// Als Operation sind `factorial` und alle Namen aus dem [`OperationRegistry`]
+/// erlaubt, siehe [`Batch::operations`].
+///
+/// # Beispiel
+///
+/// ```rust
+/// use rust_project_complete::modules::batch::{Batch, BatchFormat};
+///
+/// let mut output = Vec::new();
+/// let summary = Batch::new(BatchFormat::Csv)
+///     .run("add,2,3\ndivide,1,0\n".as_bytes(), &mut output)
+///     .unwrap();
+///
+/// assert_eq!(summary.errors, 1);
+/// assert!(String::from_utf8(output).unwrap().contains("1,add,2,3,5,,"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Batch {
+    format: BatchFormat,
+    jobs: usize,
+    chunk_size: usize,
+    calculator: Option<SharedCalculator>,
+    operations: OperationRegistry,
+    numeric_policy: NumericPolicy,
+}
+
+impl Batch {
+    /// Erstellt einen Stapellauf für das angegebene Format
+    pub fn new(format: BatchFormat) -> Self {
+        Self {
+            format,
+            jobs: 1,
+            chunk_size: 8192,
+            calculator: None,
+            operations: OperationRegistry::new(),
+            numeric_policy: NumericPolicy::default(),
+        }
+    }
+
+    /// Anzahl paralleler Threads (mindestens 1)
+    pub fn jobs(mut self, jobs: usize) -> Self {
+        self.jobs = jobs.max(1);
+        self
+    }
+
+    /// Anzahl der Zeilen, die auf einmal gelesen werden (mindestens 1)
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Zeichnet alle Operationen im Verlauf dieses Calculators auf
+    ///
+    /// Es gelten dann die Operationen und die [`NumericPolicy`] dieses Calculators,
+    /// nicht [`Batch::operations`] und [`Batch::numeric_policy`].
+    pub fn calculator(mut self, calculator: SharedCalculator) -> Self {
+        self.calculator = Some(calculator);
+        self
+    }
+
+    /// Operationen, die ohne [`Batch::calculator`] zur Verfügung stehen
+    pub fn operations(mut self, operations: OperationRegistry) -> Self {
+        self.operations = operations;
+        self
+    }
+
+    /// Richtlinie für NaN und ±Unendlich ohne [`Batch::calculator`]
+    pub fn numeric_policy(mut self, policy: NumericPolicy) -> Self {
+        self.numeric_policy = policy;
+        self
+    }
+
+    /// Verarbeitet `input` vollständig und schreibt die Ergebnisse nach `output`
+    ///
+    /// Fehler einzelner Datensätze stehen in der Ausgabe und beenden den Lauf nicht.
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn nicht gelesen oder geschrieben werden kann.
+    pub fn run<R: BufRead, W: Write>(
+        &self,
+        mut input: R,
+        mut output: W,
+    ) -> Result<BatchSummary, ProjectError> {
+        if self.format == BatchFormat::Csv {
+            writeln!(output, "{}", CSV_HEADER)?;
+        }
+
+        let mut summary = BatchSummary::default();
+        let mut line_number = 0;
+        let mut lines = Vec::with_capacity(self.chunk_size);
+        loop {
+            lines.clear();
+            while lines.len() < self.chunk_size {
+                let mut line = String::new();
+                if input.read_line(&mut line)? == 0 {
+                    break;
+                }
+                line_number += 1;
+                let skip = line.trim().is_empty()
+                    || (line_number == 1
+                        && self.format == BatchFormat::Csv
+                        && is_csv_header(&line));
+                if !skip {
+                    lines.push((line_number, line));
+                }
+            }
+            if lines.is_empty() {
+                break;
+            }
+
+            for outcome in self.process_chunk(&lines) {
+                summary.records += 1;
+                if outcome.failed {
+                    summary.errors += 1;
+                }
+                output.write_all(outcome.text.as_bytes())?;
+            }
+        }
+        output.flush()?;
+        Ok(summary)
+    }
+
+    fn process_chunk(&self, lines: &[(u64, String)]) -> Vec<Outcome> {
+        if self.jobs == 1 {
+            return self.process(lines);
+        }
+        let part_size = lines.len().div_ceil(self.jobs);
+        // Die Threads übernehmen die Korrelations-ID des Aufrufers
+        let correlation_id = logging::correlation_id();
+        thread::scope(|scope| {
+            let workers: Vec<_> = lines
+                .chunks(part_size)
+                .map(|part| {
+                    let correlation_id = &correlation_id;
+                    scope.spawn(move || {
+                        logging::with_correlation_id(correlation_id, || self.process(part))
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| match worker.join() {
+                    Ok(outcomes) => outcomes,
+                    Err(panic) => std::panic::resume_unwind(panic),
+                })
+                .collect()
+        })
+    }
+
+    fn process(&self, lines: &[(u64, String)]) -> Vec<Outcome> {
+        let mut target = match &self.calculator {
+            Some(shared) => Target::Shared(shared),
+            None => {
+                let mut calculator = Calculator::new();
+                calculator.set_operations(self.operations.clone());
+                calculator.set_numeric_policy(self.numeric_policy);
+                Target::Local(Box::new(calculator))
+            }
+        };
+        lines
+            .iter()
+            .map(|(line_number, line)| match self.parse(*line_number, line) {
+                Ok(record) => {
+                    let result = target.execute(&record);
+                    self.format_outcome(*line_number, Some(record), result)
+                }
+                Err(error) => self.format_outcome(*line_number, None, Err(error)),
+            })
+            .collect()
+    }
+
+    /// Liest einen Datensatz; Fehler zeigen auf die Stelle in Zeile `line_number`
+    fn parse(&self, line_number: u64, line: &str) -> Result<Record, ProjectError> {
+        let at = |diagnostic: Diagnostic| diagnostic.starting_at_line(line_number as usize);
+        match self.format {
+            BatchFormat::Csv => {
+                let fields = csv_fields(line);
+                let (op, a, b) = match fields.as_slice() {
+                    [op, a] => (op, a, None),
+                    [op, a, ("", _)] => (op, a, None),
+                    [op, a, b] => (op, a, Some(b)),
+                    _ => {
+                        let diagnostic = Diagnostic::new(Message::new("batch.fields", []))
+                            .with_span(line, 0..line.len());
+                        return Err(at(diagnostic).into());
+                    }
+                };
+                if op.0.is_empty() {
+                    let diagnostic = Diagnostic::new(Message::new("batch.missing_operation", [])).with_span(line, op.1.clone());
+                    return Err(at(diagnostic).into());
+                }
+                let number = |(field, range): &(&str, Range<usize>)| {
+                    field.parse::<f64>().map_err(|_| {
+                        let message = Message::new("expression.invalid_number", [(*field).into()]);
+                        ProjectError::from(at(Diagnostic::new(message).with_span(line, range.clone())))
+                    })
+                };
+                Ok(Record {
+                    op: op.0.to_string(),
+                    a: number(a)?,
+                    b: b.map(number).transpose()?,
+                })
+            }
+            BatchFormat::Jsonl => serde_json::from_str(line)
+                .map_err(|e| at(Diagnostic::from_json(&e, Some(line))).into()),
+        }
+    }
+
+    fn format_outcome(
+        &self,
+        line: u64,
+        record: Option<Record>,
+        result: Result<Number, ProjectError>,
+    ) -> Outcome {
+        let (result, error) = match result {
+            Ok(value) => (Some(value), None),
+            Err(error) => (None, Some(error)),
+        };
+        let failed = error.is_some();
+
+        let text = match self.format {
+            BatchFormat::Csv => {
+                let field = |value: Option<String>| value.unwrap_or_default();
+                format!(
+                    "{},{},{},{},{},{},{}\n",
+                    line,
+                    field(record.as_ref().map(|r| csv_escape(&r.op))),
+                    field(record.as_ref().map(|r| r.a.to_string())),
+                    field(record.as_ref().and_then(|r| r.b).map(|b| b.to_string())),
+                    field(result.map(|value| value.to_string())),
+                    field(error.as_ref().map(|e| e.code().to_string())),
+                    field(error.as_ref().map(|e| csv_escape(&e.to_string()))),
+                )
+            }
+            BatchFormat::Jsonl => {
+                let output = OutputRecord {
+                    line,
+                    record,
+                    result,
+                    error: error.map(|error| OutputError {
+                        code: error.code(),
+                        message: error.to_string(),
+                    }),
+                };
+                // Die Ausgabetypen lassen sich immer serialisieren
+                let mut text = serde_json::to_string(&output).unwrap_or_default();
+                text.push('\n');
+                text
+            }
+        };
+        Outcome { text, failed }
+    }
+}
+
+/// Calculator, über den ein Teil eines Blocks rechnet
+enum Target<'a> {
+    Local(Box<Calculator>),
+    Shared(&'a SharedCalculator),
+}
+
+impl Target<'_> {
+    fn execute(&mut self, record: &Record) -> Result<Number, ProjectError> {
+        let a = record.a;
+        if record.op == FACTORIAL {
+            if record.b.is_some() {
+                return Err(ProjectError::invalid_input(Message::new(
+                    "batch.factorial_operand",
+                    [],
+                )));
+            }
+            if a < 0.0 || a.fract() != 0.0 || a > u64::MAX as f64 {
+                return Err(ProjectError::invalid_input(Message::new(
+                    "operation.not_natural",
+                    [a.into()],
+                )));
+            }
+        }
+
+        let operands: Vec<f64> = std::iter::once(a).chain(record.b).collect();
+        let execute = |calc: &mut Calculator| match record.op.as_str() {
+            FACTORIAL => calc.factorial(a as u64).map(Number::Integer),
+            name => calc.apply(name, &operands).map(Number::Float),
+        };
+        match self {
+            Target::Local(calc) => execute(calc),
+            Target::Shared(calc) => calc.write(execute),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Record {
+    op: String,
+    a: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    b: Option<f64>,
+}
+
+#[derive(Serialize)]
+struct OutputRecord {
+    line: u64,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    record: Option<Record>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Number>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<OutputError>,
+}
+
+/// Ergebnis; Fakultäten bleiben ganzzahlig, NaN wird in JSON zu `null`
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(untagged)]
+enum Number {
+    Float(f64),
+    Integer(u64),
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Number::Float(value) => value.fmt(f),
+            Number::Integer(value) => value.fmt(f),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct OutputError {
+    code: &'static str,
+    message: String,
+}
+
+struct Outcome {
+    text: String,
+    failed: bool,
+}
+
+fn is_csv_header(line: &str) -> bool {
+    line.split(',')
+        .next()
+        .is_some_and(|field| field.trim().eq_ignore_ascii_case("op"))
+}
+
+/// Felder einer CSV-Zeile ohne Leerraum, mit ihrem Byte-Bereich in der Zeile
+fn csv_fields(line: &str) -> Vec<(&str, Range<usize>)> {
+    let mut start = 0;
+    line.split(',')
+        .map(|field| {
+            let offset = start + field.len() - field.trim_start().len();
+            start += field.len() + 1;
+            let field = field.trim();
+            (field, offset..offset + field.len())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(batch: &Batch, input: &str) -> (BatchSummary, String) {
+        let mut output = Vec::new();
+        let summary = batch.run(input.as_bytes(), &mut output).unwrap();
+        (summary, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_csv() {
+        let input = "op,a,b\nadd,2,3\n\ndivide,1,0\nfactorial,5\npower,2,3\nadd,x,1\n";
+        let (summary, output) = run(&Batch::new(BatchFormat::Csv), input);
+
+        assert_eq!(
+            summary,
+            BatchSummary {
+                records: 5,
+                errors: 3
+            }
+        );
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(lines[1], "2,add,2,3,5,,");
+        assert_eq!(
+            lines[2],
+            "4,divide,1,0,,division_by_zero,Division durch Null ist nicht erlaubt"
+        );
+        assert_eq!(lines[3], "5,factorial,5,,120,,");
+        assert_eq!(
+            lines[4],
+            "6,power,2,3,,invalid_input,Ungültige Eingabe: Unbekannte Operation 'power'"
+        );
+        assert_eq!(
+            lines[5],
+            "7,,,,,parse_error,\"Parsing-Fehler: Ungültige Zahl 'x' (Zeile 7, Spalte 5)\""
+        );
+    }
+
+    #[test]
+    fn test_custom_operations() {
+        use crate::modules::operations::FnOperator;
+        use std::sync::Arc;
+
+        let mut operations = OperationRegistry::new();
+        operations
+            .register(Arc::new(FnOperator::new("power", 2, |x| {
+                Ok(x[0].powf(x[1]))
+            })))
+            .unwrap();
+        operations
+            .register(Arc::new(FnOperator::new("negate", 1, |x| Ok(-x[0]))))
+            .unwrap();
+        let batch = Batch::new(BatchFormat::Csv).operations(operations);
+        let (summary, output) = run(
+            &batch,
+            "power,2,10
+negate,4
+negate,4,1
+",
+        );
+
+        assert_eq!(summary.errors, 1);
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines[1], "1,power,2,10,1024,,");
+        assert_eq!(lines[2], "2,negate,4,,-4,,");
+        assert!(lines[3].contains("'negate' erwartet 1 Operanden, erhalten 2"));
+    }
+
+    #[test]
+    fn test_jsonl() {
+        let input = concat!(
+            "{\"op\":\"multiply\",\"a\":2,\"b\":4}\n",
+            "{\"op\":\"divide\",\"a\":1,\"b\":0}\n",
+            "{\"op\":\"add\",\"a\":1}\n",
+            "kein json\n",
+        );
+        let (summary, output) = run(&Batch::new(BatchFormat::Jsonl), input);
+
+        assert_eq!(
+            summary,
+            BatchSummary {
+                records: 4,
+                errors: 3
+            }
+        );
+        let records: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            records[0],
+            serde_json::json!({"line": 1, "op": "multiply", "a": 2.0, "b": 4.0, "result": 8.0})
+        );
+        assert_eq!(records[1]["error"]["code"], "division_by_zero");
+        assert_eq!(records[1]["op"], "divide");
+        assert_eq!(records[2]["error"]["code"], "invalid_input");
+        assert_eq!(records[3]["error"]["code"], "parse_error");
+        assert!(records[3].get("op").is_none());
+    }
+
+    #[test]
+    fn test_parallel_preserves_order() {
+        let input: String = (0..1000).map(|i| format!("add,{},1\n", i)).collect();
+        let sequential = run(&Batch::new(BatchFormat::Csv), &input);
+        let parallel = run(&Batch::new(BatchFormat::Csv).jobs(4).chunk_size(64), &input);
+
+        assert_eq!(sequential, parallel);
+        assert_eq!(parallel.0.records, 1000);
+    }
+
+    #[test]
+    fn test_records_into_shared_calculator() {
+        let calculator = SharedCalculator::new();
+        let batch = Batch::new(BatchFormat::Csv)
+            .calculator(calculator.clone())
+            .jobs(2)
+            .chunk_size(2);
+        run(&batch, "add,1,2\nmultiply,2,3\ndivide,1,0\nsubtract,5,1\n");
+
+        assert_eq!(calculator.history_count(), 3);
+    }
+}
@@ -1,6 +1,9 @@
 //! Kernfunktionalität des Projekts
 
 use crate::modules::error::ProjectError;
+use crate::modules::i18n::{Locale, Message};
+use crate::modules::numeric::{lossless, NumericPolicy};
+use crate::{Operation, OperationType};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -10,6 +13,12 @@ pub struct Config {
     pub max_history_size: usize,
     pub precision: u32,
     pub debug_mode: bool,
+    /// Sprache der Meldungen; ohne Angabe gilt die Umgebung (`LANG`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<Locale>,
+    /// Umgang mit NaN und ±Unendlich; ohne Angabe gilt `permissive`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub numeric_policy: Option<NumericPolicy>,
 }
 
 impl Default for Config {
@@ -18,6 +27,49 @@ impl Default for Config {
             max_history_size: 1000,
             precision: 2,
             debug_mode: false,
+            locale: None,
+            numeric_policy: None,
+        }
+    }
+}
+
+/// Statistiken für eine einzelne Operationsart
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperationStatistics {
+    pub count: u64,
+    #[serde(with = "lossless")]
+    pub average_result: f64,
+    #[serde(with = "lossless")]
+    pub min_result: f64,
+    #[serde(with = "lossless")]
+    pub max_result: f64,
+}
+
+impl Default for OperationStatistics {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            average_result: 0.0,
+            min_result: f64::MAX,
+            max_result: f64::MIN,
+        }
+    }
+}
+
+impl OperationStatistics {
+    /// Nimmt ein Ergebnis in die laufenden Kennzahlen auf
+    fn record(&mut self, result: f64) {
+        self.count += 1;
+
+        if self.count == 1 {
+            self.average_result = result;
+            self.min_result = result;
+            self.max_result = result;
+        } else {
+            let old_sum = self.average_result * (self.count - 1) as f64;
+            self.average_result = (old_sum + result) / self.count as f64;
+            self.min_result = self.min_result.min(result);
+            self.max_result = self.max_result.max(result);
         }
     }
 }
@@ -26,9 +78,12 @@ impl Default for Config {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Statistics {
     pub total_operations: u64,
-    pub operation_counts: HashMap<String, u64>,
+    pub by_operation: HashMap<OperationType, OperationStatistics>,
+    #[serde(with = "lossless")]
     pub average_result: f64,
+    #[serde(with = "lossless")]
     pub min_result: f64,
+    #[serde(with = "lossless")]
     pub max_result: f64,
 }
 
@@ -36,7 +91,7 @@ impl Default for Statistics {
     fn default() -> Self {
         Self {
             total_operations: 0,
-            operation_counts: HashMap::new(),
+            by_operation: HashMap::new(),
             average_result: 0.0,
             min_result: f64::MAX,
             max_result: f64::MIN,
@@ -50,12 +105,26 @@ impl Statistics {
         Self::default()
     }
 
+    /// Berechnet die Statistiken für einen vorhandenen Verlauf
+    pub fn from_history(history: &[Operation]) -> Self {
+        let mut stats = Self::new();
+        for operation in history {
+            stats.record(operation);
+        }
+        stats
+    }
+
+    /// Nimmt eine Operation aus dem Verlauf in die Statistiken auf
+    pub fn record(&mut self, operation: &Operation) {
+        self.add_operation(operation.operation_type.clone(), operation.result);
+    }
+
     /// Fügt eine Operation zu den Statistiken hinzu
-    pub fn add_operation(&mut self, operation_type: &str, result: f64) {
+    pub fn add_operation(&mut self, operation_type: OperationType, result: f64) {
         self.total_operations += 1;
-        
-        *self.operation_counts.entry(operation_type.to_string()).or_insert(0) += 1;
-        
+
+        self.by_operation.entry(operation_type).or_default().record(result);
+
         if self.total_operations == 1 {
             self.average_result = result;
             self.min_result = result;
@@ -64,7 +133,7 @@ impl Statistics {
             // Berechne neuen Durchschnitt
             let old_sum = self.average_result * (self.total_operations - 1) as f64;
             self.average_result = (old_sum + result) / self.total_operations as f64;
-            
+
             // Aktualisiere Min/Max
             if result < self.min_result {
                 self.min_result = result;
@@ -76,8 +145,23 @@ impl Statistics {
     }
 
     /// Gibt die Anzahl der Operationen eines bestimmten Typs zurück
-    pub fn get_operation_count(&self, operation_type: &str) -> u64 {
-        self.operation_counts.get(operation_type).copied().unwrap_or(0)
+    pub fn get_operation_count(&self, operation_type: &OperationType) -> u64 {
+        self.by_operation.get(operation_type).map_or(0, |stats| stats.count)
+    }
+
+    /// Gibt die Statistiken für einen bestimmten Operationstyp zurück
+    pub fn get_operation_statistics(
+        &self,
+        operation_type: &OperationType,
+    ) -> Option<&OperationStatistics> {
+        self.by_operation.get(operation_type)
+    }
+
+    /// Gibt die Statistiken je Operationstyp in fester Reihenfolge zurück
+    pub fn operations(&self) -> Vec<(&OperationType, &OperationStatistics)> {
+        let mut entries: Vec<_> = self.by_operation.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        entries
     }
 
     /// Prüft, ob Statistiken leer sind
@@ -91,6 +175,9 @@ impl Statistics {
     }
 }
 
+/// Anzahl der Schleifendurchläufe zwischen zwei Abbruchprüfungen
+const CANCEL_CHECK_INTERVAL: usize = 1 << 12;
+
 /// Mathematische Hilfsfunktionen
 pub struct MathUtils;
 
@@ -107,88 +194,166 @@ impl MathUtils {
     /// Berechnet das kleinste gemeinsame Vielfache
     pub fn lcm(a: u64, b: u64) -> Result<u64, ProjectError> {
         if a == 0 || b == 0 {
-            return Err(ProjectError::InvalidInput("LCM von Null ist nicht definiert".to_string()));
+            return Err(ProjectError::invalid_input(Message::new("math.lcm_of_zero", []))
+                .with_operation("lcm", &[a as f64, b as f64]));
         }
         
         let gcd = Self::gcd(a, b);
         let result = (a / gcd).checked_mul(b)
-            .ok_or_else(|| ProjectError::OverflowError("LCM Overflow".to_string()))?;
+            .ok_or_else(|| {
+                ProjectError::overflow(Message::new("math.lcm_overflow", [])).with_operation("lcm", &[a as f64, b as f64])
+            })?;
         
         Ok(result)
     }
 
     /// Prüft, ob eine Zahl eine Primzahl ist
     pub fn is_prime(n: u64) -> bool {
+        match Self::is_prime_cancellable(n, &|| false) {
+            Ok(result) => result,
+            Err(_) => unreachable!("Primzahltest ohne Abbruch kann nicht fehlschlagen"),
+        }
+    }
+
+    /// Prüft, ob eine Zahl eine Primzahl ist, und bricht ab, sobald `is_cancelled` `true` liefert
+    ///
+    /// # Errors
+    ///
+    /// Gibt [`ProjectError::Cancelled`] zurück, wenn die Prüfung abgebrochen wurde.
+    pub fn is_prime_cancellable(
+        n: u64,
+        is_cancelled: &dyn Fn() -> bool,
+    ) -> Result<bool, ProjectError> {
         if n < 2 {
-            return false;
+            return Ok(false);
         }
         if n == 2 {
-            return true;
+            return Ok(true);
         }
-        if n % 2 == 0 {
-            return false;
+        if n.is_multiple_of(2) {
+            return Ok(false);
         }
-        
+
         let sqrt_n = (n as f64).sqrt() as u64;
-        for i in (3..=sqrt_n).step_by(2) {
-            if n % i == 0 {
-                return false;
+        for (step, i) in (3..=sqrt_n).step_by(2).enumerate() {
+            if step % CANCEL_CHECK_INTERVAL == 0 && is_cancelled() {
+                return Err(ProjectError::Cancelled);
+            }
+            if n.is_multiple_of(i) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Größtes `n`, das Server und RPC für [`MathUtils::big_factorial`] annehmen
+    ///
+    /// `10000!` hat 35660 Stellen und ist in Millisekunden berechnet; der
+    /// Aufwand wächst quadratisch mit `n`.
+    pub const BIG_FACTORIAL_MAX: u64 = 10_000;
+
+    /// Prüft, ob `n` höchstens [`MathUtils::BIG_FACTORIAL_MAX`] ist
+    ///
+    /// # Errors
+    ///
+    /// Gibt [`ProjectError::InvalidInput`] zurück, wenn `n` zu groß ist.
+    pub fn check_big_factorial(n: u64) -> Result<(), ProjectError> {
+        if n > Self::BIG_FACTORIAL_MAX {
+            return Err(ProjectError::invalid_input(Message::new(
+                "operation.big_factorial_limit",
+                [Self::BIG_FACTORIAL_MAX.into(), n.into()],
+            )));
+        }
+        Ok(())
+    }
+
+    /// Berechnet die Fakultät beliebig großer Zahlen als Dezimalzeichenkette
+    ///
+    /// # Beispiel
+    ///
+    /// ```rust
+    /// use rust_project_complete::modules::core::MathUtils;
+    ///
+    /// assert_eq!(MathUtils::big_factorial(25), "15511210043330985984000000");
+    /// ```
+    pub fn big_factorial(n: u64) -> String {
+        match Self::big_factorial_cancellable(n, &|| false) {
+            Ok(result) => result,
+            Err(_) => unreachable!("Fakultät ohne Abbruch kann nicht fehlschlagen"),
+        }
+    }
+
+    /// Berechnet die Fakultät als Dezimalzeichenkette, abbrechbar über `is_cancelled`
+    ///
+    /// # Errors
+    ///
+    /// Gibt [`ProjectError::Cancelled`] zurück, wenn die Berechnung abgebrochen wurde.
+    pub fn big_factorial_cancellable(
+        n: u64,
+        is_cancelled: &dyn Fn() -> bool,
+    ) -> Result<String, ProjectError> {
+        const BASE: u128 = 1_000_000_000;
+
+        // Ziffernblöcke zur Basis 10^9, niederwertigster Block zuerst
+        let mut limbs: Vec<u128> = vec![1];
+        for i in 2..=n {
+            // Jeder Schritt ist teurer als die Abbruchprüfung, daher wird immer geprüft
+            if is_cancelled() {
+                return Err(ProjectError::Cancelled);
+            }
+
+            let mut carry: u128 = 0;
+            for limb in limbs.iter_mut() {
+                let value = *limb * i as u128 + carry;
+                *limb = value % BASE;
+                carry = value / BASE;
+            }
+            while carry > 0 {
+                limbs.push(carry % BASE);
+                carry /= BASE;
             }
         }
-        true
+
+        let mut digits = limbs.last().map(|limb| limb.to_string()).unwrap_or_default();
+        for limb in limbs.iter().rev().skip(1) {
+            digits.push_str(&format!("{:09}", limb));
+        }
+        Ok(digits)
     }
 
     /// Berechnet die n-te Fibonacci-Zahl
-    /* pub fn _org_fibonacci(n: u64) -> Result<u64, ProjectError> {
+    pub fn fibonacci(n: u64) -> Result<u64, ProjectError> {
         if n > 93 {
-            return Err(ProjectError::OverflowError("Fibonacci Overflow".to_string()));
+            return Err(ProjectError::overflow(Message::new("math.fibonacci_overflow", []))
+                .with_operation("fibonacci", &[n as f64]));
         }
-        
+
         if n <= 1 {
             return Ok(n);
         }
-        
-        let mut a = 0;
-        let mut b = 1;
-        
+
+        let mut a: u64 = 0;
+        let mut b: u64 = 1;
+
         for _ in 2..=n {
             let temp = a.checked_add(b)
-                .ok_or_else(|| ProjectError::OverflowError("Fibonacci Overflow".to_string()))?;
+                .ok_or_else(|| {
+                    ProjectError::overflow(Message::new("math.fibonacci_overflow", []))
+                        .with_operation("fibonacci", &[n as f64])
+                })?;
             a = b;
             b = temp;
         }
-        
-        Ok(b)
-    } */
 
-    /// Berechnet die n-te Fibonacci-Zahl
-pub fn fibonacci(n: u64) -> Result<u64, ProjectError> {
-    if n > 93 {
-        return Err(ProjectError::OverflowError("Fibonacci Overflow".to_string()));
-    }
-    
-    if n <= 1 {
-        return Ok(n);
-    }
-    
-    let mut a: u64 = 0;
-    let mut b: u64 = 1;
-    
-    for _ in 2..=n {
-        let temp = a.checked_add(b)
-            .ok_or_else(|| ProjectError::OverflowError("Fibonacci Overflow".to_string()))?;
-        a = b;
-        b = temp;
-    }
-    
-    Ok(b)
-}
+        Ok(b)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use rstest::rstest;
+    use crate::modules::history::HistoryQuery;
 
     #[test]
     fn test_config_default() {
@@ -208,10 +373,10 @@ mod tests {
     #[test]
     fn test_statistics_add_operation() {
         let mut stats = Statistics::new();
-        stats.add_operation("add", 5.0);
+        stats.add_operation(OperationType::Add, 5.0);
         
         assert_eq!(stats.total_operations, 1);
-        assert_eq!(stats.get_operation_count("add"), 1);
+        assert_eq!(stats.get_operation_count(&OperationType::Add), 1);
         assert_eq!(stats.average_result, 5.0);
         assert_eq!(stats.min_result, 5.0);
         assert_eq!(stats.max_result, 5.0);
@@ -220,13 +385,13 @@ mod tests {
     #[test]
     fn test_statistics_multiple_operations() {
         let mut stats = Statistics::new();
-        stats.add_operation("add", 2.0);
-        stats.add_operation("add", 8.0);
-        stats.add_operation("multiply", 10.0);
+        stats.add_operation(OperationType::Add, 2.0);
+        stats.add_operation(OperationType::Add, 8.0);
+        stats.add_operation(OperationType::Multiply, 10.0);
         
         assert_eq!(stats.total_operations, 3);
-        assert_eq!(stats.get_operation_count("add"), 2);
-        assert_eq!(stats.get_operation_count("multiply"), 1);
+        assert_eq!(stats.get_operation_count(&OperationType::Add), 2);
+        assert_eq!(stats.get_operation_count(&OperationType::Multiply), 1);
         assert_eq!(stats.average_result, (2.0 + 8.0 + 10.0) / 3.0);
         assert_eq!(stats.min_result, 2.0);
         assert_eq!(stats.max_result, 10.0);
@@ -235,13 +400,51 @@ mod tests {
     #[test]
     fn test_statistics_clear() {
         let mut stats = Statistics::new();
-        stats.add_operation("add", 5.0);
+        stats.add_operation(OperationType::Add, 5.0);
         stats.clear();
         
         assert!(stats.is_empty());
         assert_eq!(stats.total_operations, 0);
     }
 
+    #[test]
+    fn test_statistics_per_operation_breakdown() {
+        let mut stats = Statistics::new();
+        stats.add_operation(OperationType::Add, 2.0);
+        stats.add_operation(OperationType::Add, 8.0);
+        stats.add_operation(OperationType::Divide, 0.5);
+
+        let add = stats.get_operation_statistics(&OperationType::Add).unwrap();
+        assert_eq!(add.count, 2);
+        assert_eq!(add.min_result, 2.0);
+        assert_eq!(add.max_result, 8.0);
+        assert_eq!(add.average_result, 5.0);
+
+        let divide = stats.get_operation_statistics(&OperationType::Divide).unwrap();
+        assert_eq!(divide.count, 1);
+        assert_eq!(divide.average_result, 0.5);
+
+        assert!(stats.get_operation_statistics(&OperationType::Subtract).is_none());
+        assert_eq!(stats.get_operation_count(&OperationType::Subtract), 0);
+
+        let order: Vec<_> = stats.operations().into_iter().map(|(op, _)| op.clone()).collect();
+        assert_eq!(order, vec![OperationType::Add, OperationType::Divide]);
+    }
+
+    #[test]
+    fn test_statistics_from_history() {
+        let mut calc = crate::Calculator::new();
+        calc.add(1.0, 2.0).unwrap();
+        calc.multiply(3.0, 4.0).unwrap();
+        calc.add(5.0, 5.0).unwrap();
+
+        let stats = Statistics::from_history(&calc.query_history(&HistoryQuery::new()).unwrap());
+        assert_eq!(stats.total_operations, 3);
+        assert_eq!(stats.get_operation_count(&OperationType::Add), 2);
+        assert_eq!(stats.get_operation_count(&OperationType::Multiply), 1);
+        assert_eq!(stats.max_result, 12.0);
+    }
+
     #[rstest]
     #[case(12, 8, 4)]
     #[case(54, 24, 6)]
@@ -289,6 +492,43 @@ mod tests {
         assert_eq!(MathUtils::fibonacci(n).unwrap(), expected);
     }
 
+    #[test]
+    fn test_is_prime_cancellable() {
+        assert_eq!(MathUtils::is_prime_cancellable(97, &|| false), Ok(true));
+        assert_eq!(
+            MathUtils::is_prime_cancellable(18_446_744_073_709_551_557, &|| true),
+            Err(ProjectError::Cancelled)
+        );
+    }
+
+    #[rstest]
+    #[case(0, "1")]
+    #[case(1, "1")]
+    #[case(5, "120")]
+    #[case(20, "2432902008176640000")]
+    #[case(30, "265252859812191058636308480000000")]
+    fn test_big_factorial(#[case] n: u64, #[case] expected: &str) {
+        assert_eq!(MathUtils::big_factorial(n), expected);
+    }
+
+    #[test]
+    fn test_big_factorial_cancellable() {
+        assert_eq!(MathUtils::big_factorial(1000).len(), 2568);
+        assert_eq!(
+            MathUtils::big_factorial_cancellable(100_000, &|| true),
+            Err(ProjectError::Cancelled)
+        );
+    }
+
+    #[test]
+    fn test_check_big_factorial() {
+        assert!(MathUtils::check_big_factorial(MathUtils::BIG_FACTORIAL_MAX).is_ok());
+        assert_eq!(
+            MathUtils::check_big_factorial(MathUtils::BIG_FACTORIAL_MAX + 1).unwrap_err().code(),
+            "invalid_input"
+        );
+    }
+
     #[test]
     fn test_fibonacci_overflow() {
         assert!(MathUtils::fibonacci(100).is_err());
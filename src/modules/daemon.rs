@@ -0,0 +1,501 @@
+//! Hintergrunddienst mit Unix-Socket für einen gemeinsamen Calculator
+
+use crate::modules::error::ProjectError;
+use crate::modules::i18n::{Locale, Message};
+use crate::modules::numeric::lossless::Lossless;
+use crate::modules::rpc::{RpcError, RpcServer};
+use crate::modules::shared::SharedCalculator;
+use crate::Calculator;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::io::{self, BufRead, BufReader, ErrorKind, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::Notify;
+
+/// Zustand eines laufenden Daemons
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DaemonStatus {
+    pub pid: u32,
+    pub uptime_seconds: u64,
+    pub history_count: usize,
+    pub socket: String,
+}
+
+/// Speichert den Zustand des Calculators, z. B. in einer Verlaufsdatei
+pub type Persist = dyn Fn(&Calculator) -> Result<(), ProjectError> + Send + Sync;
+
+/// Daemon, der JSON-RPC-Anfragen (siehe [`RpcServer`]) auf einem Unix-Socket beantwortet
+///
+/// Zusätzlich zu den Methoden des Calculators versteht er `daemon.status`
+/// und `daemon.shutdown`. Mit [`Daemon::persist_with`] wird der Zustand nach
+/// jeder verändernden Anfrage gespeichert, sodass ein Absturz nichts verliert.
+///
+/// Die Socket-Datei wird beim Verwerfen entfernt; wer den Verlauf nach dem
+/// Beenden speichert, sollte den Daemon erst danach verwerfen, damit wartende
+/// Clients das Ende erst nach dem Speichern sehen.
+pub struct Daemon {
+    listener: UnixListener,
+    path: PathBuf,
+    persist: Option<Arc<Persist>>,
+    locale: Locale,
+}
+
+impl fmt::Debug for Daemon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Daemon")
+            .field("path", &self.path)
+            .field("persist", &self.persist.is_some())
+            .field("locale", &self.locale)
+            .finish()
+    }
+}
+
+impl Daemon {
+    /// Standardpfad des Sockets
+    ///
+    /// Liegt in `$XDG_RUNTIME_DIR`, falls gesetzt, sonst im Home-Verzeichnis.
+    pub fn default_socket_path() -> String {
+        let path = match std::env::var("XDG_RUNTIME_DIR") {
+            Ok(dir) if !dir.is_empty() => PathBuf::from(dir).join("rust_project_complete.sock"),
+            _ => {
+                let base = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+                Path::new(&base)
+                    .join(".rust_project_complete")
+                    .join("daemon.sock")
+            }
+        };
+        path.to_string_lossy().into_owned()
+    }
+
+    /// Belegt den Socket; ein verwaister Socket eines beendeten Daemons wird ersetzt
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn bereits ein Daemon läuft oder der Socket
+    /// nicht angelegt werden kann.
+    pub fn bind(socket_path: &str) -> Result<Self, ProjectError> {
+        let path = PathBuf::from(socket_path);
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|e| ProjectError::io(e, parent))?;
+        }
+        if path.exists() {
+            if UnixStream::connect(&path).is_ok() {
+                return Err(ProjectError::invalid_input(Message::new(
+                    "daemon.already_running",
+                    [socket_path.into()],
+                )));
+            }
+            fs::remove_file(&path).map_err(|e| ProjectError::io(e, &path))?;
+        }
+
+        let listener = UnixListener::bind(&path).map_err(|e| ProjectError::io(e, &path))?;
+        // Nur der eigene Benutzer darf mit dem Daemon sprechen
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))
+            .map_err(|e| ProjectError::io(e, &path))?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            path,
+            persist: None,
+            locale: Locale::default(),
+        })
+    }
+
+    /// Speichert den Zustand nach jeder Anfrage, die den Calculator verändert
+    ///
+    /// Die Funktion läuft unter der Lesesperre im Blocking-Pool; Fehler werden
+    /// protokolliert, die Antwort an den Client bleibt davon unberührt.
+    pub fn persist_with(
+        mut self,
+        persist: impl Fn(&Calculator) -> Result<(), ProjectError> + Send + Sync + 'static,
+    ) -> Self {
+        self.persist = Some(Arc::new(persist));
+        self
+    }
+
+    /// Sprache der Protokollfehler, siehe [`RpcServer::locale`]
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// Gibt den Pfad des Sockets zurück
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Beantwortet Verbindungen bis SIGTERM, SIGINT oder `daemon.shutdown`
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn der Socket nicht mehr bedient werden kann.
+    pub async fn run(&self, calculator: SharedCalculator) -> Result<(), ProjectError> {
+        let listener = tokio::net::UnixListener::from_std(self.listener.try_clone()?)?;
+        let mut terminate = signal(SignalKind::terminate())?;
+        let mut interrupt = signal(SignalKind::interrupt())?;
+        let shutdown = Arc::new(Notify::new());
+        let context = Arc::new(Context {
+            calculator,
+            shutdown: shutdown.clone(),
+            started: Instant::now(),
+            socket: self.path.to_string_lossy().into_owned(),
+            persist: self.persist.clone(),
+            locale: self.locale,
+        });
+
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => {
+                    let (stream, _) = accepted?;
+                    let context = context.clone();
+                    tokio::spawn(async move {
+                        if let Err(error) = handle_connection(stream, context).await {
+                            tracing::warn!(%error, "Verbindung zum Daemon abgebrochen");
+                        }
+                    });
+                }
+                _ = terminate.recv() => break,
+                _ = interrupt.recv() => break,
+                _ = shutdown.notified() => break,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Daemon {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+struct Context {
+    calculator: SharedCalculator,
+    shutdown: Arc<Notify>,
+    started: Instant,
+    socket: String,
+    persist: Option<Arc<Persist>>,
+    locale: Locale,
+}
+
+impl Context {
+    fn status(&self) -> DaemonStatus {
+        DaemonStatus {
+            pid: std::process::id(),
+            uptime_seconds: self.started.elapsed().as_secs(),
+            history_count: self.calculator.history_count(),
+            socket: self.socket.clone(),
+        }
+    }
+
+    /// Speichert den Zustand, falls `line` eine verändernde Anfrage enthält
+    fn persist_after(&self, line: &str) {
+        let Some(persist) = &self.persist else {
+            return;
+        };
+        if !is_mutating(line) {
+            return;
+        }
+        if let Err(error) = self.calculator.read(|calc| persist(calc)) {
+            tracing::error!(%error, "Zustand des Daemons konnte nicht gespeichert werden");
+        }
+    }
+}
+
+/// Prüft, ob eine Anfrage oder ein Stapel den Calculator verändern kann
+fn is_mutating(line: &str) -> bool {
+    let method = |request: &Value| {
+        request
+            .get("method")
+            .and_then(Value::as_str)
+            .is_some_and(RpcServer::is_mutating)
+    };
+    match serde_json::from_str::<Value>(line) {
+        Ok(Value::Array(batch)) => batch.iter().any(method),
+        Ok(request) => method(&request),
+        Err(_) => false,
+    }
+}
+
+/// Beantwortet die Anfragen einer Verbindung, bis der Client sie schließt
+///
+/// Der [`RpcServer`] der Verbindung meldet sich beim Verwerfen als Beobachter
+/// ab, auch wenn die Verbindung mit einem Fehler endet.
+async fn handle_connection(
+    stream: tokio::net::UnixStream,
+    context: Arc<Context>,
+) -> Result<(), ProjectError> {
+    let (reader, mut writer) = stream.into_split();
+    let rpc = Arc::new(RpcServer::new(context.calculator.clone()).locale(context.locale));
+    let mut lines = tokio::io::BufReader::new(reader).lines();
+
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let messages = match daemon_request(&line, &context) {
+            Some(response) => vec![response.to_string()],
+            None => {
+                // Aufwendige Berechnungen und das Speichern dürfen den Executor nicht blockieren
+                let (rpc, context) = (rpc.clone(), context.clone());
+                tokio::task::spawn_blocking(move || {
+                    let messages = rpc.handle(&line);
+                    context.persist_after(&line);
+                    messages
+                })
+                .await
+                .map_err(|_| ProjectError::Cancelled)?
+            }
+        };
+        for message in messages {
+            writer.write_all(message.as_bytes()).await?;
+            writer.write_all(b"\n").await?;
+        }
+        writer.flush().await?;
+    }
+    Ok(())
+}
+
+/// Beantwortet die Methoden `daemon.*`; alles andere geht an den [`RpcServer`]
+fn daemon_request(line: &str, context: &Context) -> Option<Value> {
+    let request: Value = serde_json::from_str(line).ok()?;
+    let method = request.get("method")?.as_str()?;
+    let result = match method {
+        "daemon.status" => serde_json::to_value(context.status()).ok()?,
+        "daemon.shutdown" => {
+            context.shutdown.notify_one();
+            json!({ "stopping": true })
+        }
+        _ => return None,
+    };
+    let id = request.get("id")?;
+    Some(json!({ "jsonrpc": "2.0", "result": result, "id": id }))
+}
+
+/// Schlanker Client für einen laufenden [`Daemon`]
+#[derive(Debug)]
+pub struct DaemonClient {
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+    next_id: u64,
+}
+
+impl DaemonClient {
+    /// Verbindet sich mit dem Daemon; `None`, wenn keiner läuft
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn der Socket existiert, aber nicht
+    /// erreichbar ist, z. B. wegen fehlender Rechte.
+    pub fn connect(socket_path: &str) -> Result<Option<Self>, ProjectError> {
+        match UnixStream::connect(socket_path) {
+            Ok(stream) => Ok(Some(Self {
+                reader: BufReader::new(stream.try_clone()?),
+                writer: stream,
+                next_id: 1,
+            })),
+            Err(error)
+                if matches!(error.kind(), ErrorKind::NotFound | ErrorKind::ConnectionRefused) =>
+            {
+                Ok(None)
+            }
+            Err(error) => Err(ProjectError::io(error, socket_path)),
+        }
+    }
+
+    /// Ruft eine Methode auf und wartet auf deren Antwort
+    ///
+    /// Benachrichtigungen, die vor der Antwort eintreffen, werden übersprungen.
+    ///
+    /// # Errors
+    ///
+    /// Gibt den vom Daemon gemeldeten Fehler oder einen Verbindungsfehler zurück.
+    pub fn call<T: DeserializeOwned>(
+        &mut self,
+        method: &str,
+        params: Value,
+    ) -> Result<T, ProjectError> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let request = json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": id });
+        writeln!(self.writer, "{}", request)?;
+        self.writer.flush()?;
+
+        loop {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Err(io::Error::from(ErrorKind::UnexpectedEof).into());
+            }
+            let mut response: Value = serde_json::from_str(&line)?;
+            if response.get("id") != Some(&json!(id)) {
+                continue;
+            }
+            if let Some(error) = response.get_mut("error") {
+                let error: RpcError = serde_json::from_value(error.take())?;
+                return Err(error.into());
+            }
+            return Ok(serde_json::from_value(response["result"].take())?);
+        }
+    }
+
+    /// Ruft eine Methode mit Zahlenoperanden auf, deren Ergebnis eine Zahl ist
+    ///
+    /// Operanden und Ergebnis dürfen NaN oder ±Unendlich sein; sie werden wie
+    /// im Verlauf als `"NaN"`, `"inf"` und `"-inf"` übertragen.
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`DaemonClient::call`].
+    pub fn call_number(&mut self, method: &str, operands: &[f64]) -> Result<f64, ProjectError> {
+        let params: Vec<Lossless> = operands.iter().copied().map(Lossless).collect();
+        let Lossless(result) = self.call(method, serde_json::to_value(params)?)?;
+        Ok(result)
+    }
+
+    /// Fragt den Zustand des Daemons ab
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`DaemonClient::call`].
+    pub fn status(&mut self) -> Result<DaemonStatus, ProjectError> {
+        self.call("daemon.status", Value::Null)
+    }
+
+    /// Fordert das Beenden des Daemons an
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`DaemonClient::call`].
+    pub fn shutdown(&mut self) -> Result<(), ProjectError> {
+        self.call::<Value>("daemon.shutdown", Value::Null).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn socket(dir: &tempfile::TempDir) -> String {
+        dir.path().join("daemon.sock").to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn test_client_without_daemon() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(DaemonClient::connect(&socket(&dir)).unwrap().is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_daemon_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = socket(&dir);
+        let daemon = Daemon::bind(&path).unwrap();
+        assert!(Daemon::bind(&path).is_err());
+
+        let calculator = SharedCalculator::new();
+        let server = {
+            let calculator = calculator.clone();
+            tokio::spawn(async move {
+                daemon.run(calculator).await.unwrap();
+                daemon
+            })
+        };
+
+        let client_path = path.clone();
+        let status = tokio::task::spawn_blocking(move || {
+            let mut client = DaemonClient::connect(&client_path).unwrap().unwrap();
+            assert_eq!(client.call::<f64>("add", json!([2, 3])).unwrap(), 5.0);
+            assert_eq!(client.call_number("multiply", &[1e200, 1e200]).unwrap(), f64::INFINITY);
+            assert_eq!(client.call_number("add", &[f64::NEG_INFINITY, 1.0]).unwrap(), f64::NEG_INFINITY);
+            assert!(client.call_number("subtract", &[f64::INFINITY, f64::INFINITY]).unwrap().is_nan());
+            assert_eq!(
+                client.call::<f64>("divide", json!([1, 0])),
+                Err(ProjectError::division_by_zero().with_operation("divide", &[1.0, 0.0]))
+            );
+            let status = client.status().unwrap();
+            client.shutdown().unwrap();
+            status
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(status.history_count, 4);
+        assert_eq!(status.pid, std::process::id());
+
+        drop(server.await.unwrap());
+        assert_eq!(calculator.history_count(), 4);
+        assert!(!Path::new(&path).exists());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_persists_changes_and_unregisters_connections() {
+        use std::sync::Mutex;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = socket(&dir);
+        let saved = Arc::new(Mutex::new(Vec::new()));
+        let daemon = {
+            let saved = saved.clone();
+            Daemon::bind(&path).unwrap().persist_with(move |calc| {
+                saved.lock().unwrap().push(calc.history_count());
+                Ok(())
+            })
+        };
+
+        let calculator = SharedCalculator::new();
+        let server = {
+            let calculator = calculator.clone();
+            tokio::spawn(async move {
+                daemon.run(calculator).await.unwrap();
+                daemon
+            })
+        };
+
+        let (client_path, probe) = (path.clone(), calculator.clone());
+        let connected = tokio::task::spawn_blocking(move || {
+            let mut client = DaemonClient::connect(&client_path).unwrap().unwrap();
+            client.call_number("add", &[1.0, 2.0]).unwrap();
+            client.call::<usize>("history_count", Value::Null).unwrap();
+            client.call::<Value>("set_variable", json!(["x", 1.0])).unwrap();
+            probe.read(|c| c.observer_count())
+        })
+        .await
+        .unwrap();
+        assert_eq!(connected, 1);
+        assert_eq!(*saved.lock().unwrap(), vec![1, 1]);
+
+        // Nach dem Schließen meldet sich die Verbindung als Beobachter ab
+        let mut remaining = usize::MAX;
+        for _ in 0..100 {
+            remaining = calculator.read(|c| c.observer_count());
+            if remaining == 0 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(remaining, 0);
+
+        DaemonClient::connect(&path).unwrap().unwrap().shutdown().unwrap();
+        drop(server.await.unwrap());
+    }
+
+    #[test]
+    fn test_stale_socket_is_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = socket(&dir);
+        drop(UnixListener::bind(&path).unwrap());
+
+        assert!(Path::new(&path).exists());
+        assert!(DaemonClient::connect(&path).unwrap().is_none());
+        assert!(Daemon::bind(&path).is_ok());
+    }
+}
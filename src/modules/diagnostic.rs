@@ -0,0 +1,390 @@
+//! Diagnosen für Parsing-Fehler mit Position im Quelltext
+
+use crate::modules::i18n::{Argument, Message};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Range;
+
+/// ANSI-Farben für [`Diagnostic::excerpt`]
+const RED: &str = "1;31";
+const BLUE: &str = "1;34";
+
+/// Position eines Fehlers im Quelltext
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    /// Zeile, ab 1
+    pub line: usize,
+    /// Spalte in Zeichen, ab 1
+    pub column: usize,
+    /// Länge der markierten Stelle in Zeichen, mindestens 1
+    pub length: usize,
+}
+
+/// Parsing-Fehler mit Position, Auszug aus dem Quelltext und Vorschlag
+///
+/// Wird als [`ProjectError::ParseError`](crate::modules::error::ProjectError::ParseError)
+/// zurückgegeben; [`ProjectError::diagnostic`](crate::modules::error::ProjectError::diagnostic)
+/// gibt Zugriff auf die Felder. Die Kommandozeile zeigt zusätzlich den
+/// [`Auszug`](Diagnostic::excerpt) mit der markierten Stelle.
+///
+/// # Beispiel
+///
+/// ```rust
+/// use rust_project_complete::Calculator;
+///
+/// let error = Calculator::new().evaluate("2 * ad(1, 2)").unwrap_err();
+/// let diagnostic = error.diagnostic().unwrap();
+/// let span = diagnostic.span.as_ref().unwrap();
+/// assert_eq!((span.line, span.column, span.length), (1, 5, 2));
+/// assert_eq!(diagnostic.suggestion.as_ref().unwrap().to_string(), "meinten Sie 'add'?");
+/// assert_eq!(
+///     diagnostic.to_string(),
+///     "Unbekannte Operation 'ad' (Zeile 1, Spalte 5); meinten Sie 'add'?"
+/// );
+/// assert_eq!(
+///     diagnostic.excerpt(false).unwrap(),
+///     "  |\n1 | 2 * ad(1, 2)\n  |     ^^\n"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(into = "DiagnosticData", from = "DiagnosticData")]
+pub struct Diagnostic {
+    /// Meldung ohne Position, z. B. `Unbekannte Operation 'ad'`
+    pub message: Message,
+    /// Datei, aus der der Quelltext stammt
+    pub file: Option<String>,
+    /// Position der markierten Stelle
+    pub span: Option<Span>,
+    /// Zeile des Quelltexts, in der die markierte Stelle beginnt
+    pub source_line: Option<String>,
+    /// Hinweis zur Behebung, z. B. `meinten Sie 'add'?`
+    pub suggestion: Option<Message>,
+}
+
+/// Form einer [`Diagnostic`] beim Serialisieren
+///
+/// `message` und `suggestion` bleiben Text, Schlüssel und Werte stehen
+/// daneben wie bei [`Message`].
+#[derive(Serialize, Deserialize)]
+struct DiagnosticData {
+    #[serde(flatten)]
+    message: Message,
+    file: Option<String>,
+    span: Option<Span>,
+    source_line: Option<String>,
+    suggestion: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    suggestion_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    suggestion_args: Vec<Argument>,
+}
+
+impl From<Diagnostic> for DiagnosticData {
+    fn from(diagnostic: Diagnostic) -> Self {
+        let (suggestion, suggestion_key, suggestion_args) = match diagnostic.suggestion {
+            Some(suggestion) => {
+                let (text, key, arguments) = suggestion.into_parts();
+                (Some(text), key, arguments)
+            }
+            None => (None, None, Vec::new()),
+        };
+        DiagnosticData {
+            message: diagnostic.message,
+            file: diagnostic.file,
+            span: diagnostic.span,
+            source_line: diagnostic.source_line,
+            suggestion,
+            suggestion_key,
+            suggestion_args,
+        }
+    }
+}
+
+impl From<DiagnosticData> for Diagnostic {
+    fn from(data: DiagnosticData) -> Self {
+        Diagnostic {
+            message: data.message,
+            file: data.file,
+            span: data.span,
+            source_line: data.source_line,
+            suggestion: data
+                .suggestion
+                .map(|text| Message::from_parts(text, data.suggestion_key, data.suggestion_args)),
+        }
+    }
+}
+
+impl Diagnostic {
+    /// Erstellt eine Diagnose ohne Position
+    pub fn new(message: impl Into<Message>) -> Self {
+        Self {
+            message: message.into(),
+            file: None,
+            span: None,
+            source_line: None,
+            suggestion: None,
+        }
+    }
+
+    /// Diagnose aus einem Fehler von `serde_json`
+    ///
+    /// Zeile und Spalte übernimmt sie aus dem Fehler, den Auszug aus `source`.
+    pub fn from_json(error: &serde_json::Error, source: Option<&str>) -> Self {
+        let message = error.to_string();
+        // serde_json hängt die Position an, die hier eigene Felder hat
+        let message = match message.rfind(" at line ") {
+            Some(index) => message[..index].to_string(),
+            None => message,
+        };
+        let mut diagnostic = Self::new(message);
+        if error.line() > 0 {
+            diagnostic.span = Some(Span {
+                line: error.line(),
+                column: error.column().max(1),
+                length: 1,
+            });
+            diagnostic.source_line = source
+                .and_then(|source| source.lines().nth(error.line() - 1))
+                .map(str::to_string);
+        }
+        diagnostic
+    }
+
+    /// Markiert den Byte-Bereich `range` in `source`
+    ///
+    /// Zeile, Spalte und Länge werden in Zeichen gezählt; ein leerer Bereich,
+    /// z. B. am Ende der Eingabe, markiert ein Zeichen.
+    pub fn with_span(mut self, source: &str, range: Range<usize>) -> Self {
+        let start = range.start.min(source.len());
+        let line_start = source[..start].rfind('\n').map_or(0, |index| index + 1);
+        let line_end = source[start..]
+            .find('\n')
+            .map_or(source.len(), |index| start + index);
+        let end = range.end.clamp(start, line_end);
+
+        self.span = Some(Span {
+            line: source[..start].matches('\n').count() + 1,
+            column: source[line_start..start].chars().count() + 1,
+            length: source[start..end].chars().count().max(1),
+        });
+        self.source_line = Some(
+            source[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string(),
+        );
+        self
+    }
+
+    /// Verschiebt die Zeile, wenn der Quelltext erst in Zeile `first_line` beginnt
+    ///
+    /// Für einzeln gelesene Zeilen einer Datei, z. B. Datensätze in `batch`.
+    pub fn starting_at_line(mut self, first_line: usize) -> Self {
+        if let Some(span) = &mut self.span {
+            span.line += first_line.saturating_sub(1);
+        }
+        self
+    }
+
+    /// Legt die Datei fest, aus der der Quelltext stammt
+    pub fn in_file(mut self, file: impl Into<String>) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+
+    /// Fügt einen Hinweis zur Behebung hinzu
+    pub fn with_suggestion(mut self, suggestion: impl Into<Message>) -> Self {
+        self.suggestion = Some(suggestion.into());
+        self
+    }
+
+    /// Auszug aus dem Quelltext mit markierter Stelle, abgeschlossen mit einem Zeilenumbruch
+    ///
+    /// Mit `color` werden Rand und Markierung mit ANSI-Farben hervorgehoben.
+    /// Ohne Position oder Quelltext gibt es keinen Auszug.
+    pub fn excerpt(&self, color: bool) -> Option<String> {
+        let span = self.span.as_ref()?;
+        let source_line = self.source_line.as_ref()?;
+        let paint = |code: &str, text: &str| {
+            if color {
+                format!("\x1b[{}m{}\x1b[0m", code, text)
+            } else {
+                text.to_string()
+            }
+        };
+
+        let number = span.line.to_string();
+        let gutter = " ".repeat(number.len());
+        let bar = paint(BLUE, "|");
+        Some(format!(
+            "{gutter} {bar}\n{} {bar} {}\n{gutter} {bar} {}{}\n",
+            paint(BLUE, &number),
+            source_line,
+            " ".repeat(span.column - 1),
+            paint(RED, &"^".repeat(span.length)),
+            gutter = gutter,
+            bar = bar,
+        ))
+    }
+}
+
+impl Diagnostic {
+    /// Meldung mit Datei, Position und Vorschlag, wie bei `Display`
+    pub fn summary(&self) -> Message {
+        let message = self.message.clone().into();
+        let summary = match (&self.file, &self.span) {
+            (Some(file), Some(span)) => Message::new(
+                "diagnostic.at_file_position",
+                [message, file.into(), span.line.into(), span.column.into()],
+            ),
+            (None, Some(span)) => Message::new(
+                "diagnostic.at_position",
+                [message, span.line.into(), span.column.into()],
+            ),
+            (Some(file), None) => Message::new("diagnostic.in_file", [message, file.into()]),
+            (None, None) => self.message.clone(),
+        };
+        match &self.suggestion {
+            Some(suggestion) => Message::new(
+                "diagnostic.with_suggestion",
+                [summary.into(), suggestion.clone().into()],
+            ),
+            None => summary,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.summary().fmt(f)
+    }
+}
+
+/// Ähnlichster Name aus `candidates` für einen Vorschlag, z. B. `add` für `ad`
+///
+/// Groß- und Kleinschreibung zählen nicht; je drei Zeichen ist ein
+/// abweichendes Zeichen erlaubt, mindestens eines.
+///
+/// # Beispiel
+///
+/// ```rust
+/// use rust_project_complete::modules::diagnostic::closest;
+///
+/// assert_eq!(closest("sqr", ["add", "sqrt"]), Some("sqrt"));
+/// assert_eq!(closest("wurzel", ["add", "sqrt"]), None);
+/// ```
+pub fn closest<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let name = name.to_lowercase();
+    let limit = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(&name, &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein-Distanz in Zeichen
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_span_in_multiline_source() {
+        let source = "a = 1\nb = 2 $ 3\n";
+        let start = source.find('$').unwrap();
+        let diagnostic = Diagnostic::new(Message::new("expression.unexpected_character", ['$'.into()]))
+            .with_span(source, start..start + 1);
+        assert_eq!(
+            diagnostic.span,
+            Some(Span {
+                line: 2,
+                column: 7,
+                length: 1
+            })
+        );
+        assert_eq!(diagnostic.source_line.as_deref(), Some("b = 2 $ 3"));
+        assert_eq!(
+            diagnostic.excerpt(false).unwrap(),
+            "  |\n2 | b = 2 $ 3\n  |       ^\n"
+        );
+        assert_eq!(
+            diagnostic.excerpt(true).unwrap(),
+            "  \x1b[1;34m|\x1b[0m\n\x1b[1;34m2\x1b[0m \x1b[1;34m|\x1b[0m b = 2 $ 3\n  \x1b[1;34m|\x1b[0m       \x1b[1;31m^\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_span_counts_characters() {
+        let source = "größe + ";
+        let diagnostic = Diagnostic::new(Message::new("expression.unexpected_end", []))
+            .with_span(source, source.len()..source.len())
+            .starting_at_line(4)
+            .in_file("daten.csv");
+        let span = diagnostic.span.as_ref().unwrap();
+        assert_eq!((span.line, span.column, span.length), (4, 9, 1));
+        assert_eq!(
+            diagnostic.to_string(),
+            "Unerwartetes Ende des Ausdrucks (Datei daten.csv, Zeile 4, Spalte 9)"
+        );
+    }
+
+    #[test]
+    fn test_from_json() {
+        let source = "{\n  \"a\": 1,\n  \"b\": x\n}";
+        let error = serde_json::from_str::<serde_json::Value>(source).unwrap_err();
+        let diagnostic = Diagnostic::from_json(&error, Some(source));
+        assert_eq!(diagnostic.message.to_string(), "expected value");
+        assert_eq!(diagnostic.span.as_ref().map(|span| span.line), Some(3));
+        assert_eq!(diagnostic.source_line.as_deref(), Some("  \"b\": x"));
+        assert!(diagnostic
+            .excerpt(false)
+            .unwrap()
+            .ends_with("  |        ^\n"));
+
+        assert_eq!(Diagnostic::new("Operation fehlt").excerpt(false), None);
+    }
+
+    #[test]
+    fn test_summary_is_localized() {
+        let diagnostic = Diagnostic::new(Message::new("operation.unknown", ["Subtraktion".into()]))
+            .with_span("Subtraktion(1)", 0..11)
+            .with_suggestion(Message::new("diagnostic.did_you_mean", ["subtract".into()]));
+        assert_eq!(
+            diagnostic.summary().render(crate::modules::i18n::Locale::En),
+            "Unknown operation 'Subtraktion' (line 1, column 1); did you mean 'subtract'?"
+        );
+
+        let json = serde_json::to_value(&diagnostic).unwrap();
+        assert_eq!(json["message"], "Unbekannte Operation 'Subtraktion'");
+        assert_eq!(json["suggestion"], "meinten Sie 'subtract'?");
+        assert_eq!(json["suggestion_key"], "diagnostic.did_you_mean");
+        let restored: Diagnostic = serde_json::from_value(json).unwrap();
+        assert_eq!(restored, diagnostic);
+    }
+
+    #[test]
+    fn test_closest() {
+        assert_eq!(closest("ad", ["add", "subtract"]), Some("add"));
+        assert_eq!(
+            closest("MULTIPLI", ["multiply", "divide"]),
+            Some("multiply")
+        );
+        assert_eq!(closest("add", ["add"]), Some("add"));
+        assert_eq!(closest("xyz", ["add"]), None);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+}
@@ -1,110 +1,656 @@
 //! Fehlerbehandlung für das Projekt
 
+use crate::modules::diagnostic::Diagnostic;
+use crate::modules::i18n::{self, Locale, Message};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use thiserror::Error;
 
+/// Exit-Code von `daemon status`, wenn kein Daemon läuft; das ist kein Fehler
+pub const NOT_RUNNING_EXIT_CODE: i32 = 1;
+
+/// Exit-Code der Kommandozeile bei falschem Aufruf, z. B. unbekannten Optionen
+pub const USAGE_EXIT_CODE: i32 = 2;
+
 /// Projektspezifische Fehler
-#[derive(Error, Debug, Clone, PartialEq)]
+///
+/// Serialisiert wird jede Variante als Objekt mit ihrem [`code`](ProjectError::code)
+/// im Feld `code` und ihren übrigen Feldern, z. B.
+/// `{"code":"io_error","kind":"not_found","message":"...","path":"verlauf.json"}`.
+/// Der ursprüngliche Fehler hinter [`source`](std::error::Error::source) wird
+/// nicht serialisiert und bei Vergleichen nicht berücksichtigt.
+#[derive(Error, Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "code", rename_all = "snake_case")]
 pub enum ProjectError {
-    #[error("Division durch Null ist nicht erlaubt")]
-    DivisionByZero,
-    
-    #[error("Overflow-Fehler: {0}")]
-    OverflowError(String),
-    
-    #[error("Ungültige Eingabe: {0}")]
-    InvalidInput(String),
-    
-    #[error("IO-Fehler: {0}")]
-    IoError(String),
-    
-    #[error("Parsing-Fehler: {0}")]
-    ParseError(String),
+    #[error("{}", self.message())]
+    DivisionByZero {
+        /// Operation, in der der Fehler auftrat
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        operation: Option<OperationContext>,
+    },
+
+    #[serde(rename = "overflow")]
+    #[error("{}", self.message())]
+    OverflowError {
+        #[serde(flatten)]
+        message: Message,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        operation: Option<OperationContext>,
+    },
+
+    #[error("{}", self.message())]
+    InvalidInput {
+        #[serde(flatten)]
+        message: Message,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        operation: Option<OperationContext>,
+    },
+
+    /// Fehler beim Zugriff auf Dateien, Sockets oder die Datenbank
+    #[error("{}", self.message())]
+    IoError {
+        /// Art des Fehlers, z. B. [`io::ErrorKind::NotFound`]
+        #[serde(with = "io_kind")]
+        kind: io::ErrorKind,
+        message: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        path: Option<PathBuf>,
+        #[serde(skip)]
+        source: Option<ErrorSource>,
+    },
+
+    /// Nicht lesbare Eingabe, mit Position, falls bekannt
+    #[error("{}", self.message())]
+    ParseError {
+        diagnostic: Box<Diagnostic>,
+        #[serde(skip)]
+        source: Option<ErrorSource>,
+    },
+
+    #[error("{}", self.message())]
+    Cancelled,
+
+    #[error("{}", self.message())]
+    AuditViolation {
+        entry: u64,
+        #[serde(with = "i18n::text")]
+        reason: Message,
+    },
+}
+
+/// Operation und Operanden, bei denen ein Fehler auftrat
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperationContext {
+    /// Name der Operation, z. B. `divide`
+    pub name: String,
+    /// NaN und ±Unendlich bleiben als `"NaN"`, `"inf"` und `"-inf"` erhalten
+    #[serde(with = "crate::modules::numeric::lossless::vec")]
+    pub operands: Vec<f64>,
+}
+
+/// Ursprünglicher Fehler, z. B. ein [`io::Error`]
+///
+/// Erlaubt `Clone` für [`ProjectError`].
+#[derive(Debug, Clone)]
+pub struct ErrorSource(Arc<dyn std::error::Error + Send + Sync>);
+
+impl ErrorSource {
+    pub fn new(error: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Self(Arc::new(error))
+    }
+
+    /// Der ursprüngliche Fehler, z. B. für `downcast_ref`
+    pub fn get(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+        self.0.as_ref()
+    }
 }
 
-impl From<std::io::Error> for ProjectError {
-    fn from(error: std::io::Error) -> Self {
-        ProjectError::IoError(error.to_string())
+impl fmt::Display for ErrorSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for ErrorSource {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.0.source()
+    }
+}
+
+/// Vergleicht alle Felder außer dem ursprünglichen Fehler, der nach dem
+/// Deserialisieren fehlt
+impl PartialEq for ProjectError {
+    fn eq(&self, other: &Self) -> bool {
+        use ProjectError::*;
+        match (self, other) {
+            (DivisionByZero { operation: a }, DivisionByZero { operation: b }) => a == b,
+            (
+                OverflowError {
+                    message: a,
+                    operation: x,
+                },
+                OverflowError {
+                    message: b,
+                    operation: y,
+                },
+            )
+            | (
+                InvalidInput {
+                    message: a,
+                    operation: x,
+                },
+                InvalidInput {
+                    message: b,
+                    operation: y,
+                },
+            ) => a == b && x == y,
+            (
+                IoError {
+                    kind: a,
+                    message: x,
+                    path: p,
+                    ..
+                },
+                IoError {
+                    kind: b,
+                    message: y,
+                    path: q,
+                    ..
+                },
+            ) => a == b && x == y && p == q,
+            (ParseError { diagnostic: a, .. }, ParseError { diagnostic: b, .. }) => a == b,
+            (Cancelled, Cancelled) => true,
+            (
+                AuditViolation {
+                    entry: a,
+                    reason: x,
+                },
+                AuditViolation {
+                    entry: b,
+                    reason: y,
+                },
+            ) => a == b && x == y,
+            _ => false,
+        }
+    }
+}
+
+impl ProjectError {
+    /// Division durch Null ohne Angabe der Operation
+    pub fn division_by_zero() -> Self {
+        ProjectError::DivisionByZero { operation: None }
+    }
+
+    /// Überlauf ohne Angabe der Operation
+    pub fn overflow(message: impl Into<Message>) -> Self {
+        ProjectError::OverflowError {
+            message: message.into(),
+            operation: None,
+        }
+    }
+
+    /// Ungültige Eingabe ohne Angabe der Operation
+    pub fn invalid_input(message: impl Into<Message>) -> Self {
+        ProjectError::InvalidInput {
+            message: message.into(),
+            operation: None,
+        }
+    }
+
+    /// Ein-/Ausgabefehler beim Zugriff auf `path`
+    pub fn io(error: io::Error, path: impl AsRef<Path>) -> Self {
+        ProjectError::from(error).with_path(path)
+    }
+
+    /// Stabiler, maschinenlesbarer Code der Fehlerart
+    ///
+    /// Steht beim Serialisieren im Feld `code`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ProjectError::DivisionByZero { .. } => "division_by_zero",
+            ProjectError::OverflowError { .. } => "overflow",
+            ProjectError::InvalidInput { .. } => "invalid_input",
+            ProjectError::IoError { .. } => "io_error",
+            ProjectError::ParseError { .. } => "parse_error",
+            ProjectError::Cancelled => "cancelled",
+            ProjectError::AuditViolation { .. } => "audit_violation",
+        }
+    }
+
+    /// Diagnose eines Parsing-Fehlers mit Position und Vorschlag
+    pub fn diagnostic(&self) -> Option<&Diagnostic> {
+        match self {
+            ProjectError::ParseError { diagnostic, .. } => Some(diagnostic),
+            _ => None,
+        }
+    }
+
+    /// Art eines Ein-/Ausgabefehlers, z. B. um fehlende Dateien von fehlenden
+    /// Rechten zu unterscheiden
+    ///
+    /// # Beispiel
+    ///
+    /// ```rust
+    /// use rust_project_complete::modules::utils::read_file_content;
+    /// use std::io::ErrorKind;
+    ///
+    /// let error = read_file_content("gibt-es-nicht.json").unwrap_err();
+    /// assert_eq!(error.io_kind(), Some(ErrorKind::NotFound));
+    /// assert_eq!(error.path().unwrap().to_str(), Some("gibt-es-nicht.json"));
+    /// ```
+    pub fn io_kind(&self) -> Option<io::ErrorKind> {
+        match self {
+            ProjectError::IoError { kind, .. } => Some(*kind),
+            _ => None,
+        }
+    }
+
+    /// Datei, auf die sich der Fehler bezieht
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            ProjectError::IoError { path, .. } => path.as_deref(),
+            ProjectError::ParseError { diagnostic, .. } => {
+                diagnostic.file.as_deref().map(Path::new)
+            }
+            _ => None,
+        }
+    }
+
+    /// Operation, in der ein Rechenfehler auftrat
+    pub fn operation(&self) -> Option<&OperationContext> {
+        match self {
+            ProjectError::DivisionByZero { operation }
+            | ProjectError::OverflowError { operation, .. }
+            | ProjectError::InvalidInput { operation, .. } => operation.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Ergänzt die Datei bei Ein-/Ausgabe- und Parsing-Fehlern
+    ///
+    /// Eine bereits bekannte Datei bleibt erhalten.
+    pub fn with_path(mut self, file: impl AsRef<Path>) -> Self {
+        match &mut self {
+            ProjectError::IoError { path, .. } => {
+                path.get_or_insert_with(|| file.as_ref().to_path_buf());
+            }
+            ProjectError::ParseError { diagnostic, .. } => {
+                diagnostic
+                    .file
+                    .get_or_insert_with(|| file.as_ref().display().to_string());
+            }
+            _ => {}
+        }
+        self
+    }
+
+    /// Ergänzt Operation und Operanden bei Rechenfehlern
+    ///
+    /// Eine bereits bekannte Operation bleibt erhalten.
+    pub fn with_operation(mut self, name: &str, operands: &[f64]) -> Self {
+        if let ProjectError::DivisionByZero { operation }
+        | ProjectError::OverflowError { operation, .. }
+        | ProjectError::InvalidInput { operation, .. } = &mut self
+        {
+            operation.get_or_insert_with(|| OperationContext {
+                name: name.to_string(),
+                operands: operands.to_vec(),
+            });
+        }
+        self
+    }
+
+    /// Vollständige Meldung, z. B. `Ungültige Eingabe: ...`; `Display` gibt sie auf Deutsch aus
+    pub fn message(&self) -> Message {
+        match self {
+            ProjectError::DivisionByZero { .. } => Message::new("error.division_by_zero", []),
+            ProjectError::OverflowError { message, .. } => {
+                Message::new("error.overflow", [message.clone().into()])
+            }
+            ProjectError::InvalidInput { message, .. } => {
+                Message::new("error.invalid_input", [message.clone().into()])
+            }
+            ProjectError::IoError {
+                message,
+                path: Some(path),
+                ..
+            } => Message::new(
+                "error.io_in_file",
+                [message.into(), path.display().to_string().into()],
+            ),
+            ProjectError::IoError { message, .. } => Message::new("error.io", [message.into()]),
+            ProjectError::ParseError { diagnostic, .. } => {
+                Message::new("error.parse", [diagnostic.summary().into()])
+            }
+            ProjectError::Cancelled => Message::new("error.cancelled", []),
+            ProjectError::AuditViolation { entry, reason } => Message::new(
+                "error.audit_violation",
+                [(*entry).into(), reason.clone().into()],
+            ),
+        }
+    }
+
+    /// Meldung in der gewünschten Sprache; [`ProjectError::code`] bleibt gleich
+    pub fn localized(&self, locale: Locale) -> String {
+        self.message().render(locale)
+    }
+
+    /// Exit-Code der Kommandozeile für diese Fehlerart
+    ///
+    /// | Code | Fehlerart |
+    /// |------|-----------|
+    /// | 1    | kein Fehler: `daemon status` ohne laufenden Daemon ([`NOT_RUNNING_EXIT_CODE`]) |
+    /// | 2    | falscher Aufruf ([`USAGE_EXIT_CODE`]) |
+    /// | 3    | `DivisionByZero` |
+    /// | 4    | `OverflowError` |
+    /// | 5    | `InvalidInput` |
+    /// | 6    | `IoError` |
+    /// | 7    | `ParseError` |
+    /// | 8    | `Cancelled` |
+    /// | 9    | `AuditViolation` |
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ProjectError::DivisionByZero { .. } => 3,
+            ProjectError::OverflowError { .. } => 4,
+            ProjectError::InvalidInput { .. } => 5,
+            ProjectError::IoError { .. } => 6,
+            ProjectError::ParseError { .. } => 7,
+            ProjectError::Cancelled => 8,
+            ProjectError::AuditViolation { .. } => 9,
+        }
+    }
+}
+
+impl From<io::Error> for ProjectError {
+    fn from(error: io::Error) -> Self {
+        ProjectError::IoError {
+            kind: error.kind(),
+            message: error.to_string(),
+            path: None,
+            source: Some(ErrorSource::new(error)),
+        }
+    }
+}
+
+impl From<Diagnostic> for ProjectError {
+    fn from(diagnostic: Diagnostic) -> Self {
+        ProjectError::ParseError {
+            diagnostic: Box::new(diagnostic),
+            source: None,
+        }
+    }
+}
+
+/// Ohne Quelltext; mit Quelltext liefert [`Diagnostic::from_json`] auch einen Auszug
+impl From<serde_json::Error> for ProjectError {
+    fn from(error: serde_json::Error) -> Self {
+        ProjectError::ParseError {
+            diagnostic: Box::new(Diagnostic::from_json(&error, None)),
+            source: Some(ErrorSource::new(error)),
+        }
     }
 }
 
 impl From<std::num::ParseIntError> for ProjectError {
     fn from(error: std::num::ParseIntError) -> Self {
-        ProjectError::ParseError(error.to_string())
+        ProjectError::ParseError {
+            diagnostic: Box::new(Diagnostic::new(error.to_string())),
+            source: Some(ErrorSource::new(error)),
+        }
     }
 }
 
 impl From<std::num::ParseFloatError> for ProjectError {
     fn from(error: std::num::ParseFloatError) -> Self {
-        ProjectError::ParseError(error.to_string())
+        ProjectError::ParseError {
+            diagnostic: Box::new(Diagnostic::new(error.to_string())),
+            source: Some(ErrorSource::new(error)),
+        }
+    }
+}
+
+/// [`io::ErrorKind`] als Name in snake_case, z. B. `not_found`
+///
+/// Arten ohne eigenen Namen werden als `other` serialisiert.
+mod io_kind {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::io::ErrorKind;
+
+    const KINDS: &[(ErrorKind, &str)] = &[
+        (ErrorKind::NotFound, "not_found"),
+        (ErrorKind::PermissionDenied, "permission_denied"),
+        (ErrorKind::AlreadyExists, "already_exists"),
+        (ErrorKind::NotADirectory, "not_a_directory"),
+        (ErrorKind::IsADirectory, "is_a_directory"),
+        (ErrorKind::DirectoryNotEmpty, "directory_not_empty"),
+        (ErrorKind::ReadOnlyFilesystem, "read_only_filesystem"),
+        (ErrorKind::StorageFull, "storage_full"),
+        (ErrorKind::ConnectionRefused, "connection_refused"),
+        (ErrorKind::ConnectionReset, "connection_reset"),
+        (ErrorKind::ConnectionAborted, "connection_aborted"),
+        (ErrorKind::NotConnected, "not_connected"),
+        (ErrorKind::AddrInUse, "addr_in_use"),
+        (ErrorKind::BrokenPipe, "broken_pipe"),
+        (ErrorKind::WouldBlock, "would_block"),
+        (ErrorKind::TimedOut, "timed_out"),
+        (ErrorKind::Interrupted, "interrupted"),
+        (ErrorKind::InvalidInput, "invalid_input"),
+        (ErrorKind::InvalidData, "invalid_data"),
+        (ErrorKind::UnexpectedEof, "unexpected_eof"),
+        (ErrorKind::WriteZero, "write_zero"),
+        (ErrorKind::Unsupported, "unsupported"),
+        (ErrorKind::OutOfMemory, "out_of_memory"),
+        (ErrorKind::Other, "other"),
+    ];
+
+    pub fn serialize<S: Serializer>(kind: &ErrorKind, serializer: S) -> Result<S::Ok, S::Error> {
+        let name = KINDS
+            .iter()
+            .find(|(known, _)| known == kind)
+            .map_or("other", |(_, name)| name);
+        serializer.serialize_str(name)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ErrorKind, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(KINDS
+            .iter()
+            .find(|(_, known)| *known == name)
+            .map_or(ErrorKind::Other, |(kind, _)| *kind))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::error::Error as _;
 
     #[test]
     fn test_error_display() {
-        let error = ProjectError::DivisionByZero;
+        let error = ProjectError::division_by_zero();
         assert_eq!(error.to_string(), "Division durch Null ist nicht erlaubt");
     }
 
     #[test]
     fn test_overflow_error() {
-        let error = ProjectError::OverflowError("Test overflow".to_string());
+        let error = ProjectError::overflow("Test overflow");
         assert_eq!(error.to_string(), "Overflow-Fehler: Test overflow");
     }
 
     #[test]
     fn test_invalid_input_error() {
-        let error = ProjectError::InvalidInput("Invalid number".to_string());
+        let error = ProjectError::invalid_input("Invalid number");
         assert_eq!(error.to_string(), "Ungültige Eingabe: Invalid number");
     }
 
     #[test]
     fn test_io_error_conversion() {
-        let io_error = std::io::Error::new(std::io::ErrorKind::NotFound, "File not found");
-        let project_error = ProjectError::from(io_error);
-        
-        match project_error {
-            ProjectError::IoError(msg) => assert!(msg.contains("File not found")),
-            _ => panic!("Expected IoError"),
-        }
+        let io_error = io::Error::new(io::ErrorKind::NotFound, "File not found");
+        let project_error = ProjectError::io(io_error, "verlauf.json");
+
+        assert_eq!(project_error.io_kind(), Some(io::ErrorKind::NotFound));
+        assert_eq!(project_error.path(), Some(Path::new("verlauf.json")));
+        assert_eq!(
+            project_error.to_string(),
+            "IO-Fehler: File not found (Datei verlauf.json)"
+        );
+        let source = project_error.source().unwrap();
+        assert_eq!(source.to_string(), "File not found");
+
+        let denied = ProjectError::from(io::Error::from(io::ErrorKind::PermissionDenied));
+        assert_eq!(denied.io_kind(), Some(io::ErrorKind::PermissionDenied));
+        assert_eq!(denied.path(), None);
     }
 
     #[test]
     fn test_parse_int_error_conversion() {
         let parse_error = "abc".parse::<i32>().unwrap_err();
         let project_error = ProjectError::from(parse_error);
-        
-        match project_error {
-            ProjectError::ParseError(_) => (),
-            _ => panic!("Expected ParseError"),
-        }
+
+        assert_eq!(project_error.code(), "parse_error");
+        let source = project_error.source().unwrap();
+        assert!(source.to_string().contains("invalid digit"));
     }
 
     #[test]
     fn test_parse_float_error_conversion() {
         let parse_error = "xyz".parse::<f64>().unwrap_err();
         let project_error = ProjectError::from(parse_error);
-        
+
         match project_error {
-            ProjectError::ParseError(_) => (),
+            ProjectError::ParseError { source, .. } => assert!(source.is_some()),
             _ => panic!("Expected ParseError"),
         }
     }
 
+    #[test]
+    fn test_serde_json_error_conversion() {
+        let json_error = serde_json::from_str::<Vec<f64>>("[1.0,").unwrap_err();
+        let project_error = ProjectError::from(json_error).with_path("zahlen.json");
+
+        match &project_error {
+            ProjectError::ParseError { diagnostic, source } => {
+                assert_eq!(diagnostic.span.as_ref().map(|span| span.line), Some(1));
+                assert_eq!(diagnostic.file.as_deref(), Some("zahlen.json"));
+                let source = source.as_ref().unwrap().get();
+                assert!(source.downcast_ref::<serde_json::Error>().is_some());
+            }
+            _ => panic!("Expected ParseError"),
+        }
+        assert_eq!(project_error.path(), Some(Path::new("zahlen.json")));
+    }
+
+    #[test]
+    fn test_audit_violation_display() {
+        let error = ProjectError::AuditViolation {
+            entry: 3,
+            reason: Message::new("audit.modified", []),
+        };
+        assert_eq!(
+            error.to_string(),
+            "Audit-Protokoll verletzt bei Eintrag 3: Inhalt wurde verändert"
+        );
+    }
+
+    #[test]
+    fn test_with_operation() {
+        let error = ProjectError::division_by_zero()
+            .with_operation("divide", &[1.0, 0.0])
+            .with_operation("modulo", &[]);
+        let operation = error.operation().unwrap();
+        assert_eq!(operation.name, "divide");
+        assert_eq!(operation.operands, vec![1.0, 0.0]);
+
+        assert_eq!(
+            ProjectError::Cancelled
+                .with_operation("add", &[1.0])
+                .operation(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_error_codes() {
+        assert_eq!(ProjectError::division_by_zero().code(), "division_by_zero");
+        assert_eq!(
+            ProjectError::from(Diagnostic::new("")).code(),
+            "parse_error"
+        );
+        assert_eq!(ProjectError::Cancelled.code(), "cancelled");
+    }
+
+    fn all_variants() -> Vec<ProjectError> {
+        vec![
+            ProjectError::division_by_zero().with_operation("divide", &[1.0, 0.0]),
+            ProjectError::overflow("zu groß"),
+            ProjectError::invalid_input("leer"),
+            ProjectError::io(io::Error::from(io::ErrorKind::PermissionDenied), "/etc/x"),
+            ProjectError::from(Diagnostic::new("kaputt")),
+            ProjectError::Cancelled,
+            ProjectError::AuditViolation {
+                entry: 1,
+                reason: Message::text(""),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_exit_codes_are_distinct() {
+        let errors = all_variants();
+        let mut codes: Vec<i32> = errors.iter().map(ProjectError::exit_code).collect();
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), errors.len());
+        assert!(codes.iter().all(|code| *code > USAGE_EXIT_CODE));
+        assert!(!codes.contains(&NOT_RUNNING_EXIT_CODE));
+    }
+
+    #[test]
+    fn test_serialize_roundtrip() {
+        for error in all_variants() {
+            let value = serde_json::to_value(&error).unwrap();
+            assert_eq!(value["code"], error.code());
+            let restored: ProjectError = serde_json::from_value(value).unwrap();
+            assert_eq!(restored, error);
+        }
+
+        let error = ProjectError::io(io::Error::from(io::ErrorKind::NotFound), "a.json");
+        let value = serde_json::to_value(&error).unwrap();
+        assert_eq!(value["kind"], "not_found");
+        assert_eq!(value["path"], "a.json");
+        let value = serde_json::to_value(ProjectError::division_by_zero()).unwrap();
+        assert_eq!(value, serde_json::json!({"code": "division_by_zero"}));
+    }
+
+    #[test]
+    fn test_serialize_non_finite_operands() {
+        let error = ProjectError::invalid_input("NaN")
+            .with_operation("add", &[f64::NAN, f64::NEG_INFINITY, 1.0]);
+        let value = serde_json::to_value(&error).unwrap();
+        assert_eq!(value["operation"]["operands"], serde_json::json!(["NaN", "-inf", 1.0]));
+
+        let restored: ProjectError = serde_json::from_value(value).unwrap();
+        let operands = &restored.operation().unwrap().operands;
+        assert!(operands[0].is_nan());
+        assert_eq!(operands[1..], [f64::NEG_INFINITY, 1.0]);
+    }
+
     #[test]
     fn test_error_equality() {
-        let error1 = ProjectError::DivisionByZero;
-        let error2 = ProjectError::DivisionByZero;
+        let error1 = ProjectError::division_by_zero();
+        let error2 = ProjectError::division_by_zero();
         assert_eq!(error1, error2);
+        assert_ne!(error1, error2.with_operation("divide", &[1.0, 0.0]));
     }
 
     #[test]
     fn test_error_clone() {
-        let error = ProjectError::DivisionByZero;
+        let error = ProjectError::from(io::Error::from(io::ErrorKind::NotFound));
         let cloned = error.clone();
         assert_eq!(error, cloned);
+        assert!(cloned.source().is_some());
     }
 }
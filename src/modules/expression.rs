@@ -0,0 +1,579 @@
+//! Auswertung arithmetischer Ausdrücke
+
+use crate::modules::diagnostic::{self, Diagnostic};
+use crate::modules::error::ProjectError;
+use crate::modules::i18n::Message;
+use crate::modules::operations::{OperationRegistry, BUILT_INS};
+use crate::{Calculator, OperationType};
+
+/// Syntaxbaum eines arithmetischen Ausdrucks
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    Number(f64),
+    Variable(String),
+    Negate(Box<Expression>),
+    Binary {
+        operation_type: OperationType,
+        left: Box<Expression>,
+        right: Box<Expression>,
+    },
+    /// Aufruf einer Operation als Funktion, z. B. `power(2, 10)`
+    Call {
+        operation_type: OperationType,
+        arguments: Vec<Expression>,
+    },
+}
+
+impl Expression {
+    /// Parst einen Ausdruck aus Zahlen, Variablen, `+ - * /` und Klammern
+    ///
+    /// Grundrechenarten können auch als Funktion geschrieben werden, z. B.
+    /// `add(1, 2)`; für eigene Operationen siehe [`Expression::parse_with`].
+    ///
+    /// # Errors
+    ///
+    /// Gibt [`ProjectError::ParseError`] mit der Position des ersten Fehlers
+    /// zurück, siehe [`Diagnostic`].
+    ///
+    /// # Beispiel
+    ///
+    /// ```rust
+    /// use rust_project_complete::modules::expression::Expression;
+    ///
+    /// assert!(Expression::parse("2 * (miete + 50)").is_ok());
+    /// assert!(Expression::parse("2 * (miete + 50").is_err());
+    /// ```
+    pub fn parse(input: &str) -> Result<Self, ProjectError> {
+        Self::parse_with(input, &BUILT_INS)
+    }
+
+    /// Parst einen Ausdruck mit den Operationen aus `registry`
+    ///
+    /// Jede Operation kann als Funktion `name(a, b, …)` aufgerufen werden;
+    /// zweistellige Operationen mit Symbol stehen zusätzlich als Infix-Operator
+    /// zur Verfügung.
+    ///
+    /// # Errors
+    ///
+    /// Gibt [`ProjectError::ParseError`] mit der Position des ersten Fehlers
+    /// zurück, auch für unbekannte Operationen und falsche Anzahl an Argumenten.
+    pub fn parse_with(input: &str, registry: &OperationRegistry) -> Result<Self, ProjectError> {
+        let tokens = tokenize(input, registry)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            position: 0,
+            input,
+            registry,
+        };
+        let expression = parser.expression()?;
+        match parser.peek() {
+            None => Ok(expression),
+            Some(token) => Err(unexpected(input, token)),
+        }
+    }
+
+    /// Wertet den Ausdruck aus
+    ///
+    /// Jede Operation wird über den Calculator ausgeführt und damit im
+    /// Verlauf festgehalten; Variablen werden aus dem Calculator gelesen.
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn eine Variable unbekannt ist oder eine
+    /// Operation fehlschlägt.
+    pub fn evaluate(&self, calculator: &mut Calculator) -> Result<f64, ProjectError> {
+        match self {
+            Expression::Number(value) => Ok(*value),
+            Expression::Variable(name) => calculator.get_variable(name).ok_or_else(|| {
+                ProjectError::invalid_input(Message::new("expression.unknown_variable", [name.into()]))
+            }),
+            Expression::Negate(inner) => Ok(-inner.evaluate(calculator)?),
+            Expression::Binary {
+                operation_type,
+                left,
+                right,
+            } => {
+                let a = left.evaluate(calculator)?;
+                let b = right.evaluate(calculator)?;
+                calculator.apply(operation_type.name(), &[a, b])
+            }
+            Expression::Call {
+                operation_type,
+                arguments,
+            } => {
+                let operands = arguments
+                    .iter()
+                    .map(|argument| argument.evaluate(calculator))
+                    .collect::<Result<Vec<_>, _>>()?;
+                calculator.apply(operation_type.name(), &operands)
+            }
+        }
+    }
+
+    /// Wertet den Ausdruck ohne Calculator aus, z. B. als Rumpf einer Operation
+    ///
+    /// Operationen werden direkt aus `registry` berechnet und nicht im Verlauf
+    /// festgehalten; Variablen liefert `variable`.
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn eine Variable oder Operation unbekannt
+    /// ist oder eine Operation fehlschlägt.
+    pub fn evaluate_with(
+        &self,
+        variable: &dyn Fn(&str) -> Option<f64>,
+        registry: &OperationRegistry,
+    ) -> Result<f64, ProjectError> {
+        let apply = |operation_type: &OperationType, operands: &[f64]| {
+            let operator = registry.get(operation_type.name()).ok_or_else(|| {
+                ProjectError::invalid_input(Message::new("operation.unknown", [operation_type.name().into()]))
+            })?;
+            operator.validate(operands)?;
+            operator.evaluate(operands)
+        };
+        match self {
+            Expression::Number(value) => Ok(*value),
+            Expression::Variable(name) => variable(name).ok_or_else(|| {
+                ProjectError::invalid_input(Message::new("expression.unknown_variable", [name.into()]))
+            }),
+            Expression::Negate(inner) => Ok(-inner.evaluate_with(variable, registry)?),
+            Expression::Binary {
+                operation_type,
+                left,
+                right,
+            } => {
+                let a = left.evaluate_with(variable, registry)?;
+                let b = right.evaluate_with(variable, registry)?;
+                apply(operation_type, &[a, b])
+            }
+            Expression::Call {
+                operation_type,
+                arguments,
+            } => {
+                let operands = arguments
+                    .iter()
+                    .map(|argument| argument.evaluate_with(variable, registry))
+                    .collect::<Result<Vec<_>, _>>()?;
+                apply(operation_type, &operands)
+            }
+        }
+    }
+
+    /// Ruft `visit` für jede Variable im Ausdruck auf
+    pub fn visit_variables(&self, visit: &mut dyn FnMut(&str)) {
+        match self {
+            Expression::Number(_) => {}
+            Expression::Variable(name) => visit(name),
+            Expression::Negate(inner) => inner.visit_variables(visit),
+            Expression::Binary { left, right, .. } => {
+                left.visit_variables(visit);
+                right.visit_variables(visit);
+            }
+            Expression::Call { arguments, .. } => {
+                for argument in arguments {
+                    argument.visit_variables(visit);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Number(f64),
+    Identifier(String),
+    Operator(String),
+    LeftParen,
+    RightParen,
+    Comma,
+}
+
+/// Token mit Byte-Bereich `position..end` in der Eingabe
+#[derive(Debug, Clone, PartialEq)]
+struct Token {
+    kind: TokenKind,
+    position: usize,
+    end: usize,
+}
+
+fn tokenize(input: &str, registry: &OperationRegistry) -> Result<Vec<Token>, ProjectError> {
+    // Längere Symbole zuerst, damit z. B. `**` nicht als `*` gelesen wird
+    let mut symbols: Vec<&str> = registry.iter().filter_map(|op| op.symbol()).collect();
+    symbols.sort_by_key(|symbol| std::cmp::Reverse(symbol.len()));
+
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(position, c)) = chars.peek() {
+        if let Some(symbol) = symbols.iter().find(|s| input[position..].starts_with(**s)) {
+            while chars.peek().is_some_and(|&(index, _)| index < position + symbol.len()) {
+                chars.next();
+            }
+            tokens.push(Token {
+                kind: TokenKind::Operator(symbol.to_string()),
+                position,
+                end: position + symbol.len(),
+            });
+            continue;
+        }
+
+        let kind = match c {
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '(' => {
+                chars.next();
+                TokenKind::LeftParen
+            }
+            ')' => {
+                chars.next();
+                TokenKind::RightParen
+            }
+            ',' => {
+                chars.next();
+                TokenKind::Comma
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let mut end = position;
+                let mut previous = c;
+                while let Some(&(index, next)) = chars.peek() {
+                    let exponent_sign = (next == '+' || next == '-') && matches!(previous, 'e' | 'E');
+                    if !(next.is_ascii_alphanumeric() || next == '.' || exponent_sign) {
+                        break;
+                    }
+                    end = index + next.len_utf8();
+                    previous = next;
+                    chars.next();
+                }
+                let literal = &input[position..end];
+                let value = literal.parse().map_err(|_| {
+                    Diagnostic::new(Message::new("expression.invalid_number", [literal.into()]))
+                        .with_span(input, position..end)
+                })?;
+                TokenKind::Number(value)
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut end = position;
+                while let Some(&(index, next)) = chars.peek() {
+                    if !(next.is_alphanumeric() || next == '_') {
+                        break;
+                    }
+                    end = index + next.len_utf8();
+                    chars.next();
+                }
+                TokenKind::Identifier(input[position..end].to_string())
+            }
+            other => {
+                let end = position + other.len_utf8();
+                return Err(Diagnostic::new(Message::new("expression.unexpected_character", [other.into()]))
+                    .with_span(input, position..end)
+                    .into());
+            }
+        };
+        let end = chars.peek().map_or(input.len(), |&(index, _)| index);
+        tokens.push(Token {
+            kind,
+            position,
+            end,
+        });
+    }
+    Ok(tokens)
+}
+
+/// Rekursiver Abstieg mit üblicher Operatorrangfolge
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+    input: &'a str,
+    registry: &'a OperationRegistry,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.position);
+        self.position += 1;
+        token
+    }
+
+    /// Liest den nächsten Operator, falls `accept` ihn annimmt
+    fn next_operator(&mut self, accept: impl Fn(&str) -> bool) -> Option<String> {
+        match self.peek() {
+            Some(Token {
+                kind: TokenKind::Operator(symbol),
+                ..
+            }) if accept(symbol) => {
+                let symbol = symbol.clone();
+                self.position += 1;
+                Some(symbol)
+            }
+            _ => None,
+        }
+    }
+
+    fn binary(&self, symbol: &str, left: Expression, right: Expression) -> Expression {
+        let operation_type = self
+            .registry
+            .by_symbol(symbol)
+            .map(|operator| operator.operation_type())
+            .or_else(|| BUILT_INS.by_symbol(symbol).map(|operator| operator.operation_type()))
+            .unwrap_or(OperationType::Custom(symbol.to_string()));
+        Expression::Binary {
+            operation_type,
+            left: Box::new(left),
+            right: Box::new(right),
+        }
+    }
+
+    fn expression(&mut self) -> Result<Expression, ProjectError> {
+        let mut left = self.term()?;
+        while let Some(symbol) = self.next_operator(|s| s == "+" || s == "-") {
+            let right = self.term()?;
+            left = self.binary(&symbol, left, right);
+        }
+        Ok(left)
+    }
+
+    /// `*`, `/` und alle Symbole eigener Operationen
+    fn term(&mut self) -> Result<Expression, ProjectError> {
+        let mut left = self.unary()?;
+        while let Some(symbol) = self.next_operator(|s| s != "+" && s != "-") {
+            let right = self.unary()?;
+            left = self.binary(&symbol, left, right);
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expression, ProjectError> {
+        match self.next_operator(|s| s == "+" || s == "-").as_deref() {
+            Some("-") => Ok(Expression::Negate(Box::new(self.unary()?))),
+            Some(_) => self.unary(),
+            None => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expression, ProjectError> {
+        let input = self.input;
+        let token = self.next().ok_or_else(|| {
+            Diagnostic::new(Message::new("expression.unexpected_end", []))
+                .with_span(input, input.len()..input.len())
+        })?;
+        match &token.kind {
+            TokenKind::Number(value) => Ok(Expression::Number(*value)),
+            TokenKind::Identifier(name) => {
+                let (name, position) = (name.clone(), token.position);
+                match self.peek() {
+                    Some(Token {
+                        kind: TokenKind::LeftParen,
+                        ..
+                    }) => self.call(name, position),
+                    _ => Ok(Expression::Variable(name)),
+                }
+            }
+            TokenKind::LeftParen => {
+                let open = token.position;
+                let inner = self.expression()?;
+                match self.next() {
+                    Some(Token {
+                        kind: TokenKind::RightParen,
+                        ..
+                    }) => Ok(inner),
+                    Some(token) => Err(unexpected(input, token)),
+                    None => Err(unclosed(input, open)),
+                }
+            }
+            _ => Err(unexpected(input, token)),
+        }
+    }
+
+    /// Liest `name(a, b, …)`; die öffnende Klammer ist das nächste Token
+    fn call(&mut self, name: String, position: usize) -> Result<Expression, ProjectError> {
+        let (input, name_span) = (self.input, position..position + name.len());
+        let operator = self.registry.get(&name).ok_or_else(|| {
+            let mut diagnostic = Diagnostic::new(Message::new("operation.unknown", [(&name).into()]))
+                .with_span(input, name_span.clone());
+            let names = self.registry.iter().map(|operator| operator.name());
+            if let Some(similar) = diagnostic::closest(&name, names) {
+                diagnostic = diagnostic
+                    .with_suggestion(Message::new("diagnostic.did_you_mean", [similar.into()]));
+            }
+            diagnostic
+        })?;
+        let (operation_type, arity) = (operator.operation_type(), operator.arity());
+        self.next();
+
+        let mut arguments = Vec::new();
+        let empty = matches!(
+            self.peek(),
+            Some(Token {
+                kind: TokenKind::RightParen,
+                ..
+            })
+        );
+        if !empty {
+            loop {
+                arguments.push(self.expression()?);
+                match self.peek() {
+                    Some(Token {
+                        kind: TokenKind::Comma,
+                        ..
+                    }) => {
+                        self.next();
+                    }
+                    _ => break,
+                }
+            }
+        }
+        match self.next() {
+            Some(Token {
+                kind: TokenKind::RightParen,
+                ..
+            }) => {}
+            Some(token) => return Err(unexpected(input, token)),
+            None => return Err(unclosed(input, name_span.end)),
+        }
+
+        if arguments.len() != arity {
+            let message = Message::new(
+                "expression.arity",
+                [name.into(), arity.into(), arguments.len().into()],
+            );
+            return Err(Diagnostic::new(message).with_span(input, name_span).into());
+        }
+        Ok(Expression::Call {
+            operation_type,
+            arguments,
+        })
+    }
+}
+
+fn unexpected(input: &str, token: &Token) -> ProjectError {
+    let span = token.position..token.end;
+    Diagnostic::new(Message::new("expression.unexpected", [input[span.clone()].into()]))
+        .with_span(input, span)
+        .into()
+}
+
+/// Öffnende Klammer an `open` ohne schließende
+fn unclosed(input: &str, open: usize) -> ProjectError {
+    Diagnostic::new(Message::new("expression.unclosed", []))
+        .with_span(input, open..open + 1)
+        .with_suggestion(Message::new("diagnostic.close_parenthesis", []))
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::history::HistoryQuery;
+
+    fn eval(input: &str) -> Result<f64, ProjectError> {
+        Calculator::new().evaluate(input)
+    }
+
+    #[test]
+    fn test_precedence_and_parentheses() {
+        assert_eq!(eval("2 + 3 * 4").unwrap(), 14.0);
+        assert_eq!(eval("(2 + 3) * 4").unwrap(), 20.0);
+        assert_eq!(eval("10 - 4 - 3").unwrap(), 3.0);
+        assert_eq!(eval("-2 * -(3 + 1)").unwrap(), 8.0);
+        assert_eq!(eval("1.5e2 / 3").unwrap(), 50.0);
+    }
+
+    #[test]
+    fn test_variables() {
+        let mut calc = Calculator::new();
+        calc.set_variable("miete", 900.0);
+
+        assert_eq!(calc.evaluate("miete * 12").unwrap(), 10800.0);
+        assert!(matches!(
+            calc.evaluate("strom * 12"),
+            Err(ProjectError::InvalidInput { .. })
+        ));
+    }
+
+    #[test]
+    fn test_operations_are_recorded() {
+        let mut calc = Calculator::new();
+        calc.evaluate("1 + 2 * 3").unwrap();
+
+        let history = calc.query_history(&HistoryQuery::new()).unwrap();
+        let types: Vec<_> = history.iter().map(|op| op.operation_type.clone()).collect();
+        assert_eq!(types, vec![OperationType::Multiply, OperationType::Add]);
+    }
+
+    #[test]
+    fn test_errors_report_span() {
+        let diagnostic = |input: &str| match eval(input) {
+            Err(ProjectError::ParseError { diagnostic, .. }) => *diagnostic,
+            other => panic!("Expected ParseError, got {:?}", other),
+        };
+        let column = |input: &str| diagnostic(input).span.unwrap().column;
+
+        assert_eq!(column("2 + * 3"), 5);
+        assert_eq!(column("2 + 3)"), 6);
+        assert_eq!(column("(2 + 3"), 1);
+        assert_eq!(column("2 +"), 4);
+        assert!(diagnostic("2 +").message.to_string().contains("Ende"));
+        assert_eq!(diagnostic("2 $ 3").message.to_string(), "Unerwartetes Zeichen '$'");
+
+        let number = diagnostic("1 + 1.2.3");
+        assert_eq!(number.message.to_string(), "Ungültige Zahl '1.2.3'");
+        assert_eq!(number.span.as_ref().map(|span| span.length), Some(5));
+        assert_eq!(number.excerpt(false).unwrap(), "  |\n1 | 1 + 1.2.3\n  |     ^^^^^\n");
+
+        let unclosed = diagnostic("(2 + 3");
+        assert_eq!(unclosed.suggestion.as_ref().unwrap().to_string(), "fehlende ')' ergänzen");
+        assert_eq!(
+            unclosed.to_string(),
+            "Klammer wird nicht geschlossen (Zeile 1, Spalte 1); fehlende ')' ergänzen"
+        );
+    }
+
+    #[test]
+    fn test_custom_operations() {
+        use crate::modules::operations::FnOperator;
+        use std::sync::Arc;
+
+        let mut calc = Calculator::new();
+        calc.register_operation(Arc::new(
+            FnOperator::new("power", 2, |x| Ok(x[0].powf(x[1]))).symbol("**"),
+        ))
+        .unwrap();
+        calc.register_operation(Arc::new(FnOperator::new("clamp", 3, |x| {
+            Ok(x[0].clamp(x[1], x[2]))
+        })))
+        .unwrap();
+
+        assert_eq!(calc.evaluate("1 + 2 ** 3 * 2").unwrap(), 17.0);
+        assert_eq!(calc.evaluate("clamp(power(2, 5), 0, 10) - 1").unwrap(), 9.0);
+        assert_eq!(calc.evaluate("add(1, 2)").unwrap(), 3.0);
+
+        let message = |input: &str| match calc.snapshot().unwrap().evaluate(input) {
+            Err(ProjectError::ParseError { diagnostic, .. }) => diagnostic.to_string(),
+            other => panic!("Expected ParseError, got {:?}", other),
+        };
+        assert_eq!(
+            message("wurzel(4)"),
+            "Unbekannte Operation 'wurzel' (Zeile 1, Spalte 1)"
+        );
+        assert_eq!(
+            message("2 * powr(2, 3)"),
+            "Unbekannte Operation 'powr' (Zeile 1, Spalte 5); meinten Sie 'power'?"
+        );
+        assert!(message("1 + clamp(1, 2)").contains("erwartet 3 Argumente, erhalten 2"));
+        assert!(message("power(2, 3").contains("Klammer"));
+
+        // Ohne Registrierung sind die Operationen unbekannt
+        assert!(Calculator::new().evaluate("2 ** 3").is_err());
+    }
+
+    #[test]
+    fn test_division_by_zero() {
+        assert_eq!(eval("1 / (2 - 2)"), Err(ProjectError::division_by_zero().with_operation("divide", &[1.0, 0.0])));
+    }
+}
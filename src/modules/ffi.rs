@@ -0,0 +1,441 @@
+//! C-Schnittstelle für die Einbettung in C- und C++-Programme
+//!
+//! Der Header `include/rust_project_complete.h` wird mit cbindgen aus diesem
+//! Modul erzeugt. Alle Funktionen geben einen [`CalcStatus`] zurück; die
+//! ausführliche Meldung des letzten Fehlers im aktuellen Thread liefert
+//! [`calc_last_error_message`]. Ein Handle darf nicht gleichzeitig aus
+//! mehreren Threads verwendet werden.
+
+use crate::modules::error::ProjectError;
+use crate::modules::history::HistoryQuery;
+use crate::{Calculator, Operation, OperationType};
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+/// Ergebnis eines Aufrufs
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalcStatus {
+    Ok = 0,
+    DivisionByZero = 1,
+    Overflow = 2,
+    InvalidInput = 3,
+    IoError = 4,
+    ParseError = 5,
+    Cancelled = 6,
+    AuditViolation = 7,
+    /// Ein Zeiger war `NULL` oder eine Zeichenkette kein gültiges UTF-8
+    InvalidArgument = 100,
+    /// Ein interner Fehler hat die Operation abgebrochen
+    Panic = 101,
+}
+
+impl From<&ProjectError> for CalcStatus {
+    fn from(error: &ProjectError) -> Self {
+        match error {
+            ProjectError::DivisionByZero { .. } => CalcStatus::DivisionByZero,
+            ProjectError::OverflowError { .. } => CalcStatus::Overflow,
+            ProjectError::InvalidInput { .. } => CalcStatus::InvalidInput,
+            ProjectError::IoError { .. } => CalcStatus::IoError,
+            ProjectError::ParseError { .. } => CalcStatus::ParseError,
+            ProjectError::Cancelled => CalcStatus::Cancelled,
+            ProjectError::AuditViolation { .. } => CalcStatus::AuditViolation,
+        }
+    }
+}
+
+/// Art einer Operation im Verlauf
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalcOperationType {
+    Add = 0,
+    Subtract = 1,
+    Multiply = 2,
+    Divide = 3,
+    /// Registrierte Operation; der Name ist über die C-Schnittstelle nicht verfügbar
+    Custom = 4,
+}
+
+impl From<&OperationType> for CalcOperationType {
+    fn from(operation_type: &OperationType) -> Self {
+        match operation_type {
+            OperationType::Add => CalcOperationType::Add,
+            OperationType::Subtract => CalcOperationType::Subtract,
+            OperationType::Multiply => CalcOperationType::Multiply,
+            OperationType::Divide => CalcOperationType::Divide,
+            OperationType::Custom(_) => CalcOperationType::Custom,
+        }
+    }
+}
+
+/// Eintrag im Verlauf
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CalcOperation {
+    pub id: u64,
+    pub operation_type: CalcOperationType,
+    pub operands: [f64; 2],
+    pub result: f64,
+    pub timestamp: u64,
+}
+
+impl From<&Operation> for CalcOperation {
+    fn from(operation: &Operation) -> Self {
+        let mut operands = [f64::NAN; 2];
+        for (slot, value) in operands.iter_mut().zip(&operation.operands) {
+            *slot = *value;
+        }
+        Self {
+            id: operation.id,
+            operation_type: CalcOperationType::from(&operation.operation_type),
+            operands,
+            result: operation.result,
+            timestamp: operation.timestamp,
+        }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    // Eingebettete Nullbytes würden die Meldung abschneiden
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// Führt `f` aus, merkt sich Fehler und fängt Paniken ab
+fn guard(f: impl FnOnce() -> Result<(), ProjectError>) -> CalcStatus {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => CalcStatus::Ok,
+        Ok(Err(error)) => {
+            set_last_error(error.to_string());
+            CalcStatus::from(&error)
+        }
+        Err(_) => {
+            set_last_error("Interner Fehler".to_string());
+            CalcStatus::Panic
+        }
+    }
+}
+
+/// Meldet einen Fehler des Aufrufers, z. B. einen `NULL`-Zeiger
+fn invalid_argument(message: &str) -> CalcStatus {
+    set_last_error(message.to_string());
+    CalcStatus::InvalidArgument
+}
+
+fn null_pointer() -> CalcStatus {
+    invalid_argument("Zeiger darf nicht NULL sein")
+}
+
+/// Undurchsichtiges Handle auf einen Calculator mit Verlauf im Arbeitsspeicher
+pub struct CalcCalculator(Calculator);
+
+/// Erstellt einen neuen Calculator; freigeben mit [`calc_free`]
+#[no_mangle]
+pub extern "C" fn calc_new() -> *mut CalcCalculator {
+    Box::into_raw(Box::new(CalcCalculator(Calculator::new())))
+}
+
+/// Gibt einen Calculator frei; `NULL` wird ignoriert
+///
+/// # Safety
+///
+/// `calc` muss von [`calc_new`] stammen und darf danach nicht mehr verwendet werden.
+#[no_mangle]
+pub unsafe extern "C" fn calc_free(calc: *mut CalcCalculator) {
+    if !calc.is_null() {
+        drop(Box::from_raw(calc));
+    }
+}
+
+/// Gemeinsamer Rumpf der Grundrechenarten
+unsafe fn binary_operation(
+    calc: *mut CalcCalculator,
+    result: *mut f64,
+    operation: impl FnOnce(&mut Calculator) -> Result<f64, ProjectError>,
+) -> CalcStatus {
+    let (Some(calc), Some(result)) = (calc.as_mut(), result.as_mut()) else {
+        return null_pointer();
+    };
+    guard(|| {
+        *result = operation(&mut calc.0)?;
+        Ok(())
+    })
+}
+
+/// Addiert zwei Zahlen und schreibt das Ergebnis nach `result`
+///
+/// # Safety
+///
+/// `calc` muss ein gültiges Handle und `result` ein gültiger Zeiger sein.
+#[no_mangle]
+pub unsafe extern "C" fn calc_add(
+    calc: *mut CalcCalculator,
+    a: f64,
+    b: f64,
+    result: *mut f64,
+) -> CalcStatus {
+    binary_operation(calc, result, |calc| calc.add(a, b))
+}
+
+/// Subtrahiert zwei Zahlen und schreibt das Ergebnis nach `result`
+///
+/// # Safety
+///
+/// `calc` muss ein gültiges Handle und `result` ein gültiger Zeiger sein.
+#[no_mangle]
+pub unsafe extern "C" fn calc_subtract(
+    calc: *mut CalcCalculator,
+    a: f64,
+    b: f64,
+    result: *mut f64,
+) -> CalcStatus {
+    binary_operation(calc, result, |calc| calc.subtract(a, b))
+}
+
+/// Multipliziert zwei Zahlen und schreibt das Ergebnis nach `result`
+///
+/// # Safety
+///
+/// `calc` muss ein gültiges Handle und `result` ein gültiger Zeiger sein.
+#[no_mangle]
+pub unsafe extern "C" fn calc_multiply(
+    calc: *mut CalcCalculator,
+    a: f64,
+    b: f64,
+    result: *mut f64,
+) -> CalcStatus {
+    binary_operation(calc, result, |calc| calc.multiply(a, b))
+}
+
+/// Dividiert zwei Zahlen und schreibt das Ergebnis nach `result`
+///
+/// # Safety
+///
+/// `calc` muss ein gültiges Handle und `result` ein gültiger Zeiger sein.
+#[no_mangle]
+pub unsafe extern "C" fn calc_divide(
+    calc: *mut CalcCalculator,
+    a: f64,
+    b: f64,
+    result: *mut f64,
+) -> CalcStatus {
+    binary_operation(calc, result, |calc| calc.divide(a, b))
+}
+
+/// Berechnet die Fakultät von `n`
+///
+/// # Safety
+///
+/// `calc` muss ein gültiges Handle und `result` ein gültiger Zeiger sein.
+#[no_mangle]
+pub unsafe extern "C" fn calc_factorial(
+    calc: *mut CalcCalculator,
+    n: u64,
+    result: *mut u64,
+) -> CalcStatus {
+    let (Some(calc), Some(result)) = (calc.as_mut(), result.as_mut()) else {
+        return null_pointer();
+    };
+    guard(|| {
+        *result = calc.0.factorial(n)?;
+        Ok(())
+    })
+}
+
+/// Wertet einen arithmetischen Ausdruck (UTF-8) aus
+///
+/// # Safety
+///
+/// `calc` muss ein gültiges Handle, `expression` eine nullterminierte
+/// Zeichenkette und `result` ein gültiger Zeiger sein.
+#[no_mangle]
+pub unsafe extern "C" fn calc_evaluate(
+    calc: *mut CalcCalculator,
+    expression: *const c_char,
+    result: *mut f64,
+) -> CalcStatus {
+    let (Some(calc), Some(result), false) = (calc.as_mut(), result.as_mut(), expression.is_null())
+    else {
+        return null_pointer();
+    };
+    let Ok(expression) = CStr::from_ptr(expression).to_str() else {
+        return invalid_argument("Ausdruck ist kein gültiges UTF-8");
+    };
+    guard(|| {
+        *result = calc.0.evaluate(expression)?;
+        Ok(())
+    })
+}
+
+/// Gibt die Anzahl der Operationen im Verlauf zurück; 0 bei `NULL`
+///
+/// # Safety
+///
+/// `calc` muss `NULL` oder ein gültiges Handle sein.
+#[no_mangle]
+pub unsafe extern "C" fn calc_history_count(calc: *const CalcCalculator) -> usize {
+    calc.as_ref().map_or(0, |calc| calc.0.history_count())
+}
+
+/// Schreibt den Verlaufseintrag an Position `index` (ab 0) nach `entry`
+///
+/// # Safety
+///
+/// `calc` muss ein gültiges Handle und `entry` ein gültiger Zeiger sein.
+#[no_mangle]
+pub unsafe extern "C" fn calc_history_entry(
+    calc: *mut CalcCalculator,
+    index: usize,
+    entry: *mut CalcOperation,
+) -> CalcStatus {
+    let (Some(calc), Some(entry)) = (calc.as_mut(), entry.as_mut()) else {
+        return null_pointer();
+    };
+    guard(|| {
+        let page = calc.0.query_history(&HistoryQuery::new().offset(index).limit(1))?;
+        let operation = page.first().ok_or_else(|| {
+            ProjectError::invalid_input(format!(
+                "Index {} außerhalb des Verlaufs ({} Einträge)",
+                index,
+                calc.0.history_count()
+            ))
+        })?;
+        *entry = CalcOperation::from(operation);
+        Ok(())
+    })
+}
+
+/// Löscht den Verlauf
+///
+/// # Safety
+///
+/// `calc` muss ein gültiges Handle sein.
+#[no_mangle]
+pub unsafe extern "C" fn calc_clear_history(calc: *mut CalcCalculator) -> CalcStatus {
+    match calc.as_mut() {
+        Some(calc) => guard(|| calc.0.clear_history()),
+        None => null_pointer(),
+    }
+}
+
+/// Meldung des letzten Fehlers im aktuellen Thread oder `NULL`
+///
+/// Der Zeiger bleibt bis zum nächsten Aufruf im selben Thread gültig und darf
+/// nicht freigegeben werden.
+#[no_mangle]
+pub extern "C" fn calc_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |m| m.as_ptr()))
+}
+
+/// Kurzer, gleichbleibender Name eines Status, z. B. `"division_by_zero"`
+#[no_mangle]
+pub extern "C" fn calc_status_name(status: CalcStatus) -> *const c_char {
+    let name: &'static CStr = match status {
+        CalcStatus::Ok => c"ok",
+        CalcStatus::DivisionByZero => c"division_by_zero",
+        CalcStatus::Overflow => c"overflow",
+        CalcStatus::InvalidInput => c"invalid_input",
+        CalcStatus::IoError => c"io_error",
+        CalcStatus::ParseError => c"parse_error",
+        CalcStatus::Cancelled => c"cancelled",
+        CalcStatus::AuditViolation => c"audit_violation",
+        CalcStatus::InvalidArgument => c"invalid_argument",
+        CalcStatus::Panic => c"panic",
+    };
+    name.as_ptr()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn last_error() -> String {
+        let message = calc_last_error_message();
+        assert!(!message.is_null());
+        unsafe { CStr::from_ptr(message) }
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_operations_and_history() {
+        unsafe {
+            let calc = calc_new();
+            let mut result = 0.0;
+            assert_eq!(calc_add(calc, 2.0, 3.0, &mut result), CalcStatus::Ok);
+            assert_eq!(result, 5.0);
+            assert!(calc_last_error_message().is_null());
+
+            assert_eq!(
+                calc_evaluate(calc, c"2 * (1 + 3)".as_ptr(), &mut result),
+                CalcStatus::Ok
+            );
+            assert_eq!(result, 8.0);
+            assert_eq!(calc_history_count(calc), 3);
+
+            let mut entry = CalcOperation::from(&Operation {
+                id: 0,
+                operation_type: OperationType::Add,
+                operands: vec![],
+                result: 0.0,
+                timestamp: 0,
+            });
+            assert_eq!(calc_history_entry(calc, 2, &mut entry), CalcStatus::Ok);
+            assert_eq!(entry.id, 3);
+            assert_eq!(entry.operation_type, CalcOperationType::Multiply);
+            assert_eq!(entry.operands, [2.0, 4.0]);
+            assert_eq!(
+                calc_history_entry(calc, 3, &mut entry),
+                CalcStatus::InvalidInput
+            );
+
+            assert_eq!(calc_clear_history(calc), CalcStatus::Ok);
+            assert_eq!(calc_history_count(calc), 0);
+            calc_free(calc);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        unsafe {
+            let calc = calc_new();
+            let mut result = 0.0;
+            assert_eq!(
+                calc_divide(calc, 1.0, 0.0, &mut result),
+                CalcStatus::DivisionByZero
+            );
+            assert_eq!(last_error(), "Division durch Null ist nicht erlaubt");
+
+            assert_eq!(
+                calc_evaluate(calc, c"2 +".as_ptr(), &mut result),
+                CalcStatus::ParseError
+            );
+            assert_eq!(
+                calc_add(calc, 1.0, 1.0, ptr::null_mut()),
+                CalcStatus::InvalidArgument
+            );
+            assert_eq!(
+                calc_add(ptr::null_mut(), 1.0, 1.0, &mut result),
+                CalcStatus::InvalidArgument
+            );
+            assert_eq!(
+                calc_evaluate(calc, ptr::null(), &mut result),
+                CalcStatus::InvalidArgument
+            );
+            assert_eq!(calc_history_count(ptr::null()), 0);
+            calc_free(calc);
+            calc_free(ptr::null_mut());
+        }
+
+        let name = unsafe { CStr::from_ptr(calc_status_name(CalcStatus::DivisionByZero)) };
+        assert_eq!(name.to_str().unwrap(), "division_by_zero");
+    }
+}
@@ -0,0 +1,565 @@
+//! Speicher für den Verlauf der Operationen
+
+use crate::modules::diagnostic::Diagnostic;
+use crate::modules::error::ProjectError;
+use crate::{Operation, OperationType};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// Filter für Abfragen auf den Verlauf
+///
+/// Alle gesetzten Bedingungen müssen erfüllt sein. Zeitstempel sind Sekunden
+/// seit der Unix-Epoche, `since` ist inklusiv und `until` exklusiv. Mit
+/// `offset` und `limit` lässt sich der Verlauf seitenweise lesen.
+///
+/// # Beispiel
+///
+/// ```rust
+/// use rust_project_complete::modules::history::HistoryQuery;
+/// use rust_project_complete::OperationType;
+///
+/// let now = 1_700_000_000;
+/// let last_week = HistoryQuery::new()
+///     .operation_type(OperationType::Divide)
+///     .since(now - 7 * 24 * 60 * 60);
+/// assert_eq!(last_week.operation_type, Some(OperationType::Divide));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryQuery {
+    pub operation_type: Option<OperationType>,
+    pub since: Option<u64>,
+    pub until: Option<u64>,
+    pub after_id: Option<u64>,
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+impl HistoryQuery {
+    /// Erstellt eine Abfrage ohne Einschränkungen
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Beschränkt die Abfrage auf einen Operationstyp
+    pub fn operation_type(mut self, operation_type: OperationType) -> Self {
+        self.operation_type = Some(operation_type);
+        self
+    }
+
+    /// Beschränkt die Abfrage auf Operationen ab diesem Zeitstempel
+    pub fn since(mut self, timestamp: u64) -> Self {
+        self.since = Some(timestamp);
+        self
+    }
+
+    /// Beschränkt die Abfrage auf Operationen vor diesem Zeitstempel
+    pub fn until(mut self, timestamp: u64) -> Self {
+        self.until = Some(timestamp);
+        self
+    }
+
+    /// Beschränkt die Abfrage auf Operationen mit größerer ID
+    pub fn after_id(mut self, id: u64) -> Self {
+        self.after_id = Some(id);
+        self
+    }
+
+    /// Überspringt die ersten passenden Operationen
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Begrenzt die Anzahl der Ergebnisse
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Prüft, ob eine Operation die Bedingungen erfüllt (ohne `offset` und `limit`)
+    pub fn matches(&self, operation: &Operation) -> bool {
+        self.operation_type
+            .as_ref()
+            .is_none_or(|t| *t == operation.operation_type)
+            && self.since.is_none_or(|since| operation.timestamp >= since)
+            && self.until.is_none_or(|until| operation.timestamp < until)
+            && self.after_id.is_none_or(|id| operation.id > id)
+    }
+}
+
+/// Speicher für den Verlauf eines [`Calculator`](crate::Calculator)
+///
+/// Operationen werden in der Reihenfolge ihres Anhängens geliefert.
+/// Implementierungen müssen nur `append`, `len`, `scan` und `clear`
+/// bereitstellen; `query` sollte überschrieben werden, wenn der Speicher
+/// Filter selbst auswerten kann.
+pub trait HistoryStore: Send + Sync + fmt::Debug {
+    /// Hängt eine Operation an den Verlauf an
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn die Operation nicht gespeichert werden kann.
+    fn append(&mut self, operation: &Operation) -> Result<(), ProjectError>;
+
+    /// Gibt die Anzahl der gespeicherten Operationen zurück
+    fn len(&self) -> usize;
+
+    /// Prüft, ob der Verlauf leer ist
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Übergibt alle Operationen der Reihe nach an `visit`, ohne sie gesammelt zu laden
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn der Verlauf nicht gelesen werden kann.
+    fn scan(&self, visit: &mut dyn FnMut(&Operation)) -> Result<(), ProjectError>;
+
+    /// Löscht den gesamten Verlauf
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn der Verlauf nicht gelöscht werden kann.
+    fn clear(&mut self) -> Result<(), ProjectError>;
+
+    /// Gibt alle Operationen zurück
+    ///
+    /// Lädt den gesamten Verlauf in den Arbeitsspeicher; für große Verläufe
+    /// sind [`HistoryStore::scan`] und [`HistoryStore::query`] gedacht.
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn der Verlauf nicht gelesen werden kann.
+    fn operations(&self) -> Result<Cow<'_, [Operation]>, ProjectError> {
+        let mut operations = Vec::with_capacity(self.len());
+        self.scan(&mut |op| operations.push(op.clone()))?;
+        Ok(Cow::Owned(operations))
+    }
+
+    /// Gibt alle Operationen zurück, die `query` erfüllen
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn der Verlauf nicht gelesen werden kann.
+    fn query(&self, query: &HistoryQuery) -> Result<Vec<Operation>, ProjectError> {
+        let limit = query.limit.unwrap_or(usize::MAX);
+        let mut skip = query.offset.unwrap_or(0);
+        let mut operations = Vec::new();
+        self.scan(&mut |op| {
+            if operations.len() < limit && query.matches(op) {
+                match skip {
+                    0 => operations.push(op.clone()),
+                    _ => skip -= 1,
+                }
+            }
+        })?;
+        Ok(operations)
+    }
+}
+
+/// Verlauf im Arbeitsspeicher (Standard)
+#[derive(Debug, Clone, Default)]
+pub struct MemoryHistoryStore {
+    operations: Vec<Operation>,
+}
+
+impl MemoryHistoryStore {
+    /// Erstellt einen leeren Speicher
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl From<Vec<Operation>> for MemoryHistoryStore {
+    fn from(operations: Vec<Operation>) -> Self {
+        Self { operations }
+    }
+}
+
+impl HistoryStore for MemoryHistoryStore {
+    fn append(&mut self, operation: &Operation) -> Result<(), ProjectError> {
+        self.operations.push(operation.clone());
+        Ok(())
+    }
+
+    fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    fn scan(&self, visit: &mut dyn FnMut(&Operation)) -> Result<(), ProjectError> {
+        self.operations.iter().for_each(visit);
+        Ok(())
+    }
+
+    fn clear(&mut self) -> Result<(), ProjectError> {
+        self.operations.clear();
+        Ok(())
+    }
+
+    fn operations(&self) -> Result<Cow<'_, [Operation]>, ProjectError> {
+        Ok(Cow::Borrowed(&self.operations))
+    }
+}
+
+/// Verlauf als Datei mit einer JSON-Zeile pro Operation, die nur angehängt wird
+///
+/// Es werden nur die Anzahl der Einträge im Speicher gehalten; Abfragen lesen
+/// die Datei zeilenweise.
+#[derive(Debug)]
+pub struct FileHistoryStore {
+    path: PathBuf,
+    file: File,
+    len: usize,
+}
+
+impl FileHistoryStore {
+    /// Öffnet oder erstellt eine Verlaufsdatei
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn die Datei nicht geöffnet werden kann oder
+    /// ungültige Einträge enthält.
+    pub fn open(file_path: &str) -> Result<Self, ProjectError> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(file_path)
+            .map_err(|e| ProjectError::io(e, file_path))?;
+        let mut store = Self {
+            path: PathBuf::from(file_path),
+            file,
+            len: 0,
+        };
+
+        let mut len = 0;
+        store.scan(&mut |_| len += 1)?;
+        store.len = len;
+        Ok(store)
+    }
+
+    /// Gibt den Pfad der Verlaufsdatei zurück
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl HistoryStore for FileHistoryStore {
+    fn append(&mut self, operation: &Operation) -> Result<(), ProjectError> {
+        let mut line = serde_json::to_string(operation)?;
+        line.push('\n');
+        self.file
+            .write_all(line.as_bytes())
+            .and_then(|_| self.file.flush())
+            .map_err(|e| ProjectError::io(e, &self.path))?;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn scan(&self, visit: &mut dyn FnMut(&Operation)) -> Result<(), ProjectError> {
+        let file = File::open(&self.path).map_err(|e| ProjectError::io(e, &self.path))?;
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(|e| ProjectError::io(e, &self.path))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let operation: Operation = serde_json::from_str(&line).map_err(|e| {
+                Diagnostic::from_json(&e, Some(&line))
+                    .starting_at_line(index + 1)
+                    .in_file(self.path.display().to_string())
+            })?;
+            visit(&operation);
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self) -> Result<(), ProjectError> {
+        self.file.set_len(0)?;
+        self.len = 0;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteHistoryStore;
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use super::{HistoryQuery, HistoryStore};
+    use crate::modules::error::{ErrorSource, ProjectError};
+    use crate::modules::numeric::lossless;
+    use crate::{Operation, OperationType};
+    use rusqlite::types::Value;
+    use rusqlite::{params, params_from_iter, Connection, ErrorCode};
+    use std::io::ErrorKind;
+    use std::sync::{Mutex, MutexGuard, PoisonError};
+
+    const SCHEMA: &str = "
+        CREATE TABLE IF NOT EXISTS operations (
+            id INTEGER PRIMARY KEY,
+            operation_type TEXT NOT NULL,
+            operands TEXT NOT NULL,
+            result REAL,
+            timestamp INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS operations_type_time
+            ON operations (operation_type, timestamp);
+        CREATE INDEX IF NOT EXISTS operations_time ON operations (timestamp);
+    ";
+
+    const SELECT: &str =
+        "SELECT id, operation_type, operands, result, timestamp FROM operations";
+
+    /// Verlauf in einer eingebetteten SQLite-Datenbank
+    ///
+    /// Filter aus [`HistoryQuery`] werden als SQL ausgewertet und nutzen die
+    /// Indizes auf Operationstyp und Zeitstempel.
+    #[derive(Debug)]
+    pub struct SqliteHistoryStore {
+        connection: Mutex<Connection>,
+        len: usize,
+    }
+
+    impl SqliteHistoryStore {
+        /// Öffnet oder erstellt eine Datenbankdatei
+        ///
+        /// # Errors
+        ///
+        /// Gibt einen Fehler zurück, wenn die Datenbank nicht geöffnet werden kann.
+        pub fn open(file_path: &str) -> Result<Self, ProjectError> {
+            Connection::open(file_path)
+                .map_err(to_error)
+                .and_then(Self::with_connection)
+                .map_err(|e| e.with_path(file_path))
+        }
+
+        /// Erstellt eine Datenbank im Arbeitsspeicher
+        ///
+        /// # Errors
+        ///
+        /// Gibt einen Fehler zurück, wenn die Datenbank nicht angelegt werden kann.
+        pub fn open_in_memory() -> Result<Self, ProjectError> {
+            Self::with_connection(Connection::open_in_memory().map_err(to_error)?)
+        }
+
+        fn with_connection(connection: Connection) -> Result<Self, ProjectError> {
+            connection.execute_batch(SCHEMA).map_err(to_error)?;
+            let len: i64 = connection
+                .query_row("SELECT COUNT(*) FROM operations", [], |row| row.get(0))
+                .map_err(to_error)?;
+            Ok(Self {
+                connection: Mutex::new(connection),
+                len: len as usize,
+            })
+        }
+
+        fn connection(&self) -> MutexGuard<'_, Connection> {
+            self.connection.lock().unwrap_or_else(PoisonError::into_inner)
+        }
+
+        fn select(
+            &self,
+            sql: &str,
+            values: Vec<Value>,
+            visit: &mut dyn FnMut(&Operation),
+        ) -> Result<(), ProjectError> {
+            let connection = self.connection();
+            let mut statement = connection.prepare(sql).map_err(to_error)?;
+            let mut rows = statement.query(params_from_iter(values)).map_err(to_error)?;
+
+            while let Some(row) = rows.next().map_err(to_error)? {
+                let operation_type: String = row.get(1).map_err(to_error)?;
+                let operands: String = row.get(2).map_err(to_error)?;
+                let result: Option<f64> = row.get(3).map_err(to_error)?;
+                let id: i64 = row.get(0).map_err(to_error)?;
+                let timestamp: i64 = row.get(4).map_err(to_error)?;
+
+                visit(&Operation {
+                    id: id as u64,
+                    operation_type: decode_type(&operation_type)?,
+                    operands: lossless::vec::deserialize(&mut serde_json::Deserializer::from_str(
+                        &operands,
+                    ))?,
+                    // SQLite speichert NaN als NULL
+                    result: result.unwrap_or(f64::NAN),
+                    timestamp: timestamp as u64,
+                });
+            }
+            Ok(())
+        }
+    }
+
+    impl HistoryStore for SqliteHistoryStore {
+        fn append(&mut self, operation: &Operation) -> Result<(), ProjectError> {
+            self.connection()
+                .execute(
+                    "INSERT INTO operations (id, operation_type, operands, result, timestamp)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![
+                        operation.id as i64,
+                        encode_type(&operation.operation_type)?,
+                        encode_operands(&operation.operands)?,
+                        operation.result,
+                        operation.timestamp as i64,
+                    ],
+                )
+                .map_err(to_error)?;
+            self.len += 1;
+            Ok(())
+        }
+
+        fn len(&self) -> usize {
+            self.len
+        }
+
+        fn scan(&self, visit: &mut dyn FnMut(&Operation)) -> Result<(), ProjectError> {
+            self.select(&format!("{} ORDER BY id", SELECT), Vec::new(), visit)
+        }
+
+        fn clear(&mut self) -> Result<(), ProjectError> {
+            self.connection()
+                .execute("DELETE FROM operations", [])
+                .map_err(to_error)?;
+            self.len = 0;
+            Ok(())
+        }
+
+        fn query(&self, query: &HistoryQuery) -> Result<Vec<Operation>, ProjectError> {
+            let mut conditions = Vec::new();
+            let mut values = Vec::new();
+
+            if let Some(operation_type) = &query.operation_type {
+                conditions.push("operation_type = ?");
+                values.push(Value::Text(encode_type(operation_type)?));
+            }
+            if let Some(since) = query.since {
+                conditions.push("timestamp >= ?");
+                values.push(Value::Integer(since as i64));
+            }
+            if let Some(until) = query.until {
+                conditions.push("timestamp < ?");
+                values.push(Value::Integer(until as i64));
+            }
+            if let Some(after_id) = query.after_id {
+                conditions.push("id > ?");
+                values.push(Value::Integer(after_id as i64));
+            }
+
+            let mut sql = SELECT.to_string();
+            if !conditions.is_empty() {
+                sql.push_str(" WHERE ");
+                sql.push_str(&conditions.join(" AND "));
+            }
+            sql.push_str(" ORDER BY id");
+            if query.limit.is_some() || query.offset.is_some() {
+                // SQLite kennt OFFSET nur zusammen mit LIMIT; -1 steht für unbegrenzt
+                let limit = query.limit.map_or(-1, |limit| limit.min(i64::MAX as usize) as i64);
+                let offset = query.offset.unwrap_or(0).min(i64::MAX as usize) as i64;
+                sql.push_str(" LIMIT ? OFFSET ?");
+                values.push(Value::Integer(limit));
+                values.push(Value::Integer(offset));
+            }
+
+            let mut operations = Vec::new();
+            self.select(&sql, values, &mut |op| operations.push(op.clone()))?;
+            Ok(operations)
+        }
+    }
+
+    /// Speichert die Operanden als JSON, ±Unendlich und NaN verlustfrei
+    fn encode_operands(operands: &[f64]) -> Result<String, ProjectError> {
+        let mut json = Vec::new();
+        lossless::vec::serialize(operands, &mut serde_json::Serializer::new(&mut json))?;
+        Ok(String::from_utf8(json).expect("serde_json schreibt UTF-8"))
+    }
+
+    /// Speichert den Operationstyp unter seinem serialisierten Namen
+    fn encode_type(operation_type: &OperationType) -> Result<String, ProjectError> {
+        match serde_json::to_value(operation_type)? {
+            serde_json::Value::String(name) => Ok(name),
+            other => Ok(other.to_string()),
+        }
+    }
+
+    fn decode_type(name: &str) -> Result<OperationType, ProjectError> {
+        Ok(serde_json::from_value(serde_json::Value::String(name.to_string()))?)
+    }
+
+    /// SQLite-Fehler als [`ProjectError::IoError`] mit passender Art
+    fn to_error(error: rusqlite::Error) -> ProjectError {
+        let kind = match error.sqlite_error_code() {
+            Some(ErrorCode::PermissionDenied | ErrorCode::ReadOnly) => ErrorKind::PermissionDenied,
+            Some(ErrorCode::CannotOpen) => ErrorKind::NotFound,
+            Some(ErrorCode::DiskFull) => ErrorKind::StorageFull,
+            Some(ErrorCode::NotADatabase | ErrorCode::DatabaseCorrupt) => ErrorKind::InvalidData,
+            _ => ErrorKind::Other,
+        };
+        ProjectError::IoError {
+            kind,
+            message: format!("SQLite: {}", error),
+            path: None,
+            source: Some(ErrorSource::new(error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn operation(id: u64, operation_type: OperationType, timestamp: u64) -> Operation {
+        Operation {
+            id,
+            operation_type,
+            operands: vec![1.0, 2.0],
+            result: 3.0,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_query_matches() {
+        let op = operation(5, OperationType::Divide, 100);
+
+        assert!(HistoryQuery::new().matches(&op));
+        assert!(HistoryQuery::new().operation_type(OperationType::Divide).matches(&op));
+        assert!(!HistoryQuery::new().operation_type(OperationType::Add).matches(&op));
+        assert!(HistoryQuery::new().since(100).until(101).matches(&op));
+        assert!(!HistoryQuery::new().until(100).matches(&op));
+        assert!(!HistoryQuery::new().after_id(5).matches(&op));
+    }
+
+    #[test]
+    fn test_memory_store_borrows_operations() {
+        let mut store = MemoryHistoryStore::new();
+        store.append(&operation(1, OperationType::Add, 0)).unwrap();
+
+        assert!(matches!(store.operations().unwrap(), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_file_store_reports_corrupt_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        std::fs::write(&path, "\nkein json\n").unwrap();
+
+        let error = FileHistoryStore::open(path.to_str().unwrap()).unwrap_err();
+        match error {
+            ProjectError::ParseError { diagnostic, .. } => {
+                assert_eq!(diagnostic.file.as_deref(), path.to_str());
+                assert_eq!(diagnostic.span.map(|span| span.line), Some(2));
+                assert_eq!(diagnostic.source_line.as_deref(), Some("kein json"));
+            }
+            other => panic!("Expected ParseError, got {:?}", other),
+        }
+    }
+}
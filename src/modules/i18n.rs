@@ -0,0 +1,714 @@
+//! Übersetzung von Meldungen
+//!
+//! Meldungen sind [`Message`]s mit einem festen Schlüssel und eingesetzten
+//! Werten. Der Schlüssel wählt die Vorlage der gewünschten Sprache aus dem
+//! Katalog; Werte wie Namen von Sitzungen oder Operationen werden nie
+//! übersetzt. Text ohne Schlüssel, z. B. Meldungen des Betriebssystems, bleibt
+//! unverändert.
+//!
+//! Für Programme, die auf Fehler reagieren, sind die sprachunabhängigen Codes
+//! aus [`ProjectError::code`](crate::modules::error::ProjectError::code) und
+//! die Namen aus [`OperationType::name`](crate::OperationType::name) gedacht.
+
+use crate::modules::error::ProjectError;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+
+/// Sprache der Meldungen
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    /// Deutsch, die Sprache der Bibliothek
+    #[default]
+    De,
+    /// Englisch
+    En,
+}
+
+impl Locale {
+    /// Sprache aus `LC_ALL`, `LC_MESSAGES` oder `LANG`
+    ///
+    /// Wie unter POSIX zählt die erste gesetzte Variable; ist ihre Sprache
+    /// nicht verfügbar (z. B. `C` oder `fr_FR`), wird `None` zurückgegeben.
+    pub fn from_env() -> Option<Self> {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| value.parse().ok())
+    }
+}
+
+impl FromStr for Locale {
+    type Err = ProjectError;
+
+    /// Akzeptiert Sprachcodes wie `en`, `de-AT` oder `en_US.UTF-8`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let language = s
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match language.as_str() {
+            "de" => Ok(Locale::De),
+            "en" => Ok(Locale::En),
+            _ => Err(ProjectError::invalid_input(Message::new(
+                "cli.unsupported_language",
+                [s.into()],
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Locale::De => f.write_str("de"),
+            Locale::En => f.write_str("en"),
+        }
+    }
+}
+
+/// Vorlagen je Schlüssel auf Deutsch und Englisch
+///
+/// Platzhalter `{}` stehen in beiden Sprachen in derselben Reihenfolge.
+const CATALOG: &[(&str, &str, &str)] = &[
+    // Diagnosen: Position und Vorschlag
+    (
+        "diagnostic.at_file_position",
+        "{} (Datei {}, Zeile {}, Spalte {})",
+        "{} (file {}, line {}, column {})",
+    ),
+    ("diagnostic.at_position", "{} (Zeile {}, Spalte {})", "{} (line {}, column {})"),
+    ("diagnostic.in_file", "{} (Datei {})", "{} (file {})"),
+    ("diagnostic.with_suggestion", "{}; {}", "{}; {}"),
+    ("diagnostic.did_you_mean", "meinten Sie '{}'?", "did you mean '{}'?"),
+    ("diagnostic.close_parenthesis", "fehlende ')' ergänzen", "add the missing ')'"),
+    // Fehlerarten
+    ("error.headline", "Fehler: {}", "Error: {}"),
+    (
+        "error.division_by_zero",
+        "Division durch Null ist nicht erlaubt",
+        "Division by zero is not allowed",
+    ),
+    ("error.overflow", "Overflow-Fehler: {}", "Overflow error: {}"),
+    ("error.invalid_input", "Ungültige Eingabe: {}", "Invalid input: {}"),
+    ("error.io", "IO-Fehler: {}", "I/O error: {}"),
+    ("error.io_in_file", "IO-Fehler: {} (Datei {})", "I/O error: {} (file {})"),
+    ("error.parse", "Parsing-Fehler: {}", "Parse error: {}"),
+    ("error.cancelled", "Operation wurde abgebrochen", "Operation was cancelled"),
+    (
+        "error.audit_violation",
+        "Audit-Protokoll verletzt bei Eintrag {}: {}",
+        "Audit log violated at entry {}: {}",
+    ),
+    // Operationsarten
+    ("operation_type.add", "Addition", "Addition"),
+    ("operation_type.subtract", "Subtraktion", "Subtraction"),
+    ("operation_type.multiply", "Multiplikation", "Multiplication"),
+    ("operation_type.divide", "Division", "Division"),
+    // Calculator und Operationen
+    ("operation.unknown", "Unbekannte Operation '{}'", "Unknown operation '{}'"),
+    (
+        "expression.arity",
+        "'{}' erwartet {} Argumente, erhalten {}",
+        "'{}' expects {} arguments, got {}",
+    ),
+    (
+        "operation.arity",
+        "'{}' erwartet {} Operanden, erhalten {}",
+        "'{}' expects {} operands, got {}",
+    ),
+    (
+        "operation.not_natural",
+        "Fakultät erwartet eine natürliche Zahl, nicht {}",
+        "Factorial expects a natural number, not {}",
+    ),
+    (
+        "operation.big_factorial_limit",
+        "Fakultät ist auf n <= {} begrenzt, nicht {}",
+        "Factorial is limited to n <= {}, not {}",
+    ),
+    (
+        "numeric.operand_not_finite",
+        "Operand {} ist keine endliche Zahl",
+        "Operand {} is not a finite number",
+    ),
+    (
+        "numeric.result_not_finite",
+        "Ergebnis {} ist keine endliche Zahl",
+        "Result {} is not a finite number",
+    ),
+    ("numeric.result_nan", "Ergebnis ist keine Zahl (NaN)", "Result is not a number (NaN)"),
+    (
+        "numeric.unknown_policy",
+        "Unbekannte Zahlenrichtlinie '{}' (strict, warn oder permissive)",
+        "Unknown numeric policy '{}' (strict, warn or permissive)",
+    ),
+    ("registry.invalid_name", "Ungültiger Name '{}'", "Invalid name '{}'"),
+    (
+        "registry.already_registered",
+        "Operation '{}' ist bereits registriert",
+        "Operation '{}' is already registered",
+    ),
+    ("registry.invalid_symbol", "Ungültiges Symbol '{}'", "Invalid symbol '{}'"),
+    (
+        "registry.symbol_arity",
+        "Nur zweistellige Operationen können ein Symbol haben, '{}' hat {}",
+        "Only binary operations can have a symbol, '{}' has {}",
+    ),
+    ("registry.symbol_taken", "Symbol '{}' ist bereits vergeben", "Symbol '{}' is already taken"),
+    (
+        "observer.out_of_range",
+        "{}: Operand {} liegt außerhalb von [{}, {}]",
+        "{}: operand {} is outside of [{}, {}]",
+    ),
+    (
+        "definition.malformed",
+        "Definition '{}' hat nicht die Form 'name(a, b) = ausdruck'",
+        "Definition '{}' is not of the form 'name(a, b) = expression'",
+    ),
+    ("definition.invalid_parameter", "Ungültiger Parameter '{}'", "Invalid parameter '{}'"),
+    ("definition.duplicate_parameter", "Parameter '{}' ist doppelt", "Parameter '{}' is duplicated"),
+    (
+        "definition.not_a_parameter",
+        "'{}' ist kein Parameter von '{}'",
+        "'{}' is not a parameter of '{}'",
+    ),
+    ("definition.defined", "Operation {} definiert", "Operation {} defined"),
+    // Ausdrücke
+    ("expression.unknown_variable", "Unbekannte Variable '{}'", "Unknown variable '{}'"),
+    ("expression.invalid_number", "Ungültige Zahl '{}'", "Invalid number '{}'"),
+    ("expression.unexpected_character", "Unerwartetes Zeichen '{}'", "Unexpected character '{}'"),
+    ("expression.unexpected_end", "Unerwartetes Ende des Ausdrucks", "Unexpected end of expression"),
+    ("expression.unclosed", "Klammer wird nicht geschlossen", "Parenthesis is not closed"),
+    ("expression.unexpected", "Unerwartetes '{}'", "Unexpected '{}'"),
+    // Mathematische Hilfsfunktionen
+    ("math.lcm_of_zero", "LCM von Null ist nicht definiert", "LCM of zero is not defined"),
+    ("math.lcm_overflow", "LCM Overflow", "LCM overflow"),
+    ("math.fibonacci_overflow", "Fibonacci Overflow", "Fibonacci overflow"),
+    ("math.empty_list", "Liste ist leer", "List is empty"),
+    ("math.list_contains_nan", "Liste enthält NaN", "List contains NaN"),
+    ("math.gcd", "ggT({}, {}) = {}", "gcd({}, {}) = {}"),
+    ("math.lcm", "kgV({}, {}) = {}", "lcm({}, {}) = {}"),
+    ("math.prime", "{} ist eine Primzahl", "{} is a prime number"),
+    ("math.not_prime", "{} ist keine Primzahl", "{} is not a prime number"),
+    // Sitzungen
+    ("session.not_found", "Sitzung '{}' existiert nicht", "Session '{}' does not exist"),
+    ("session.exists", "Sitzung '{}' existiert bereits", "Session '{}' already exists"),
+    (
+        "session.invalid_name",
+        "Ungültiger Sitzungsname '{}': erlaubt sind Buchstaben, Ziffern, '-' und '_'",
+        "Invalid session name '{}': letters, digits, '-' and '_' are allowed",
+    ),
+    ("session.created", "Sitzung '{}' erstellt", "Session '{}' created"),
+    ("session.renamed", "Sitzung '{}' in '{}' umbenannt", "Session '{}' renamed to '{}'"),
+    ("session.copied", "Sitzung '{}' nach '{}' kopiert", "Session '{}' copied to '{}'"),
+    ("session.deleted", "Sitzung '{}' gelöscht", "Session '{}' deleted"),
+    ("session.none", "Keine Sitzungen vorhanden", "No sessions"),
+    // Audit-Protokoll
+    ("audit.unreadable", "Eintrag ist nicht lesbar: {}", "Entry is not readable: {}"),
+    (
+        "audit.sequence",
+        "Sequenznummer {} erwartet, {} gefunden",
+        "Expected sequence number {}, found {}",
+    ),
+    (
+        "audit.previous_hash",
+        "Verweis auf den vorherigen Eintrag stimmt nicht",
+        "Reference to the previous entry does not match",
+    ),
+    ("audit.modified", "Inhalt wurde verändert", "Content was modified"),
+    ("audit.unchanged", "Audit-Protokoll unverändert: {} Einträge", "Audit log unchanged: {} entries"),
+    ("audit.last_hash", "Letzter Hash: {}", "Last hash: {}"),
+    // Stapelverarbeitung
+    (
+        "batch.unknown_format",
+        "Unbekanntes Format '{}' (csv oder jsonl)",
+        "Unknown format '{}' (csv or jsonl)",
+    ),
+    ("batch.fields", "Erwartet werden die Felder op,a,b", "Expected the fields op,a,b"),
+    ("batch.missing_operation", "Operation fehlt", "Operation is missing"),
+    ("batch.factorial_operand", "factorial erwartet einen Operanden", "factorial expects one operand"),
+    (
+        "batch.summary",
+        "{} Datensätze verarbeitet, davon {} fehlerhaft",
+        "{} records processed, {} of them failed",
+    ),
+    // Daemon und Server
+    ("daemon.already_running", "Daemon läuft bereits unter {}", "Daemon is already running at {}"),
+    ("daemon.started", "Daemon gestartet (PID {}) unter {}", "Daemon started (PID {}) at {}"),
+    ("daemon.status", "Daemon läuft (PID {}) unter {}", "Daemon is running (PID {}) at {}"),
+    ("daemon.running", "Daemon läuft unter {}", "Daemon is running at {}"),
+    ("daemon.not_running", "Daemon läuft nicht", "Daemon is not running"),
+    ("daemon.stopped", "Daemon beendet", "Daemon stopped"),
+    ("daemon.start_failed", "Daemon konnte nicht gestartet werden", "Daemon could not be started"),
+    ("daemon.uptime", "Laufzeit: {} s", "Uptime: {} s"),
+    ("server.running", "Server läuft auf {}", "Server is running at {}"),
+    // Verlauf und Statistiken
+    ("history.empty", "Keine Operationen im Verlauf", "No operations in history"),
+    ("history.count", "Operationen im Verlauf: {}", "Operations in history: {}"),
+    ("statistics.total", "Operationen gesamt: {}", "Total operations: {}"),
+    ("statistics.average", "Durchschnitt: {}", "Average: {}"),
+    ("statistics.minimum", "Minimum: {}", "Minimum: {}"),
+    ("statistics.maximum", "Maximum: {}", "Maximum: {}"),
+    ("statistics.standard_deviation", "Standardabweichung: {}", "Standard deviation: {}"),
+    (
+        "statistics.operation",
+        "{}: {} (Min {}, Max {}, Durchschnitt {})",
+        "{}: {} (min {}, max {}, average {})",
+    ),
+    ("statistics.operation_count", "{}: {}", "{}: {}"),
+    ("operations.entry", "{} ({} Operanden)", "{} ({} operands)"),
+    ("operations.entry_with_symbol", "{} ({} Operanden, Symbol {})", "{} ({} operands, symbol {})"),
+    // Oberfläche im Terminal
+    ("tui.history", "Verlauf", "History"),
+    ("tui.statistics", "Statistiken", "Statistics"),
+    ("tui.variables", "Variablen", "Variables"),
+    ("tui.no_variables", "Keine Variablen", "No variables"),
+    ("tui.input", "Eingabe", "Input"),
+    (
+        "tui.help",
+        "Enter: berechnen  ↑/↓: Verlauf  Tab: Ergebnis übernehmen  Esc: beenden",
+        "Enter: evaluate  ↑/↓: history  Tab: insert result  Esc: quit",
+    ),
+    // JSON-RPC
+    ("rpc.invalid_json", "Ungültiges JSON: {}", "Invalid JSON: {}"),
+    ("rpc.invalid_request", "Ungültige Anfrage: {}", "Invalid request: {}"),
+    ("rpc.invalid_params", "Ungültige Parameter: {}", "Invalid params: {}"),
+    ("rpc.unknown_method", "Unbekannte Methode '{}'", "Unknown method '{}'"),
+    ("rpc.empty_batch", "leerer Stapel", "empty batch"),
+    ("rpc.object_expected", "Objekt erwartet", "object expected"),
+    (
+        "rpc.invalid_id",
+        "'id' muss Zahl, Zeichenkette oder null sein",
+        "'id' must be a number, string or null",
+    ),
+    ("rpc.invalid_version", "'jsonrpc' muss \"2.0\" sein", "'jsonrpc' must be \"2.0\""),
+    (
+        "rpc.invalid_params_type",
+        "'params' muss Array oder Objekt sein",
+        "'params' must be an array or object",
+    ),
+    ("rpc.missing_method", "'method' fehlt", "'method' is missing"),
+    (
+        "rpc.too_many_params",
+        "höchstens {} Parameter erwartet, {} erhalten",
+        "at most {} parameters expected, got {}",
+    ),
+    // Kommandozeile
+    ("cli.man_written", "Handbuchseiten nach {} geschrieben", "Manual pages written to {}"),
+    ("cli.unknown_shell", "Unbekannte Shell '{}'", "Unknown shell '{}'"),
+    ("cli.no_command", "Es wurde kein Befehl angegeben", "No command was given"),
+    (
+        "cli.define_needs_store",
+        "define braucht --history, --session oder einen laufenden Daemon",
+        "define needs --history, --session or a running daemon",
+    ),
+    (
+        "cli.stdio_rpc_with_command",
+        "--stdio-rpc kann nicht mit einem Befehl kombiniert werden",
+        "--stdio-rpc cannot be combined with a command",
+    ),
+    (
+        "cli.tui_unavailable",
+        "interactive ist nicht verfügbar: das Programm wurde ohne das Feature `tui` gebaut",
+        "interactive is not available: the program was built without the `tui` feature",
+    ),
+    (
+        "cli.unknown_output_format",
+        "Unbekanntes Ausgabeformat '{}' (text, json, csv oder table)",
+        "Unknown output format '{}' (text, json, csv or table)",
+    ),
+    (
+        "cli.unknown_log_format",
+        "Unbekanntes Log-Format '{}' (pretty oder json)",
+        "Unknown log format '{}' (pretty or json)",
+    ),
+    (
+        "cli.unsupported_language",
+        "Nicht unterstützte Sprache '{}' (de oder en)",
+        "Unsupported language '{}' (de or en)",
+    ),
+];
+
+/// Eintrag im Katalog mit dem Schlüssel als `&'static str`
+fn entry(key: &str) -> Option<&'static (&'static str, &'static str, &'static str)> {
+    CATALOG.iter().find(|(k, _, _)| *k == key)
+}
+
+/// Meldung mit festem Schlüssel und eingesetzten Werten
+///
+/// Der Schlüssel wählt die Vorlage der gewünschten Sprache aus dem Katalog.
+/// Werte ([`Argument::Value`]) werden unverändert eingesetzt, nur eingesetzte
+/// Meldungen ([`Argument::Message`]) werden mit übersetzt. Text ohne
+/// Schlüssel, z. B. aus `From<String>`, bleibt in jeder Sprache gleich.
+/// `Display` gibt die deutsche Meldung aus.
+///
+/// Serialisiert wird die Meldung als `message` mit dem deutschen Text sowie
+/// `message_key` und `message_args`, sodass sie z. B. nach dem Weg über den
+/// Daemon weiterhin übersetzt werden kann.
+///
+/// # Beispiel
+///
+/// ```rust
+/// use rust_project_complete::modules::i18n::{Locale, Message};
+///
+/// let message = Message::new("session.created", ["Multiplikation".into()]);
+/// assert_eq!(message.to_string(), "Sitzung 'Multiplikation' erstellt");
+/// assert_eq!(message.render(Locale::En), "Session 'Multiplikation' created");
+/// assert_eq!(Message::from("2 + 3 = 5").render(Locale::En), "2 + 3 = 5");
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(into = "MessageData", from = "MessageData")]
+pub struct Message(Content);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Content {
+    Template {
+        key: &'static str,
+        arguments: Vec<Argument>,
+    },
+    Text(String),
+    Lines(Vec<Message>),
+}
+
+/// Eingesetzter Wert einer [`Message`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Argument {
+    /// Wert, der in jeder Sprache gleich bleibt, z. B. ein Name oder eine Zahl
+    Value(String),
+    /// Meldung, die in derselben Sprache eingesetzt wird
+    Message(Message),
+}
+
+impl Message {
+    /// Meldung aus dem Katalog
+    ///
+    /// Schlüssel und Anzahl der Werte werden in Debug-Builds geprüft.
+    pub fn new<const N: usize>(key: &'static str, arguments: [Argument; N]) -> Self {
+        debug_assert!(
+            entry(key).is_some_and(|(_, german, _)| german.matches("{}").count() == N),
+            "Schlüssel '{}' fehlt im Katalog oder erwartet nicht {} Werte",
+            key,
+            N
+        );
+        Message(Content::Template {
+            key,
+            arguments: arguments.into(),
+        })
+    }
+
+    /// Text, der in jeder Sprache gleich bleibt
+    pub fn text(text: impl Into<String>) -> Self {
+        Message(Content::Text(text.into()))
+    }
+
+    /// Mehrere Meldungen, je eine Zeile
+    pub fn lines(lines: impl IntoIterator<Item = Message>) -> Self {
+        Message(Content::Lines(lines.into_iter().collect()))
+    }
+
+    /// Schlüssel im Katalog, z. B. `"session.created"`; `None` für Text
+    pub fn key(&self) -> Option<&'static str> {
+        match &self.0 {
+            Content::Template { key, .. } => Some(key),
+            _ => None,
+        }
+    }
+
+    /// Meldung in der gewünschten Sprache
+    pub fn render(&self, locale: Locale) -> String {
+        match &self.0 {
+            Content::Template { key, arguments } => {
+                let template = match (entry(key), locale) {
+                    (Some((_, german, _)), Locale::De) => german,
+                    (Some((_, _, english)), Locale::En) => english,
+                    (None, _) => key,
+                };
+                let mut parts = template.split("{}");
+                let mut out = parts.next().unwrap_or_default().to_string();
+                for (part, argument) in parts.zip(arguments) {
+                    out.push_str(&argument.render(locale));
+                    out.push_str(part);
+                }
+                out
+            }
+            Content::Text(text) => text.clone(),
+            Content::Lines(lines) => lines
+                .iter()
+                .map(|line| line.render(locale))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+}
+
+impl Argument {
+    fn render(&self, locale: Locale) -> String {
+        match self {
+            Argument::Value(value) => value.clone(),
+            Argument::Message(message) => message.render(locale),
+        }
+    }
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(Locale::De))
+    }
+}
+
+impl From<String> for Message {
+    fn from(text: String) -> Self {
+        Message::text(text)
+    }
+}
+
+impl From<&str> for Message {
+    fn from(text: &str) -> Self {
+        Message::text(text)
+    }
+}
+
+impl From<Message> for Argument {
+    fn from(message: Message) -> Self {
+        Argument::Message(message)
+    }
+}
+
+impl From<String> for Argument {
+    fn from(value: String) -> Self {
+        Argument::Value(value)
+    }
+}
+
+impl From<&String> for Argument {
+    fn from(value: &String) -> Self {
+        Argument::Value(value.clone())
+    }
+}
+
+impl From<&str> for Argument {
+    fn from(value: &str) -> Self {
+        Argument::Value(value.to_string())
+    }
+}
+
+/// Zahlen und Zeichen werden wie mit `{}` formatiert
+macro_rules! value_from_display {
+    ($($type:ty),*) => {
+        $(impl From<$type> for Argument {
+            fn from(value: $type) -> Self {
+                Argument::Value(value.to_string())
+            }
+        })*
+    };
+}
+
+value_from_display!(f64, u64, i64, u32, usize, char);
+
+/// Form einer [`Message`] beim Serialisieren
+#[derive(Serialize, Deserialize)]
+struct MessageData {
+    message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    message_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    message_args: Vec<Argument>,
+}
+
+impl From<Message> for MessageData {
+    fn from(message: Message) -> Self {
+        let text = message.to_string();
+        match message.0 {
+            Content::Template { key, arguments } => MessageData {
+                message: text,
+                message_key: Some(key.to_string()),
+                message_args: arguments,
+            },
+            _ => MessageData {
+                message: text,
+                message_key: None,
+                message_args: Vec::new(),
+            },
+        }
+    }
+}
+
+impl Message {
+    /// Text, Schlüssel und Werte wie beim Serialisieren
+    pub(crate) fn into_parts(self) -> (String, Option<String>, Vec<Argument>) {
+        let data = MessageData::from(self);
+        (data.message, data.message_key, data.message_args)
+    }
+
+    /// Gegenstück zu [`Message::into_parts`]
+    pub(crate) fn from_parts(text: String, key: Option<String>, arguments: Vec<Argument>) -> Self {
+        MessageData {
+            message: text,
+            message_key: key,
+            message_args: arguments,
+        }
+        .into()
+    }
+}
+
+/// Unbekannte Schlüssel, z. B. von einer neueren Version, ergeben den Text
+impl From<MessageData> for Message {
+    fn from(data: MessageData) -> Self {
+        let known = data.message_key.as_deref().and_then(entry).filter(|(_, german, _)| {
+            german.matches("{}").count() == data.message_args.len()
+        });
+        match known {
+            Some((key, _, _)) => Message(Content::Template {
+                key,
+                arguments: data.message_args,
+            }),
+            None => Message::text(data.message),
+        }
+    }
+}
+
+/// Serialisiert eine [`Message`] nur als deutschen Text
+///
+/// Für Felder, die schon vor [`Message`] Text waren, z. B. den Grund einer
+/// Verletzung des Audit-Protokolls.
+pub(crate) mod text {
+    use super::Message;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(message: &Message, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(message)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Message, D::Error> {
+        String::deserialize(deserializer).map(Message::text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Calculator, OperationType};
+
+    #[test]
+    fn test_translate_errors() {
+        let mut calc = Calculator::new();
+        let error = calc.divide(1.0, 0.0).unwrap_err();
+        assert_eq!(
+            error.localized(Locale::En),
+            "Division by zero is not allowed"
+        );
+        assert_eq!(
+            error.localized(Locale::De),
+            "Division durch Null ist nicht erlaubt"
+        );
+
+        let error = calc.evaluate("1 + (2").unwrap_err();
+        assert_eq!(
+            error.localized(Locale::En),
+            "Parse error: Parenthesis is not closed (line 1, column 5); add the missing ')'"
+        );
+        assert_eq!(error.code(), "parse_error");
+    }
+
+    #[test]
+    fn test_values_are_not_translated() {
+        let message = Message::new("session.created", ["Multiplikation".into()]);
+        assert_eq!(message.render(Locale::En), "Session 'Multiplikation' created");
+
+        let error = Calculator::new().apply("Subtraktion", &[1.0, 2.0]).unwrap_err();
+        assert_eq!(
+            error.localized(Locale::En),
+            "Invalid input: Unknown operation 'Subtraktion'"
+        );
+        assert_eq!(
+            Message::from("Subtraktion: Fehler").render(Locale::En),
+            "Subtraktion: Fehler"
+        );
+    }
+
+    #[test]
+    fn test_nested_messages() {
+        assert_eq!(OperationType::Subtract.localized(Locale::En), "Subtraction");
+        let lines = Message::lines([
+            Message::new("statistics.total", [2u64.into()]),
+            Message::new(
+                "statistics.operation",
+                [
+                    (&OperationType::Subtract).into(),
+                    2u64.into(),
+                    1.0.into(),
+                    3.0.into(),
+                    2.0.into(),
+                ],
+            ),
+        ]);
+        assert_eq!(
+            lines.render(Locale::En),
+            "Total operations: 2\nSubtraction: 2 (min 1, max 3, average 2)"
+        );
+        assert_eq!(
+            lines.to_string(),
+            "Operationen gesamt: 2\nSubtraktion: 2 (Min 1, Max 3, Durchschnitt 2)"
+        );
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let message = Message::new(
+            "error.invalid_input",
+            [Message::new("operation.unknown", ["wurzel".into()]).into()],
+        );
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(json["message"], "Ungültige Eingabe: Unbekannte Operation 'wurzel'");
+        assert_eq!(json["message_key"], "error.invalid_input");
+        assert_eq!(json["message_args"][0]["message_key"], "operation.unknown");
+
+        let restored: Message = serde_json::from_value(json).unwrap();
+        assert_eq!(restored, message);
+        assert_eq!(
+            restored.render(Locale::En),
+            "Invalid input: Unknown operation 'wurzel'"
+        );
+
+        // Unbekannte Schlüssel behalten den deutschen Text
+        let unknown: Message = serde_json::from_value(
+            serde_json::json!({"message": "Neu", "message_key": "gibt.es.nicht"}),
+        )
+        .unwrap();
+        assert_eq!(unknown, Message::text("Neu"));
+        assert_eq!(unknown.key(), None);
+    }
+
+    #[test]
+    fn test_catalog_is_consistent() {
+        for (index, (key, german, english)) in CATALOG.iter().enumerate() {
+            assert_eq!(
+                german.matches("{}").count(),
+                english.matches("{}").count(),
+                "{}",
+                key
+            );
+            assert!(
+                CATALOG[..index].iter().all(|(other, _, _)| other != key),
+                "{} ist doppelt",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn test_locale_from_str() {
+        assert_eq!("en_US.UTF-8".parse::<Locale>().unwrap(), Locale::En);
+        assert_eq!("de-AT".parse::<Locale>().unwrap(), Locale::De);
+        assert_eq!("EN".parse::<Locale>().unwrap(), Locale::En);
+        assert!("C.UTF-8".parse::<Locale>().is_err());
+        assert_eq!(Locale::En.to_string(), "en");
+        assert_eq!(serde_json::to_string(&Locale::En).unwrap(), "\"en\"");
+    }
+}
@@ -0,0 +1,249 @@
+//! Strukturierte Protokollierung mit `tracing`
+//!
+//! Operationen des [`Calculator`](crate::Calculator), Dateizugriffe in
+//! [`utils`](crate::modules::utils), JSON-RPC-Anfragen und Befehle der
+//! Kommandozeile laufen in eigenen Spans. Jeder Span trägt eine
+//! Korrelations-ID, über die sich alle Einträge eines Befehls oder einer
+//! Anfrage zuordnen lassen.
+
+use crate::modules::error::ProjectError;
+use crate::modules::i18n::Message;
+use crate::modules::utils;
+use std::cell::RefCell;
+use std::fmt;
+use std::io::{self, IsTerminal};
+use std::str::FromStr;
+use tracing::Subscriber;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::EnvFilter;
+
+/// Filter, wenn `RUST_LOG` nicht gesetzt oder ungültig ist
+const DEFAULT_FILTER: &str = "warn";
+
+/// Ausgabeformat der Protokollierung
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Mehrzeilig und lesbar für das Terminal
+    #[default]
+    Pretty,
+    /// Ein JSON-Objekt je Zeile für die Log-Aggregation
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = ProjectError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pretty" => Ok(LogFormat::Pretty),
+            "json" => Ok(LogFormat::Json),
+            other => Err(ProjectError::invalid_input(Message::new(
+                "cli.unknown_log_format",
+                [other.into()],
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogFormat::Pretty => f.write_str("pretty"),
+            LogFormat::Json => f.write_str("json"),
+        }
+    }
+}
+
+thread_local! {
+    static CORRELATION_ID: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Korrelations-ID des aktuellen Threads, sonst eine neue
+pub fn correlation_id() -> String {
+    CORRELATION_ID
+        .with(|current| current.borrow().clone())
+        .unwrap_or_else(utils::generate_id)
+}
+
+/// Führt `f` mit der Korrelations-ID `id` aus
+///
+/// Alle Spans, die währenddessen im selben Thread entstehen, tragen diese ID.
+///
+/// # Beispiel
+///
+/// ```rust
+/// use rust_project_complete::modules::logging::{correlation_id, with_correlation_id};
+///
+/// with_correlation_id("auftrag-42", || assert_eq!(correlation_id(), "auftrag-42"));
+/// assert_ne!(correlation_id(), "auftrag-42");
+/// ```
+pub fn with_correlation_id<R>(id: &str, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<String>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CORRELATION_ID.with(|current| *current.borrow_mut() = self.0.take());
+        }
+    }
+
+    let previous = CORRELATION_ID.with(|current| current.replace(Some(id.to_string())));
+    let _restore = Restore(previous);
+    f()
+}
+
+/// Richtet die Protokollierung nach stderr ein
+///
+/// Der Filter wird aus `RUST_LOG` gelesen, z. B. `debug` oder
+/// `rust_project_complete=trace`; ohne Angabe werden nur Warnungen ausgegeben.
+///
+/// # Errors
+///
+/// Gibt einen Fehler zurück, wenn bereits eine Protokollierung eingerichtet ist.
+pub fn init(format: LogFormat) -> Result<(), ProjectError> {
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+    let ansi = io::stderr().is_terminal();
+    tracing::subscriber::set_global_default(subscriber(format, filter, ansi, io::stderr))
+        .map_err(|error| ProjectError::invalid_input(error.to_string()))
+}
+
+/// Erstellt einen Subscriber, der beim Schließen jedes Spans einen Eintrag schreibt
+fn subscriber<W>(
+    format: LogFormat,
+    filter: EnvFilter,
+    ansi: bool,
+    writer: W,
+) -> Box<dyn Subscriber + Send + Sync>
+where
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_writer(writer);
+    match format {
+        LogFormat::Pretty => Box::new(builder.pretty().with_ansi(ansi).finish()),
+        LogFormat::Json => Box::new(
+            builder
+                .json()
+                .with_current_span(true)
+                .with_span_list(true)
+                .finish(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::numeric::NumericPolicy;
+    use crate::Calculator;
+    use std::sync::{Arc, Mutex};
+
+    /// Sammelt die Ausgabe des Subscribers im Speicher
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn capture(format: LogFormat, f: impl FnOnce()) -> String {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = subscriber(format, EnvFilter::new("debug"), false, move || {
+            writer.clone()
+        });
+        tracing::subscriber::with_default(subscriber, f);
+        let output = buffer.0.lock().unwrap().clone();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_correlation_id_is_scoped() {
+        assert_ne!(correlation_id(), correlation_id());
+        with_correlation_id("außen", || {
+            with_correlation_id("innen", || assert_eq!(correlation_id(), "innen"));
+            assert_eq!(correlation_id(), "außen");
+        });
+    }
+
+    #[test]
+    fn test_json_spans() {
+        let output = capture(LogFormat::Json, || {
+            with_correlation_id("test-1", || {
+                let mut calc = Calculator::new();
+                calc.add(2.0, 3.0).unwrap();
+                calc.divide(1.0, 0.0).unwrap_err();
+            })
+        });
+
+        let records: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+
+        let add = &records[0]["span"];
+        assert_eq!(add["name"], "operation");
+        assert_eq!(add["correlation_id"], "test-1");
+        assert_eq!(add["operation"], "add");
+        assert_eq!(add["operands"], "[2.0, 3.0]");
+        assert_eq!(add["result"], 5.0);
+
+        let divide = &records[1]["span"];
+        assert_eq!(divide["operation"], "divide");
+        assert_eq!(divide["error"], "Division durch Null ist nicht erlaubt");
+        assert!(divide.get("result").is_none());
+    }
+
+    #[test]
+    fn test_numeric_policy_warns() {
+        let output = capture(LogFormat::Json, || {
+            let mut calc = Calculator::new();
+            calc.set_numeric_policy(NumericPolicy::Warn);
+            calc.multiply(1e200, 1e200).unwrap();
+        });
+
+        let warnings: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .filter(|record: &serde_json::Value| record["level"] == "WARN")
+            .collect();
+        assert_eq!(warnings.len(), 1);
+        let fields = &warnings[0]["fields"];
+        assert_eq!(fields["message"], "Ergebnis ist keine endliche Zahl");
+        assert_eq!(fields["operation"], "multiply");
+        assert_eq!(warnings[0]["span"]["operation"], "multiply");
+    }
+
+    #[test]
+    fn test_pretty_file_spans() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("daten.txt");
+        let path = path.to_str().unwrap();
+
+        let output = capture(LogFormat::Pretty, || {
+            utils::write_file_content(path, "hallo").unwrap();
+            utils::read_file_content(path).unwrap();
+        });
+        assert!(output.contains("file_write"));
+        assert!(output.contains("file_read"));
+        assert!(output.contains("bytes: 5"));
+    }
+
+    #[test]
+    fn test_log_format_from_str() {
+        assert_eq!("json".parse::<LogFormat>().unwrap(), LogFormat::Json);
+        assert_eq!(LogFormat::Pretty.to_string(), "pretty");
+        assert!("xml".parse::<LogFormat>().is_err());
+    }
+}
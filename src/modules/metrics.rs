@@ -0,0 +1,354 @@
+//! Kennzahlen im Prometheus-Textformat
+
+use crate::modules::error::ProjectError;
+use crate::modules::observer::{CalculatorObserver, HistoryEvent, ObserverHandle};
+use crate::modules::shared::SharedCalculator;
+use crate::{Operation, OperationType};
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Obergrenzen der Latenz-Buckets in Sekunden
+const BUCKETS: [f64; 8] = [1e-6, 1e-5, 1e-4, 1e-3, 1e-2, 0.1, 1.0, 10.0];
+
+/// Content-Type der Ausgabe von [`Metrics::render`]
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+thread_local! {
+    // Beobachter laufen im Thread der Operation, daher genügt ein Startzeitpunkt je Thread
+    static STARTED: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+/// Kennzahlen eines Calculators
+///
+/// Erfasst erfolgreiche Operationen je [`OperationType`], Fehler je
+/// Operation und Fehlerart ([`ProjectError::code`]), die Dauer der Operationen
+/// als Histogramm sowie die Größe des Verlaufs. Ein `Metrics` sollte nur an
+/// einen Calculator gebunden werden, da sich die Verlaufsgröße sonst vermischt.
+///
+/// # Beispiel
+///
+/// ```rust
+/// use rust_project_complete::modules::metrics::Metrics;
+/// use rust_project_complete::modules::shared::SharedCalculator;
+///
+/// let calc = SharedCalculator::new();
+/// let metrics = Metrics::new();
+/// metrics.attach(&calc);
+///
+/// calc.add(1.0, 2.0).unwrap();
+/// calc.divide(1.0, 0.0).unwrap_err();
+///
+/// let text = metrics.render();
+/// assert!(text.contains("calculator_operations_total{operation=\"add\"} 1"));
+/// assert!(text.contains(
+///     "calculator_errors_total{operation=\"divide\",kind=\"division_by_zero\"} 1"
+/// ));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    inner: Arc<Mutex<Registry>>,
+}
+
+#[derive(Debug, Default)]
+struct Registry {
+    operations: BTreeMap<String, u64>,
+    errors: BTreeMap<(String, &'static str), u64>,
+    durations: BTreeMap<String, Histogram>,
+    history_size: u64,
+}
+
+#[derive(Debug, Default)]
+struct Histogram {
+    /// Anzahl je Bucket, nicht kumuliert
+    buckets: [u64; BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        if let Some(index) = BUCKETS.iter().position(|bound| seconds <= *bound) {
+            self.buckets[index] += 1;
+        }
+        self.sum += seconds;
+        self.count += 1;
+    }
+}
+
+impl Metrics {
+    /// Erstellt leere Kennzahlen
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Beobachter, der die Operationen eines Calculators erfasst
+    ///
+    /// Die Verlaufsgröße beginnt bei null; siehe [`Metrics::attach`].
+    pub fn observer(&self) -> Arc<dyn CalculatorObserver> {
+        Arc::new(MetricsObserver {
+            metrics: self.clone(),
+        })
+    }
+
+    /// Erfasst die Operationen von `calculator` und übernimmt die aktuelle Verlaufsgröße
+    pub fn attach(&self, calculator: &SharedCalculator) -> ObserverHandle {
+        calculator.write(|calc| {
+            self.lock().history_size = calc.history_count() as u64;
+            calc.add_observer(self.observer())
+        })
+    }
+
+    /// Anzahl erfolgreicher Operationen einer Art
+    pub fn operation_count(&self, operation_type: &OperationType) -> u64 {
+        let registry = self.lock();
+        registry
+            .operations
+            .get(operation_type.name())
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Anzahl der Fehler einer Fehlerart über alle Operationen, z. B. `"division_by_zero"`
+    pub fn error_count(&self, kind: &str) -> u64 {
+        let registry = self.lock();
+        registry
+            .errors
+            .iter()
+            .filter(|((_, k), _)| *k == kind)
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    /// Anzahl der Operationen im Verlauf
+    pub fn history_size(&self) -> u64 {
+        self.lock().history_size
+    }
+
+    /// Gibt alle Kennzahlen im Prometheus-Textformat zurück
+    pub fn render(&self) -> String {
+        let registry = self.lock();
+        let mut out = String::new();
+
+        header(
+            &mut out,
+            "calculator_operations_total",
+            "Erfolgreiche Operationen je Art",
+            "counter",
+        );
+        for (operation, count) in &registry.operations {
+            let _ = writeln!(
+                out,
+                "calculator_operations_total{{operation=\"{}\"}} {}",
+                escape(operation),
+                count
+            );
+        }
+
+        header(
+            &mut out,
+            "calculator_errors_total",
+            "Fehlgeschlagene Operationen je Art und Fehlerart",
+            "counter",
+        );
+        for ((operation, kind), count) in &registry.errors {
+            let _ = writeln!(
+                out,
+                "calculator_errors_total{{operation=\"{}\",kind=\"{}\"}} {}",
+                escape(operation),
+                kind,
+                count
+            );
+        }
+
+        header(
+            &mut out,
+            "calculator_operation_duration_seconds",
+            "Dauer der Operationen in Sekunden",
+            "histogram",
+        );
+        for (operation, histogram) in &registry.durations {
+            let operation = escape(operation);
+            let mut cumulative = 0;
+            for (bound, count) in BUCKETS.iter().zip(histogram.buckets) {
+                cumulative += count;
+                let _ = writeln!(
+                    out,
+                    "calculator_operation_duration_seconds_bucket{{operation=\"{}\",le=\"{}\"}} {}",
+                    operation, bound, cumulative
+                );
+            }
+            let _ = writeln!(
+                out,
+                "calculator_operation_duration_seconds_bucket{{operation=\"{}\",le=\"+Inf\"}} {}",
+                operation, histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "calculator_operation_duration_seconds_sum{{operation=\"{}\"}} {}",
+                operation, histogram.sum
+            );
+            let _ = writeln!(
+                out,
+                "calculator_operation_duration_seconds_count{{operation=\"{}\"}} {}",
+                operation, histogram.count
+            );
+        }
+
+        header(
+            &mut out,
+            "calculator_history_size",
+            "Operationen im Verlauf",
+            "gauge",
+        );
+        let _ = writeln!(out, "calculator_history_size {}", registry.history_size);
+        out
+    }
+
+    // Eine Panik beim Erfassen darf die Kennzahlen nicht dauerhaft sperren
+    fn lock(&self) -> MutexGuard<'_, Registry> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn observe_duration(registry: &mut Registry, operation: &str) {
+        if let Some(started) = STARTED.with(Cell::take) {
+            registry
+                .durations
+                .entry(operation.to_string())
+                .or_default()
+                .observe(started.elapsed());
+        }
+    }
+}
+
+struct MetricsObserver {
+    metrics: Metrics,
+}
+
+impl CalculatorObserver for MetricsObserver {
+    fn before_operation(
+        &self,
+        _operation_type: &OperationType,
+        _operands: &[f64],
+    ) -> Result<(), ProjectError> {
+        STARTED.with(|started| started.set(Some(Instant::now())));
+        Ok(())
+    }
+
+    fn after_operation(&self, operation: &Operation) {
+        let name = operation.operation_type.name();
+        let mut registry = self.metrics.lock();
+        *registry.operations.entry(name.to_string()).or_default() += 1;
+        Metrics::observe_duration(&mut registry, name);
+    }
+
+    fn operation_failed(
+        &self,
+        operation_type: &OperationType,
+        _operands: &[f64],
+        error: &ProjectError,
+    ) {
+        let name = operation_type.name();
+        let mut registry = self.metrics.lock();
+        *registry
+            .errors
+            .entry((name.to_string(), error.code()))
+            .or_default() += 1;
+        Metrics::observe_duration(&mut registry, name);
+    }
+
+    fn history_changed(&self, event: &HistoryEvent<'_>) {
+        let mut registry = self.metrics.lock();
+        match event {
+            HistoryEvent::Appended(_) => registry.history_size += 1,
+            HistoryEvent::Cleared => registry.history_size = 0,
+        }
+    }
+}
+
+fn header(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// Maskiert einen Label-Wert nach dem Prometheus-Textformat
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Calculator;
+
+    #[test]
+    fn test_counts_and_history_size() {
+        let mut calc = Calculator::new();
+        calc.add(1.0, 1.0).unwrap();
+        let calc = SharedCalculator::from(calc);
+        let metrics = Metrics::new();
+        metrics.attach(&calc);
+        assert_eq!(metrics.history_size(), 1);
+
+        calc.add(2.0, 2.0).unwrap();
+        calc.divide(1.0, 0.0).unwrap_err();
+        calc.divide(2.0, 0.0).unwrap_err();
+        calc.apply("wurzel", &[4.0]).unwrap_err();
+
+        assert_eq!(metrics.operation_count(&OperationType::Add), 1);
+        assert_eq!(metrics.operation_count(&OperationType::Divide), 0);
+        assert_eq!(metrics.error_count("division_by_zero"), 2);
+        assert_eq!(metrics.history_size(), 2);
+
+        calc.clear_history().unwrap();
+        assert_eq!(metrics.history_size(), 0);
+    }
+
+    #[test]
+    fn test_render() {
+        let calc = SharedCalculator::new();
+        let metrics = Metrics::new();
+        metrics.attach(&calc);
+        calc.multiply(2.0, 3.0).unwrap();
+        calc.multiply(4.0, 5.0).unwrap();
+        calc.divide(1.0, 0.0).unwrap_err();
+
+        let text = metrics.render();
+        assert!(text.contains("# TYPE calculator_operations_total counter\n"));
+        assert!(text.contains("calculator_operations_total{operation=\"multiply\"} 2\n"));
+        assert!(text.contains(
+            "calculator_errors_total{operation=\"divide\",kind=\"division_by_zero\"} 1\n"
+        ));
+        assert!(text.contains(
+            "calculator_operation_duration_seconds_bucket{operation=\"multiply\",le=\"+Inf\"} 2\n"
+        ));
+        assert!(
+            text.contains("calculator_operation_duration_seconds_count{operation=\"divide\"} 1\n")
+        );
+        assert!(text.contains("# TYPE calculator_history_size gauge\ncalculator_history_size 2\n"));
+
+        // Buckets sind kumuliert und enden bei der Gesamtzahl
+        let buckets: Vec<u64> = text
+            .lines()
+            .filter(|line| {
+                line.starts_with(
+                    "calculator_operation_duration_seconds_bucket{operation=\"multiply\"",
+                )
+            })
+            .map(|line| line.rsplit(' ').next().unwrap().parse().unwrap())
+            .collect();
+        assert_eq!(buckets.len(), BUCKETS.len() + 1);
+        assert!(buckets.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
@@ -1,5 +1,30 @@
 //! Module für das Rust-Projekt
 
+pub mod async_api;
+pub mod audit;
+pub mod batch;
 pub mod core;
+#[cfg(unix)]
+pub mod daemon;
+pub mod diagnostic;
 pub mod error;
+pub mod expression;
+pub mod ffi;
+pub mod history;
+pub mod i18n;
+pub mod logging;
+pub mod metrics;
+pub mod numeric;
+pub mod observer;
+pub mod operations;
+pub mod output;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod rpc;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod session;
+pub mod shared;
+#[cfg(feature = "tui")]
+pub mod tui;
 pub mod utils;
@@ -0,0 +1,306 @@
+//! Umgang mit nicht endlichen Zahlen (NaN und ±Unendlich)
+
+use crate::modules::error::ProjectError;
+use crate::modules::i18n::Message;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Richtlinie für NaN und ±Unendlich in Operanden und Ergebnissen
+///
+/// Gilt für alle Operationen eines [`Calculator`](crate::Calculator), auch für
+/// eigene und solche in Ausdrücken, und für das Ergebnis von
+/// [`Calculator::evaluate`](crate::Calculator::evaluate).
+///
+/// # Beispiel
+///
+/// ```rust
+/// use rust_project_complete::modules::numeric::NumericPolicy;
+/// use rust_project_complete::Calculator;
+///
+/// let mut calc = Calculator::new();
+/// assert_eq!(calc.multiply(1e200, 1e200).unwrap(), f64::INFINITY);
+///
+/// calc.set_numeric_policy(NumericPolicy::Strict);
+/// let error = calc.multiply(1e200, 1e200).unwrap_err();
+/// assert_eq!(error.code(), "overflow");
+/// assert_eq!(calc.history_count(), 1);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NumericPolicy {
+    /// Lehnt nicht endliche Operanden und Ergebnisse mit einem Fehler ab
+    Strict,
+    /// Rechnet weiter, protokolliert aber eine Warnung
+    Warn,
+    /// Rechnet ohne Prüfung weiter wie IEEE 754
+    #[default]
+    Permissive,
+}
+
+impl NumericPolicy {
+    /// Prüft die Operanden vor einer Operation
+    ///
+    /// # Errors
+    ///
+    /// Gibt mit [`NumericPolicy::Strict`] [`ProjectError::InvalidInput`] für den
+    /// ersten nicht endlichen Operanden zurück.
+    pub fn check_operands(self, operation: &str, operands: &[f64]) -> Result<(), ProjectError> {
+        let Some(value) = operands.iter().find(|value| !value.is_finite()) else {
+            return Ok(());
+        };
+        match self {
+            NumericPolicy::Strict => Err(ProjectError::invalid_input(Message::new(
+                "numeric.operand_not_finite",
+                [(*value).into()],
+            ))),
+            NumericPolicy::Warn => {
+                tracing::warn!(operation, operands = ?operands, "Operand ist keine endliche Zahl");
+                Ok(())
+            }
+            NumericPolicy::Permissive => Ok(()),
+        }
+    }
+
+    /// Prüft das Ergebnis einer Operation
+    ///
+    /// # Errors
+    ///
+    /// Gibt mit [`NumericPolicy::Strict`] [`ProjectError::OverflowError`] für
+    /// ±Unendlich und [`ProjectError::InvalidInput`] für NaN zurück.
+    pub fn check_result(
+        self,
+        operation: &str,
+        operands: &[f64],
+        result: f64,
+    ) -> Result<(), ProjectError> {
+        if result.is_finite() {
+            return Ok(());
+        }
+        match self {
+            NumericPolicy::Strict if result.is_nan() => {
+                Err(ProjectError::invalid_input(Message::new("numeric.result_nan", [])))
+            }
+            NumericPolicy::Strict => Err(ProjectError::overflow(Message::new(
+                "numeric.result_not_finite",
+                [result.into()],
+            ))),
+            NumericPolicy::Warn => {
+                tracing::warn!(
+                    operation,
+                    operands = ?operands,
+                    result,
+                    "Ergebnis ist keine endliche Zahl"
+                );
+                Ok(())
+            }
+            NumericPolicy::Permissive => Ok(()),
+        }
+    }
+}
+
+impl FromStr for NumericPolicy {
+    type Err = ProjectError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "strict" => Ok(NumericPolicy::Strict),
+            "warn" => Ok(NumericPolicy::Warn),
+            "permissive" => Ok(NumericPolicy::Permissive),
+            other => Err(ProjectError::invalid_input(Message::new(
+                "numeric.unknown_policy",
+                [other.into()],
+            ))),
+        }
+    }
+}
+
+/// Serde-Darstellung für `f64`, die NaN und ±Unendlich verlustfrei erhält
+///
+/// JSON kennt nur endliche Zahlen; `serde_json` schreibt die übrigen als
+/// `null`. Hier bleiben endliche Zahlen JSON-Zahlen, nicht endliche werden als
+/// `"inf"`, `"-inf"` und `"NaN"` geschrieben. `null` aus älteren Dateien wird
+/// als NaN gelesen.
+///
+/// # Beispiel
+///
+/// ```rust
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Messung {
+///     #[serde(with = "rust_project_complete::modules::numeric::lossless")]
+///     wert: f64,
+/// }
+///
+/// let json = serde_json::to_string(&Messung { wert: f64::NEG_INFINITY }).unwrap();
+/// assert_eq!(json, r#"{"wert":"-inf"}"#);
+/// let messung: Messung = serde_json::from_str(&json).unwrap();
+/// assert_eq!(messung.wert, f64::NEG_INFINITY);
+/// ```
+pub mod lossless {
+    use serde::de::{self, Deserializer};
+    use serde::ser::Serializer;
+    use serde::{Deserialize, Serialize};
+
+    /// Einzelner Wert in verlustfreier Darstellung
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Lossless(pub f64);
+
+    impl Serialize for Lossless {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match self.0 {
+                value if value.is_finite() => serializer.serialize_f64(value),
+                value if value.is_nan() => serializer.serialize_str("NaN"),
+                value if value > 0.0 => serializer.serialize_str("inf"),
+                _ => serializer.serialize_str("-inf"),
+            }
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Lossless {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            #[derive(Deserialize)]
+            #[serde(untagged)]
+            enum Encoded {
+                Number(f64),
+                Text(String),
+            }
+
+            match Option::<Encoded>::deserialize(deserializer)? {
+                Some(Encoded::Number(value)) => Ok(Lossless(value)),
+                Some(Encoded::Text(text)) => match text.as_str() {
+                    "inf" => Ok(Lossless(f64::INFINITY)),
+                    "-inf" => Ok(Lossless(f64::NEG_INFINITY)),
+                    "NaN" => Ok(Lossless(f64::NAN)),
+                    other => Err(de::Error::invalid_value(
+                        de::Unexpected::Str(other),
+                        &"eine Zahl, \"inf\", \"-inf\" oder \"NaN\"",
+                    )),
+                },
+                None => Ok(Lossless(f64::NAN)),
+            }
+        }
+    }
+
+    pub fn serialize<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+        Lossless(*value).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+        Ok(Lossless::deserialize(deserializer)?.0)
+    }
+
+    /// Dasselbe für Listen, z. B. Operanden
+    pub mod vec {
+        use super::Lossless;
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<S: Serializer>(values: &[f64], serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(values.iter().copied().map(Lossless))
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<f64>, D::Error> {
+            let values = Vec::<Lossless>::deserialize(deserializer)?;
+            Ok(values.into_iter().map(|value| value.0).collect())
+        }
+    }
+
+    /// Dasselbe für Tabellen, z. B. Variablen
+    pub mod map {
+        use super::Lossless;
+        use serde::{Deserialize, Deserializer, Serializer};
+        use std::collections::BTreeMap;
+
+        pub fn serialize<S: Serializer>(
+            values: &BTreeMap<String, f64>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            serializer.collect_map(values.iter().map(|(name, value)| (name, Lossless(*value))))
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<BTreeMap<String, f64>, D::Error> {
+            let values = BTreeMap::<String, Lossless>::deserialize(deserializer)?;
+            Ok(values.into_iter().map(|(name, value)| (name, value.0)).collect())
+        }
+    }
+}
+
+impl fmt::Display for NumericPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NumericPolicy::Strict => f.write_str("strict"),
+            NumericPolicy::Warn => f.write_str("warn"),
+            NumericPolicy::Permissive => f.write_str("permissive"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strict() {
+        let strict = NumericPolicy::Strict;
+        assert_eq!(strict.check_operands("add", &[1.0, 2.0]), Ok(()));
+        assert_eq!(
+            strict.check_operands("add", &[1.0, f64::NAN]),
+            Err(ProjectError::invalid_input(Message::new(
+                "numeric.operand_not_finite",
+                [f64::NAN.into()]
+            )))
+        );
+        assert_eq!(
+            strict.check_result("multiply", &[1e200, -1e200], f64::NEG_INFINITY),
+            Err(ProjectError::overflow(Message::new(
+                "numeric.result_not_finite",
+                [f64::NEG_INFINITY.into()]
+            )))
+        );
+        assert_eq!(
+            strict
+                .check_result("custom", &[], f64::NAN)
+                .unwrap_err()
+                .code(),
+            "invalid_input"
+        );
+    }
+
+    #[test]
+    fn test_warn_and_permissive_accept() {
+        for policy in [NumericPolicy::Warn, NumericPolicy::Permissive] {
+            assert_eq!(policy.check_operands("add", &[f64::INFINITY]), Ok(()));
+            assert_eq!(policy.check_result("add", &[], f64::NAN), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_lossless_round_trip() {
+        use lossless::Lossless;
+
+        for value in [1.5, -0.0, f64::INFINITY, f64::NEG_INFINITY, f64::MAX] {
+            let json = serde_json::to_string(&Lossless(value)).unwrap();
+            assert_eq!(serde_json::from_str::<Lossless>(&json).unwrap(), Lossless(value));
+        }
+        assert_eq!(serde_json::to_string(&Lossless(f64::NAN)).unwrap(), "\"NaN\"");
+        assert!(serde_json::from_str::<Lossless>("\"NaN\"").unwrap().0.is_nan());
+        assert!(serde_json::from_str::<Lossless>("null").unwrap().0.is_nan());
+        assert!(serde_json::from_str::<Lossless>("\"unendlich\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        for policy in [
+            NumericPolicy::Strict,
+            NumericPolicy::Warn,
+            NumericPolicy::Permissive,
+        ] {
+            assert_eq!(policy.to_string().parse::<NumericPolicy>(), Ok(policy));
+        }
+        assert!("streng".parse::<NumericPolicy>().is_err());
+        assert_eq!(NumericPolicy::default(), NumericPolicy::Permissive);
+    }
+}
@@ -0,0 +1,298 @@
+//! Beobachter für Operationen und Verlaufsänderungen
+
+use crate::modules::error::ProjectError;
+use crate::modules::i18n::Message;
+use crate::{Operation, OperationType};
+use std::fmt;
+use std::sync::Arc;
+
+/// Änderung am Verlauf eines Calculators
+#[derive(Debug, Clone, Copy)]
+pub enum HistoryEvent<'a> {
+    /// Eine Operation wurde an den Verlauf angehängt
+    Appended(&'a Operation),
+    /// Der Verlauf wurde gelöscht
+    Cleared,
+}
+
+/// Beobachter, der über die Operationen eines Calculators informiert wird
+///
+/// Alle Methoden haben leere Standardimplementierungen, sodass nur die
+/// benötigten Ereignisse überschrieben werden müssen.
+pub trait CalculatorObserver: Send + Sync {
+    /// Wird vor jeder Operation aufgerufen
+    ///
+    /// # Errors
+    ///
+    /// Ein Fehler verhindert die Operation und wird an den Aufrufer weitergegeben.
+    fn before_operation(
+        &self,
+        _operation_type: &OperationType,
+        _operands: &[f64],
+    ) -> Result<(), ProjectError> {
+        Ok(())
+    }
+
+    /// Wird nach jeder erfolgreichen Operation aufgerufen
+    fn after_operation(&self, _operation: &Operation) {}
+
+    /// Wird aufgerufen, wenn eine Operation abgelehnt wurde oder fehlgeschlagen ist
+    fn operation_failed(
+        &self,
+        _operation_type: &OperationType,
+        _operands: &[f64],
+        _error: &ProjectError,
+    ) {
+    }
+
+    /// Wird nach jeder Änderung des Verlaufs aufgerufen
+    fn history_changed(&self, _event: &HistoryEvent<'_>) {}
+}
+
+/// Handle, mit dem ein registrierter Beobachter wieder entfernt werden kann
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObserverHandle(u64);
+
+/// Registrierte Beobachter eines Calculators
+#[derive(Default)]
+pub struct Observers {
+    next_id: u64,
+    entries: Vec<(ObserverHandle, Arc<dyn CalculatorObserver>)>,
+}
+
+impl fmt::Debug for Observers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Observers")
+            .field("count", &self.entries.len())
+            .finish()
+    }
+}
+
+impl Observers {
+    /// Registriert einen Beobachter
+    pub fn add(&mut self, observer: Arc<dyn CalculatorObserver>) -> ObserverHandle {
+        let handle = ObserverHandle(self.next_id);
+        self.next_id += 1;
+        self.entries.push((handle, observer));
+        handle
+    }
+
+    /// Entfernt einen Beobachter; gibt `false` zurück, wenn das Handle unbekannt ist
+    pub fn remove(&mut self, handle: ObserverHandle) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|(h, _)| *h != handle);
+        self.entries.len() != before
+    }
+
+    /// Gibt die Anzahl der registrierten Beobachter zurück
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Prüft, ob keine Beobachter registriert sind
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Fragt alle Beobachter; der erste Einspruch bricht die Operation ab
+    pub(crate) fn before_operation(
+        &self,
+        operation_type: &OperationType,
+        operands: &[f64],
+    ) -> Result<(), ProjectError> {
+        self.entries
+            .iter()
+            .try_for_each(|(_, observer)| observer.before_operation(operation_type, operands))
+    }
+
+    pub(crate) fn after_operation(&self, operation: &Operation) {
+        for (_, observer) in &self.entries {
+            observer.after_operation(operation);
+        }
+    }
+
+    pub(crate) fn operation_failed(
+        &self,
+        operation_type: &OperationType,
+        operands: &[f64],
+        error: &ProjectError,
+    ) {
+        for (_, observer) in &self.entries {
+            observer.operation_failed(operation_type, operands, error);
+        }
+    }
+
+    pub(crate) fn history_changed(&self, event: &HistoryEvent<'_>) {
+        for (_, observer) in &self.entries {
+            observer.history_changed(event);
+        }
+    }
+}
+
+/// Beobachter, der Operanden außerhalb eines erlaubten Bereichs ablehnt
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperandRangePolicy {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl OperandRangePolicy {
+    /// Erstellt eine Richtlinie für den Bereich `min..=max`
+    pub fn new(min: f64, max: f64) -> Self {
+        Self { min, max }
+    }
+}
+
+impl CalculatorObserver for OperandRangePolicy {
+    fn before_operation(
+        &self,
+        operation_type: &OperationType,
+        operands: &[f64],
+    ) -> Result<(), ProjectError> {
+        match operands
+            .iter()
+            .find(|value| !(self.min..=self.max).contains(*value))
+        {
+            Some(value) => Err(ProjectError::invalid_input(Message::new(
+                "observer.out_of_range",
+                [operation_type.into(), (*value).into(), self.min.into(), self.max.into()],
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Calculator;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl CalculatorObserver for Recorder {
+        fn before_operation(
+            &self,
+            operation_type: &OperationType,
+            _operands: &[f64],
+        ) -> Result<(), ProjectError> {
+            self.events.lock().unwrap().push(format!("before {}", operation_type));
+            Ok(())
+        }
+
+        fn after_operation(&self, operation: &Operation) {
+            self.events.lock().unwrap().push(format!("after {}", operation.result));
+        }
+
+        fn operation_failed(
+            &self,
+            operation_type: &OperationType,
+            _operands: &[f64],
+            _error: &ProjectError,
+        ) {
+            self.events.lock().unwrap().push(format!("failed {}", operation_type));
+        }
+
+        fn history_changed(&self, event: &HistoryEvent<'_>) {
+            let entry = match event {
+                HistoryEvent::Appended(op) => format!("appended {}", op.operation_type),
+                HistoryEvent::Cleared => "cleared".to_string(),
+            };
+            self.events.lock().unwrap().push(entry);
+        }
+    }
+
+    #[test]
+    fn test_observer_receives_events_in_order() {
+        let recorder = Arc::new(Recorder::default());
+        let mut calc = Calculator::new();
+        calc.add_observer(recorder.clone());
+
+        calc.add(1.0, 2.0).unwrap();
+        assert!(calc.divide(1.0, 0.0).is_err());
+        calc.clear_history().unwrap();
+
+        let events = recorder.events.lock().unwrap();
+        assert_eq!(
+            *events,
+            vec![
+                "before Addition",
+                "after 3",
+                "appended Addition",
+                "before Division",
+                "failed Division",
+                "cleared",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_observer_sees_factorial() {
+        let recorder = Arc::new(Recorder::default());
+        let mut calc = Calculator::new();
+        calc.add_observer(recorder.clone());
+
+        assert_eq!(calc.factorial(4).unwrap(), 24);
+        assert!(calc.factorial(21).is_err());
+
+        let events = recorder.events.lock().unwrap();
+        assert_eq!(
+            *events,
+            vec![
+                "before factorial",
+                "after 24",
+                "appended factorial",
+                "before factorial",
+                "failed factorial",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_range_policy_vetoes_operation() {
+        let mut calc = Calculator::new();
+        calc.add_observer(Arc::new(OperandRangePolicy::new(-100.0, 100.0)));
+
+        assert_eq!(calc.multiply(10.0, 10.0).unwrap(), 100.0);
+        let result = calc.multiply(1000.0, 2.0);
+
+        assert!(matches!(result, Err(ProjectError::InvalidInput { .. })));
+        assert_eq!(calc.history_count(), 1);
+    }
+
+    #[test]
+    fn test_remove_observer() {
+        struct Counter(AtomicUsize);
+        impl CalculatorObserver for Counter {
+            fn after_operation(&self, _operation: &Operation) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let counter = Arc::new(Counter(AtomicUsize::new(0)));
+        let mut calc = Calculator::new();
+        let handle = calc.add_observer(counter.clone());
+
+        calc.add(1.0, 1.0).unwrap();
+        assert!(calc.remove_observer(handle));
+        assert!(!calc.remove_observer(handle));
+        calc.add(1.0, 1.0).unwrap();
+
+        assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_snapshot_does_not_copy_observers() {
+        let mut calc = Calculator::new();
+        calc.add_observer(Arc::new(OperandRangePolicy::new(0.0, 1.0)));
+        calc.add(0.5, 0.5).unwrap();
+
+        let mut snapshot = calc.snapshot().unwrap();
+        assert_eq!(snapshot.history_count(), 1);
+        assert!(snapshot.add(5.0, 5.0).is_ok());
+    }
+}
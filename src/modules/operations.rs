@@ -0,0 +1,584 @@
+//! Erweiterbare Operationen für Calculator, Ausdrücke und Kommandozeile
+
+use crate::modules::error::ProjectError;
+use crate::modules::i18n::Message;
+use crate::modules::expression::Expression;
+use crate::OperationType;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, LazyLock};
+
+/// Operation, die ein [`Calculator`](crate::Calculator) ausführen kann
+///
+/// Registrierte Operationen landen wie die Grundrechenarten im Verlauf und in
+/// den Statistiken; im Verlauf werden sie unter [`Operator::name`] gespeichert.
+pub trait Operator: Send + Sync {
+    /// Eindeutiger Name, z. B. `"power"`; dient auch als Funktionsname in Ausdrücken
+    fn name(&self) -> &str;
+
+    /// Anzahl der Operanden
+    fn arity(&self) -> usize;
+
+    /// Infix-Symbol in Ausdrücken, z. B. `"^"`; nur für zweistellige Operationen
+    ///
+    /// Symbole binden so stark wie `*` und `/`.
+    fn symbol(&self) -> Option<&str> {
+        None
+    }
+
+    /// Prüft die Operanden vor der Berechnung
+    ///
+    /// # Errors
+    ///
+    /// Ein Fehler verhindert die Operation.
+    fn validate(&self, _operands: &[f64]) -> Result<(), ProjectError> {
+        Ok(())
+    }
+
+    /// Berechnet das Ergebnis; `operands` hat genau [`Operator::arity`] Einträge
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn die Berechnung fehlschlägt.
+    fn evaluate(&self, operands: &[f64]) -> Result<f64, ProjectError>;
+
+    /// Art der Operation im Verlauf
+    fn operation_type(&self) -> OperationType {
+        OperationType::from_name(self.name())
+    }
+}
+
+/// Grundrechenart
+pub(crate) struct BuiltIn(OperationType);
+
+impl BuiltIn {
+    pub(crate) const ADD: BuiltIn = BuiltIn(OperationType::Add);
+    pub(crate) const SUBTRACT: BuiltIn = BuiltIn(OperationType::Subtract);
+    pub(crate) const MULTIPLY: BuiltIn = BuiltIn(OperationType::Multiply);
+    pub(crate) const DIVIDE: BuiltIn = BuiltIn(OperationType::Divide);
+}
+
+impl Operator for BuiltIn {
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+
+    fn symbol(&self) -> Option<&str> {
+        match self.0 {
+            OperationType::Add => Some("+"),
+            OperationType::Subtract => Some("-"),
+            OperationType::Multiply => Some("*"),
+            OperationType::Divide => Some("/"),
+            OperationType::Custom(_) => None,
+        }
+    }
+
+    fn validate(&self, operands: &[f64]) -> Result<(), ProjectError> {
+        if self.0 == OperationType::Divide && operands[1] == 0.0 {
+            return Err(ProjectError::division_by_zero());
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self, operands: &[f64]) -> Result<f64, ProjectError> {
+        let (a, b) = (operands[0], operands[1]);
+        Ok(match self.0 {
+            OperationType::Add => a + b,
+            OperationType::Subtract => a - b,
+            OperationType::Multiply => a * b,
+            _ => a / b,
+        })
+    }
+
+    fn operation_type(&self) -> OperationType {
+        self.0.clone()
+    }
+}
+
+/// Fakultät einer natürlichen Zahl bis 20, siehe [`Calculator::factorial`](crate::Calculator::factorial)
+pub(crate) struct Factorial;
+
+impl Factorial {
+    pub(crate) const NAME: &'static str = "factorial";
+}
+
+impl Operator for Factorial {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn validate(&self, operands: &[f64]) -> Result<(), ProjectError> {
+        let n = operands[0];
+        if n < 0.0 || n.fract() != 0.0 {
+            return Err(ProjectError::invalid_input(Message::new(
+                "operation.not_natural",
+                [n.into()],
+            )));
+        }
+        if n > 20.0 {
+            return Err(ProjectError::overflow("Factorial too large"));
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self, operands: &[f64]) -> Result<f64, ProjectError> {
+        // 20! passt in u64 und ist als f64 exakt darstellbar
+        Ok((1..=operands[0] as u64).product::<u64>() as f64)
+    }
+}
+
+type Evaluate = dyn Fn(&[f64]) -> Result<f64, ProjectError> + Send + Sync;
+type Validate = dyn Fn(&[f64]) -> Result<(), ProjectError> + Send + Sync;
+
+/// Operation aus Closures
+///
+/// # Beispiel
+///
+/// ```rust
+/// use rust_project_complete::modules::operations::FnOperator;
+/// use rust_project_complete::modules::error::ProjectError;
+/// use rust_project_complete::Calculator;
+/// use std::sync::Arc;
+///
+/// let power = FnOperator::new("power", 2, |x| Ok(x[0].powf(x[1])))
+///     .symbol("^")
+///     .validate(|x| match x[1] < 0.0 {
+///         true => Err(ProjectError::invalid_input("Negativer Exponent")),
+///         false => Ok(()),
+///     });
+///
+/// let mut calc = Calculator::new();
+/// calc.register_operation(Arc::new(power)).unwrap();
+/// assert_eq!(calc.evaluate("2 ^ 10").unwrap(), 1024.0);
+/// assert_eq!(calc.apply("power", &[3.0, 2.0]).unwrap(), 9.0);
+/// assert!(calc.apply("power", &[2.0, -1.0]).is_err());
+/// ```
+pub struct FnOperator {
+    name: String,
+    arity: usize,
+    symbol: Option<String>,
+    evaluate: Box<Evaluate>,
+    validate: Option<Box<Validate>>,
+}
+
+impl FnOperator {
+    /// Erstellt eine Operation mit Name, Anzahl der Operanden und Berechnung
+    pub fn new(
+        name: &str,
+        arity: usize,
+        evaluate: impl Fn(&[f64]) -> Result<f64, ProjectError> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            arity,
+            symbol: None,
+            evaluate: Box::new(evaluate),
+            validate: None,
+        }
+    }
+
+    /// Setzt das Infix-Symbol für Ausdrücke
+    pub fn symbol(mut self, symbol: &str) -> Self {
+        self.symbol = Some(symbol.to_string());
+        self
+    }
+
+    /// Setzt die Prüfung der Operanden
+    pub fn validate(
+        mut self,
+        validate: impl Fn(&[f64]) -> Result<(), ProjectError> + Send + Sync + 'static,
+    ) -> Self {
+        self.validate = Some(Box::new(validate));
+        self
+    }
+}
+
+impl Operator for FnOperator {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn arity(&self) -> usize {
+        self.arity
+    }
+
+    fn symbol(&self) -> Option<&str> {
+        self.symbol.as_deref()
+    }
+
+    fn validate(&self, operands: &[f64]) -> Result<(), ProjectError> {
+        self.validate
+            .as_ref()
+            .map_or(Ok(()), |validate| validate(operands))
+    }
+
+    fn evaluate(&self, operands: &[f64]) -> Result<f64, ProjectError> {
+        (self.evaluate)(operands)
+    }
+}
+
+impl fmt::Debug for FnOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FnOperator")
+            .field("name", &self.name)
+            .field("arity", &self.arity)
+            .field("symbol", &self.symbol)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Operation, die als Ausdruck über ihre Parameter definiert ist
+///
+/// Anders als [`FnOperator`] lässt sie sich speichern: der
+/// [`Calculator`](crate::Calculator) schreibt seine Definitionen mit in
+/// Verlaufs- und Sitzungsdateien, siehe
+/// [`Calculator::define_operation`](crate::Calculator::define_operation).
+///
+/// # Beispiel
+///
+/// ```rust
+/// use rust_project_complete::modules::operations::OperationDefinition;
+///
+/// let mean: OperationDefinition = "mean(a, b) = (a + b) / 2".parse().unwrap();
+/// assert_eq!(mean.name, "mean");
+/// assert_eq!(mean.parameters, vec!["a", "b"]);
+/// assert_eq!(mean.to_string(), "mean(a, b) = (a + b) / 2");
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperationDefinition {
+    pub name: String,
+    pub parameters: Vec<String>,
+    /// Rumpf aus Parametern, Zahlen und bereits bekannten Operationen
+    pub body: String,
+}
+
+impl FromStr for OperationDefinition {
+    type Err = ProjectError;
+
+    /// Liest `name(a, b, …) = ausdruck`
+    fn from_str(definition: &str) -> Result<Self, Self::Err> {
+        let malformed = || {
+            invalid(Message::new("definition.malformed", [definition.into()]))
+        };
+        let (head, body) = definition.split_once('=').ok_or_else(malformed)?;
+        let (name, parameters) = head
+            .trim()
+            .strip_suffix(')')
+            .and_then(|head| head.split_once('('))
+            .ok_or_else(malformed)?;
+        let parameters: Vec<String> = match parameters.trim() {
+            "" => Vec::new(),
+            list => list.split(',').map(|p| p.trim().to_string()).collect(),
+        };
+        Ok(Self {
+            name: name.trim().to_string(),
+            parameters,
+            body: body.trim().to_string(),
+        })
+    }
+}
+
+impl fmt::Display for OperationDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}({}) = {}", self.name, self.parameters.join(", "), self.body)
+    }
+}
+
+/// Ausführbare Form einer [`OperationDefinition`]
+///
+/// Der Rumpf sieht die Operationen, die beim Definieren registriert waren.
+pub(crate) struct ExpressionOperator {
+    definition: OperationDefinition,
+    body: Expression,
+    registry: OperationRegistry,
+}
+
+impl ExpressionOperator {
+    /// Prüft die Definition gegen die Operationen aus `registry`
+    pub(crate) fn new(
+        definition: &OperationDefinition,
+        registry: &OperationRegistry,
+    ) -> Result<Self, ProjectError> {
+        for (index, parameter) in definition.parameters.iter().enumerate() {
+            if !is_identifier(parameter) {
+                return Err(invalid(Message::new("definition.invalid_parameter", [parameter.into()])));
+            }
+            if definition.parameters[..index].contains(parameter) {
+                return Err(invalid(Message::new("definition.duplicate_parameter", [parameter.into()])));
+            }
+        }
+        let body = Expression::parse_with(&definition.body, registry)?;
+        let mut unknown = None;
+        body.visit_variables(&mut |name| {
+            if unknown.is_none() && !definition.parameters.iter().any(|p| p == name) {
+                unknown = Some(name.to_string());
+            }
+        });
+        if let Some(name) = unknown {
+            return Err(invalid(Message::new(
+                "definition.not_a_parameter",
+                [name.into(), (&definition.name).into()],
+            )));
+        }
+        Ok(Self {
+            definition: definition.clone(),
+            body,
+            registry: registry.clone(),
+        })
+    }
+}
+
+impl Operator for ExpressionOperator {
+    fn name(&self) -> &str {
+        &self.definition.name
+    }
+
+    fn arity(&self) -> usize {
+        self.definition.parameters.len()
+    }
+
+    fn evaluate(&self, operands: &[f64]) -> Result<f64, ProjectError> {
+        let parameters = &self.definition.parameters;
+        let variable = |name: &str| {
+            let index = parameters.iter().position(|p| p == name)?;
+            operands.get(index).copied()
+        };
+        self.body.evaluate_with(&variable, &self.registry)
+    }
+}
+
+/// Verzeichnis der Operationen eines Calculators, sortiert nach Namen
+///
+/// Enthält immer die Grundrechenarten `add`, `subtract`, `multiply` und `divide`.
+#[derive(Clone)]
+pub struct OperationRegistry {
+    operators: BTreeMap<String, Arc<dyn Operator>>,
+}
+
+/// Verzeichnis nur mit den Grundrechenarten
+pub(crate) static BUILT_INS: LazyLock<OperationRegistry> = LazyLock::new(OperationRegistry::new);
+
+impl OperationRegistry {
+    /// Erstellt ein Verzeichnis mit den Grundrechenarten
+    pub fn new() -> Self {
+        let operators = [
+            BuiltIn::ADD,
+            BuiltIn::SUBTRACT,
+            BuiltIn::MULTIPLY,
+            BuiltIn::DIVIDE,
+        ]
+        .into_iter()
+        .map(|operator| {
+            let operator: Arc<dyn Operator> = Arc::new(operator);
+            (operator.name().to_string(), operator)
+        })
+        .collect();
+        Self { operators }
+    }
+
+    /// Registriert eine Operation
+    ///
+    /// # Errors
+    ///
+    /// Gibt [`ProjectError::InvalidInput`] zurück, wenn Name oder Symbol bereits
+    /// vergeben oder ungültig sind. Namen bestehen aus Buchstaben, Ziffern und
+    /// `_`, Symbole aus Sonderzeichen ohne Klammern und Komma.
+    pub fn register(&mut self, operator: Arc<dyn Operator>) -> Result<(), ProjectError> {
+        let name = operator.name();
+        if !is_identifier(name) {
+            return Err(invalid(Message::new("registry.invalid_name", [name.into()])));
+        }
+        if self.get(name).is_some()
+            || name == Factorial::NAME
+            || !matches!(operator.operation_type(), OperationType::Custom(_))
+        {
+            return Err(invalid(Message::new("registry.already_registered", [name.into()])));
+        }
+
+        if let Some(symbol) = operator.symbol() {
+            let valid_symbol = !symbol.is_empty()
+                && symbol
+                    .chars()
+                    .all(|c| !(c.is_alphanumeric() || c.is_whitespace() || "_.,()".contains(c)));
+            if !valid_symbol {
+                return Err(invalid(Message::new("registry.invalid_symbol", [symbol.into()])));
+            }
+            if operator.arity() != 2 {
+                return Err(invalid(Message::new(
+                    "registry.symbol_arity",
+                    [name.into(), operator.arity().into()],
+                )));
+            }
+            if self.by_symbol(symbol).is_some() {
+                return Err(invalid(Message::new("registry.symbol_taken", [symbol.into()])));
+            }
+        }
+
+        self.operators.insert(name.to_string(), operator);
+        Ok(())
+    }
+
+    /// Sucht eine Operation nach Namen; Grundrechenarten auch als `"Add"` usw.
+    pub fn get(&self, name: &str) -> Option<&Arc<dyn Operator>> {
+        self.operators.get(OperationType::from_name(name).name())
+    }
+
+    /// Sucht eine Operation nach ihrem Infix-Symbol
+    pub fn by_symbol(&self, symbol: &str) -> Option<&Arc<dyn Operator>> {
+        self.iter()
+            .find(|operator| operator.symbol() == Some(symbol))
+    }
+
+    /// Alle Operationen, sortiert nach Namen
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<dyn Operator>> {
+        self.operators.values()
+    }
+}
+
+impl Default for OperationRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for OperationRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.operators.keys()).finish()
+    }
+}
+
+/// Namen und Parameter bestehen aus Buchstaben, Ziffern und `_`
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+fn invalid(message: Message) -> ProjectError {
+    ProjectError::invalid_input(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn operator(name: &str) -> FnOperator {
+        FnOperator::new(name, 2, |x| Ok(x[0] % x[1]))
+    }
+
+    #[test]
+    fn test_built_ins() {
+        let registry = OperationRegistry::new();
+        let names: Vec<_> = registry.iter().map(|op| op.name()).collect();
+        assert_eq!(names, vec!["add", "divide", "multiply", "subtract"]);
+
+        let divide = registry.get("Divide").unwrap();
+        assert_eq!(divide.operation_type(), OperationType::Divide);
+        assert_eq!(
+            divide.validate(&[1.0, 0.0]),
+            Err(ProjectError::division_by_zero())
+        );
+        assert_eq!(registry.by_symbol("*").unwrap().name(), "multiply");
+    }
+
+    #[test]
+    fn test_register() {
+        let mut registry = OperationRegistry::new();
+        registry
+            .register(Arc::new(operator("modulo").symbol("%")))
+            .unwrap();
+
+        let modulo = registry.get("modulo").unwrap();
+        assert_eq!(
+            modulo.operation_type(),
+            OperationType::Custom("modulo".to_string())
+        );
+        assert_eq!(modulo.evaluate(&[7.0, 4.0]).unwrap(), 3.0);
+        assert_eq!(registry.by_symbol("%").unwrap().name(), "modulo");
+    }
+
+    #[test]
+    fn test_register_rejects_conflicts() {
+        let mut registry = OperationRegistry::new();
+        registry
+            .register(Arc::new(operator("modulo").symbol("%")))
+            .unwrap();
+
+        for rejected in [
+            operator("modulo"),
+            operator("add"),
+            operator("Add"),
+            operator("factorial"),
+            operator("2x"),
+            operator("mod ulo"),
+            operator("rest").symbol("%"),
+            operator("rest").symbol("+"),
+            operator("rest").symbol("(%"),
+            FnOperator::new("negate", 1, |x| Ok(-x[0])).symbol("~"),
+        ] {
+            let name = rejected.name.clone();
+            assert!(
+                matches!(
+                    registry.register(Arc::new(rejected)),
+                    Err(ProjectError::InvalidInput { .. })
+                ),
+                "{} wurde angenommen",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_definition() {
+        let hypot: OperationDefinition = " hypot( x , y ) = x * x + y * y".parse().unwrap();
+        assert_eq!(hypot.name, "hypot");
+        assert_eq!(hypot.parameters, vec!["x", "y"]);
+        assert_eq!(hypot.body, "x * x + y * y");
+
+        let zero: OperationDefinition = "zero() = 0".parse().unwrap();
+        assert!(zero.parameters.is_empty());
+
+        for malformed in ["hypot = 1", "hypot(x, y) 1", "hypot x, y) = 1"] {
+            assert!(malformed.parse::<OperationDefinition>().is_err(), "{}", malformed);
+        }
+    }
+
+    #[test]
+    fn test_expression_operator() {
+        let mut registry = OperationRegistry::new();
+        registry
+            .register(Arc::new(operator("modulo").symbol("%")))
+            .unwrap();
+        let definition = "odd(n) = n % 2".parse().unwrap();
+        let odd = ExpressionOperator::new(&definition, &registry).unwrap();
+
+        assert_eq!(odd.arity(), 1);
+        assert_eq!(odd.evaluate(&[7.0]).unwrap(), 1.0);
+        assert_eq!(odd.operation_type(), OperationType::Custom("odd".to_string()));
+
+        let ratio = "ratio(a, b) = a / b".parse().unwrap();
+        let ratio = ExpressionOperator::new(&ratio, &registry).unwrap();
+        assert_eq!(ratio.evaluate(&[1.0, 0.0]), Err(ProjectError::division_by_zero()));
+
+        for rejected in [
+            "f(a, a) = a",
+            "f(1x) = 1",
+            "f(a) = a + b",
+            "f(a) = median(a)",
+            "f(a) = (a",
+        ] {
+            let definition = rejected.parse().unwrap();
+            assert!(ExpressionOperator::new(&definition, &registry).is_err(), "{}", rejected);
+        }
+    }
+}
@@ -0,0 +1,389 @@
+//! Ausgabeformate der Kommandozeile
+
+use crate::modules::error::ProjectError;
+use crate::modules::i18n::{Locale, Message};
+use crate::modules::utils::csv_escape;
+use serde_json::{json, Value};
+use std::fmt;
+use std::str::FromStr;
+
+/// Format der Ausgabe auf stdout und der Fehler auf stderr
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Lesbarer Text
+    #[default]
+    Text,
+    /// Ein JSON-Dokument
+    Json,
+    /// CSV mit Kopfzeile
+    Csv,
+    /// Ausgerichtete Tabelle
+    Table,
+}
+
+impl FromStr for OutputFormat {
+    type Err = ProjectError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            "table" => Ok(OutputFormat::Table),
+            other => Err(ProjectError::invalid_input(Message::new(
+                "cli.unknown_output_format",
+                [other.into()],
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputFormat::Text => f.write_str("text"),
+            OutputFormat::Json => f.write_str("json"),
+            OutputFormat::Csv => f.write_str("csv"),
+            OutputFormat::Table => f.write_str("table"),
+        }
+    }
+}
+
+/// Ergebnis eines Befehls in allen Ausgabeformaten
+///
+/// Der Text wird in der gewählten Sprache ausgegeben (siehe
+/// [`Report::translate`]), der JSON-Wert als Dokument. Für CSV
+/// und Tabellen werden die Zeilen aus dem JSON-Wert gebildet: ein Array ergibt
+/// je Element eine Zeile, ein Objekt genau eine. Die Zellen sind die Felder
+/// der gewählten Spalten.
+///
+/// # Beispiel
+///
+/// ```rust
+/// use rust_project_complete::modules::output::{OutputFormat, Report};
+/// use serde_json::json;
+///
+/// let report = Report::new(
+///     "2 + 3 = 5",
+///     json!({"operation": "add", "operands": [2.0, 3.0], "result": 5.0}),
+/// )
+/// .columns(&["operation", "operands", "result"]);
+///
+/// assert_eq!(report.render(OutputFormat::Text), "2 + 3 = 5\n");
+/// assert_eq!(
+///     report.render(OutputFormat::Csv),
+///     "operation,operands,result\nadd,2.0 3.0,5.0\n"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    text: Message,
+    locale: Locale,
+    value: Value,
+    columns: Vec<String>,
+    rows: Option<Vec<Value>>,
+}
+
+impl Report {
+    /// Erstellt ein Ergebnis aus Text und JSON-Wert
+    pub fn new(text: impl Into<Message>, value: Value) -> Self {
+        Self {
+            text: text.into(),
+            locale: Locale::default(),
+            value,
+            columns: Vec::new(),
+            rows: None,
+        }
+    }
+
+    /// Meldung ohne weitere Daten, in JSON als `{"message": ...}` auf Deutsch
+    pub fn message(text: impl Into<Message>) -> Self {
+        let text = text.into();
+        let value = json!({ "message": text.to_string() });
+        Self::new(text, value).columns(&["message"])
+    }
+
+    /// Legt die Spalten für CSV und Tabellen fest
+    pub fn columns(mut self, columns: &[&str]) -> Self {
+        self.columns = columns.iter().map(|column| column.to_string()).collect();
+        self
+    }
+
+    /// Bildet die Zeilen aus `rows` statt aus dem JSON-Wert
+    pub fn rows(mut self, rows: Vec<Value>) -> Self {
+        self.rows = Some(rows);
+        self
+    }
+
+    /// Wählt die Sprache des Texts; JSON, CSV und Tabellen bleiben sprachunabhängig
+    pub fn translate(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// JSON-Wert des Ergebnisses
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    /// Gibt das Ergebnis im gewählten Format zurück, abgeschlossen mit einem Zeilenumbruch
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Text => format!("{}\n", self.text.render(self.locale)),
+            OutputFormat::Json => format!("{}\n", self.value),
+            OutputFormat::Csv => {
+                let mut out = String::new();
+                for row in std::iter::once(self.columns.clone()).chain(self.cells()) {
+                    let row: Vec<String> = row.iter().map(|cell| csv_escape(cell)).collect();
+                    out.push_str(&row.join(","));
+                    out.push('\n');
+                }
+                out
+            }
+            OutputFormat::Table => table(&self.columns, &self.cells()),
+        }
+    }
+
+    fn cells(&self) -> Vec<Vec<String>> {
+        let rows = match (&self.rows, &self.value) {
+            (Some(rows), _) => rows.as_slice(),
+            (None, Value::Array(rows)) => rows.as_slice(),
+            (None, value) => std::slice::from_ref(value),
+        };
+        rows.iter()
+            .map(|row| {
+                self.columns
+                    .iter()
+                    .map(|column| cell(row.get(column).unwrap_or(&Value::Null)))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Gibt einen Fehler im gewählten Format für stderr zurück
+///
+/// In JSON als `{"error": {"code": ..., "message": ...}}` mit dem Code aus
+/// [`ProjectError::code`] und den übrigen Feldern des serialisierten Fehlers,
+/// z. B. `kind` und `path` bei Ein-/Ausgabefehlern; in CSV mit den Spalten
+/// `code` und `message`. Nur die Meldung wird übersetzt. Parsing-Fehler zeigen
+/// in Text und Tabellen zusätzlich den Auszug mit der markierten Stelle, mit
+/// `color` farbig; in JSON enthält `diagnostic` die
+/// [`Diagnostic`](crate::modules::diagnostic::Diagnostic).
+pub fn render_error(
+    format: OutputFormat,
+    locale: Locale,
+    error: &ProjectError,
+    color: bool,
+) -> String {
+    let message = error.localized(locale);
+    match format {
+        OutputFormat::Text | OutputFormat::Table => {
+            let headline = Message::new("error.headline", [error.message().into()]).render(locale);
+            let mut out = match headline.split_once(": ") {
+                Some((label, rest)) if color => format!("\x1b[1;31m{}:\x1b[0m {}\n", label, rest),
+                _ => format!("{}\n", headline),
+            };
+            if let Some(excerpt) = error.diagnostic().and_then(|d| d.excerpt(color)) {
+                out.push_str(&excerpt);
+            }
+            out
+        }
+        OutputFormat::Json => {
+            let mut value =
+                serde_json::to_value(error).unwrap_or_else(|_| json!({ "code": error.code() }));
+            value["message"] = Value::String(message);
+            format!("{}\n", json!({ "error": value }))
+        }
+        OutputFormat::Csv => format!("code,message\n{},{}\n", error.code(), csv_escape(&message)),
+    }
+}
+
+/// Zelle für CSV und Tabellen; Arrays werden mit Leerzeichen verbunden
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items.iter().map(cell).collect::<Vec<_>>().join(" "),
+        other => other.to_string(),
+    }
+}
+
+fn table(columns: &[String], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = columns.iter().map(|c| c.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let line = |cells: &[String]| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect();
+        format!("{}\n", padded.join("  ").trim_end())
+    };
+    let separator: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+
+    let mut out = line(columns);
+    out.push_str(&line(&separator));
+    for row in rows {
+        out.push_str(&line(row));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history() -> Report {
+        Report::new(
+            "1: add(2, 3) = 5\n2: divide(1, 4) = 0.25",
+            json!([
+                {"id": 1, "operation": "add", "operands": [2.0, 3.0], "result": 5.0},
+                {"id": 2, "operation": "divide", "operands": [1.0, 4.0], "result": 0.25},
+            ]),
+        )
+        .columns(&["id", "operation", "operands", "result"])
+    }
+
+    #[test]
+    fn test_render_formats() {
+        let report = history();
+        assert_eq!(
+            report.render(OutputFormat::Text),
+            "1: add(2, 3) = 5\n2: divide(1, 4) = 0.25\n"
+        );
+
+        let json: Value = serde_json::from_str(&report.render(OutputFormat::Json)).unwrap();
+        assert_eq!(json[1]["result"], 0.25);
+
+        assert_eq!(
+            report.render(OutputFormat::Csv),
+            "id,operation,operands,result\n1,add,2.0 3.0,5.0\n2,divide,1.0 4.0,0.25\n"
+        );
+        assert_eq!(
+            report.render(OutputFormat::Table),
+            "id  operation  operands  result\n\
+             --  ---------  --------  ------\n\
+             1   add        2.0 3.0   5.0\n\
+             2   divide     1.0 4.0   0.25\n"
+        );
+    }
+
+    #[test]
+    fn test_explicit_rows_and_message() {
+        let report = Report::new("gesamt 2", json!({"total": 2, "by": {}}))
+            .columns(&["name", "count"])
+            .rows(vec![
+                json!({"name": "a, b", "count": 2}),
+                json!({"name": "c"}),
+            ]);
+        assert_eq!(
+            report.render(OutputFormat::Csv),
+            "name,count\n\"a, b\",2\nc,\n"
+        );
+
+        let message = Report::message(Message::new("session.created", ["a".into()]));
+        assert_eq!(
+            message.render(OutputFormat::Json),
+            "{\"message\":\"Sitzung 'a' erstellt\"}\n"
+        );
+        assert_eq!(
+            message.render(OutputFormat::Table),
+            "message\n--------------------\nSitzung 'a' erstellt\n"
+        );
+        assert_eq!(
+            message.translate(Locale::En).render(OutputFormat::Text),
+            "Session 'a' created\n"
+        );
+    }
+
+    #[test]
+    fn test_render_error() {
+        let error = ProjectError::division_by_zero();
+        assert_eq!(
+            render_error(OutputFormat::Text, Locale::De, &error, false),
+            "Fehler: Division durch Null ist nicht erlaubt\n"
+        );
+        assert_eq!(
+            render_error(OutputFormat::Json, Locale::De, &error, false),
+            "{\"error\":{\"code\":\"division_by_zero\",\"message\":\"Division durch Null ist nicht erlaubt\"}}\n"
+        );
+        assert_eq!(
+            render_error(
+                OutputFormat::Csv,
+                Locale::De,
+                &ProjectError::invalid_input("a, b"),
+                false
+            ),
+            "code,message\ninvalid_input,\"Ungültige Eingabe: a, b\"\n"
+        );
+        assert_eq!(
+            render_error(OutputFormat::Text, Locale::En, &error, false),
+            "Error: Division by zero is not allowed\n"
+        );
+
+        let io_error = ProjectError::io(
+            std::io::Error::from(std::io::ErrorKind::PermissionDenied),
+            "verlauf.json",
+        );
+        let json: Value = serde_json::from_str(&render_error(
+            OutputFormat::Json,
+            Locale::En,
+            &io_error,
+            false,
+        ))
+        .unwrap();
+        assert_eq!(json["error"]["code"], "io_error");
+        assert_eq!(json["error"]["kind"], "permission_denied");
+        assert_eq!(json["error"]["path"], "verlauf.json");
+        assert_eq!(
+            json["error"]["message"],
+            "I/O error: permission denied (file verlauf.json)"
+        );
+        assert_eq!(
+            render_error(OutputFormat::Json, Locale::En, &error, false),
+            "{\"error\":{\"code\":\"division_by_zero\",\"message\":\"Division by zero is not allowed\"}}\n"
+        );
+    }
+
+    #[test]
+    fn test_render_parse_error_with_excerpt() {
+        let error = crate::Calculator::new()
+            .evaluate("2 * ad(1, 2)")
+            .unwrap_err();
+        assert_eq!(
+            render_error(OutputFormat::Text, Locale::En, &error, false),
+            "Error: Parse error: Unknown operation 'ad' (line 1, column 5); did you mean 'add'?\n\
+             \x20 |\n\
+             1 | 2 * ad(1, 2)\n\
+             \x20 |     ^^\n"
+        );
+        assert!(render_error(OutputFormat::Text, Locale::De, &error, true)
+            .starts_with("\x1b[1;31mFehler:\x1b[0m Parsing-Fehler: "));
+
+        let json: Value =
+            serde_json::from_str(&render_error(OutputFormat::Json, Locale::De, &error, false))
+                .unwrap();
+        assert_eq!(json["error"]["code"], "parse_error");
+        assert_eq!(json["error"]["diagnostic"]["span"]["column"], 5);
+        assert_eq!(
+            json["error"]["diagnostic"]["suggestion"],
+            "meinten Sie 'add'?"
+        );
+    }
+
+    #[test]
+    fn test_output_format_from_str() {
+        assert_eq!(
+            "table".parse::<OutputFormat>().unwrap(),
+            OutputFormat::Table
+        );
+        assert_eq!(OutputFormat::Csv.to_string(), "csv");
+        assert!("xml".parse::<OutputFormat>().is_err());
+    }
+}
@@ -0,0 +1,556 @@
+//! Python-Erweiterungsmodul `rust_project_complete`
+//!
+//! Wird mit dem Feature `python` gebaut, z. B. über `maturin develop`.
+//! Fehler erscheinen in Python als Unterklassen von `CalculatorError`.
+
+use crate::modules::core::{MathUtils, Statistics};
+use crate::modules::error::ProjectError;
+use crate::modules::i18n::Message;
+use crate::modules::history::HistoryQuery;
+use crate::modules::operations::{Factorial, OperationRegistry};
+use crate::modules::shared::SharedCalculator;
+use crate::modules::utils;
+use crate::{Calculator, Operation, OperationType};
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyInt};
+
+create_exception!(
+    rust_project_complete,
+    CalculatorError,
+    PyException,
+    "Basisklasse aller Fehler des Calculators"
+);
+create_exception!(rust_project_complete, DivisionByZeroError, CalculatorError);
+create_exception!(
+    rust_project_complete,
+    CalculationOverflowError,
+    CalculatorError
+);
+create_exception!(rust_project_complete, InvalidInputError, CalculatorError);
+create_exception!(rust_project_complete, StorageError, CalculatorError);
+create_exception!(rust_project_complete, ParseError, CalculatorError);
+create_exception!(rust_project_complete, CancelledError, CalculatorError);
+create_exception!(rust_project_complete, AuditViolationError, CalculatorError);
+
+impl From<ProjectError> for PyErr {
+    fn from(error: ProjectError) -> Self {
+        let message = error.to_string();
+        match error {
+            ProjectError::DivisionByZero { .. } => DivisionByZeroError::new_err(message),
+            ProjectError::OverflowError { .. } => CalculationOverflowError::new_err(message),
+            ProjectError::InvalidInput { .. } => InvalidInputError::new_err(message),
+            ProjectError::IoError { .. } => StorageError::new_err(message),
+            ProjectError::ParseError { .. } => ParseError::new_err(message),
+            ProjectError::Cancelled => CancelledError::new_err(message),
+            ProjectError::AuditViolation { .. } => AuditViolationError::new_err(message),
+        }
+    }
+}
+
+/// Name der Operationsart wie in JSON, z. B. `"Divide"` oder `"power"`
+fn operation_type_name(operation_type: &OperationType) -> &str {
+    match operation_type {
+        OperationType::Add => "Add",
+        OperationType::Subtract => "Subtract",
+        OperationType::Multiply => "Multiply",
+        OperationType::Divide => "Divide",
+        OperationType::Custom(name) => name,
+    }
+}
+
+/// Sucht eine Operationsart in `operations`; `factorial` ist immer bekannt
+fn parse_operation_type(name: &str, operations: &OperationRegistry) -> PyResult<OperationType> {
+    match name.trim() {
+        "" => Err(PyValueError::new_err("Leere Operationsart")),
+        Factorial::NAME => Ok(OperationType::from_name(Factorial::NAME)),
+        name => operations
+            .get(name)
+            .map(|operator| operator.operation_type())
+            .ok_or_else(|| PyValueError::new_err(format!("Unbekannte Operationsart '{}'", name))),
+    }
+}
+
+/// Eintrag im Verlauf
+#[pyclass(
+    name = "Operation",
+    module = "rust_project_complete",
+    frozen,
+    get_all,
+    skip_from_py_object
+)]
+#[derive(Debug, Clone)]
+pub struct PyOperation {
+    id: u64,
+    operation_type: String,
+    operands: Vec<f64>,
+    result: f64,
+    timestamp: u64,
+}
+
+impl From<&Operation> for PyOperation {
+    fn from(operation: &Operation) -> Self {
+        Self {
+            id: operation.id,
+            operation_type: operation_type_name(&operation.operation_type).to_string(),
+            operands: operation.operands.clone(),
+            result: operation.result,
+            timestamp: operation.timestamp,
+        }
+    }
+}
+
+#[pymethods]
+impl PyOperation {
+    /// Gibt den Eintrag als `dict` zurück
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("id", self.id)?;
+        dict.set_item("operation_type", &self.operation_type)?;
+        dict.set_item("operands", &self.operands)?;
+        dict.set_item("result", self.result)?;
+        dict.set_item("timestamp", self.timestamp)?;
+        Ok(dict)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Operation(id={}, operation_type='{}', operands={:?}, result={})",
+            self.id, self.operation_type, self.operands, self.result
+        )
+    }
+}
+
+/// Calculator mit Verlauf; kann aus mehreren Python-Threads genutzt werden
+#[pyclass(
+    name = "Calculator",
+    module = "rust_project_complete",
+    frozen,
+    skip_from_py_object
+)]
+#[derive(Debug, Clone, Default)]
+pub struct PyCalculator {
+    inner: SharedCalculator,
+}
+
+#[pymethods]
+impl PyCalculator {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lädt einen mit `save` gespeicherten Calculator
+    #[staticmethod]
+    fn load(path: &str) -> PyResult<Self> {
+        Ok(Self {
+            inner: Calculator::load_from_file(path)?.into(),
+        })
+    }
+
+    /// Speichert Verlauf und Variablen als JSON
+    fn save(&self, path: &str) -> PyResult<()> {
+        Ok(self.inner.read(|calc| calc.save_to_file(path))?)
+    }
+
+    fn add(&self, a: f64, b: f64) -> PyResult<f64> {
+        Ok(self.inner.add(a, b)?)
+    }
+
+    fn subtract(&self, a: f64, b: f64) -> PyResult<f64> {
+        Ok(self.inner.subtract(a, b)?)
+    }
+
+    fn multiply(&self, a: f64, b: f64) -> PyResult<f64> {
+        Ok(self.inner.multiply(a, b)?)
+    }
+
+    fn divide(&self, a: f64, b: f64) -> PyResult<f64> {
+        Ok(self.inner.divide(a, b)?)
+    }
+
+    fn factorial(&self, n: u64) -> PyResult<u64> {
+        Ok(self.inner.factorial(n)?)
+    }
+
+    /// Wertet einen arithmetischen Ausdruck aus, z. B. `"2 * (miete + 50)"`
+    fn evaluate(&self, expression: &str) -> PyResult<f64> {
+        Ok(self.inner.evaluate(expression)?)
+    }
+
+    /// Führt eine Operation nach Namen aus, z. B. `apply("mean", [1, 4])`
+    fn apply(&self, name: &str, operands: Vec<f64>) -> PyResult<f64> {
+        Ok(self.inner.apply(name, &operands)?)
+    }
+
+    /// Definiert eine Operation, z. B. `define_operation("mean(a, b) = (a + b) / 2")`
+    ///
+    /// Die Definition wird mit `save` gespeichert.
+    fn define_operation(&self, definition: &str) -> PyResult<()> {
+        Ok(self.inner.define_operation(definition.parse()?)?)
+    }
+
+    fn set_variable(&self, name: &str, value: f64) -> Option<f64> {
+        self.inner.write(|calc| calc.set_variable(name, value))
+    }
+
+    fn get_variable(&self, name: &str) -> Option<f64> {
+        self.inner.read(|calc| calc.get_variable(name))
+    }
+
+    /// Gibt den Verlauf als Liste von `Operation` zurück
+    fn get_history(&self) -> PyResult<Vec<PyOperation>> {
+        let history = self.inner.query_history(&HistoryQuery::new())?;
+        Ok(history.iter().map(PyOperation::from).collect())
+    }
+
+    /// Gibt den Verlauf als Liste von `dict` zurück, z. B. für `pandas.DataFrame`
+    fn history<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        self.get_history()?
+            .iter()
+            .map(|operation| operation.to_dict(py))
+            .collect()
+    }
+
+    fn statistics(&self) -> PyStatistics {
+        PyStatistics {
+            inner: self.inner.statistics(),
+            operations: self.inner.read(|calc| calc.operations().clone()),
+        }
+    }
+
+    fn clear_history(&self) -> PyResult<()> {
+        Ok(self.inner.clear_history()?)
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.history_count()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Calculator(history_count={})", self.inner.history_count())
+    }
+}
+
+/// Kennzahlen über die Ergebnisse eines Verlaufs
+///
+/// Operationsarten werden wie beim Calculator nach Namen angegeben; unbekannte
+/// Namen ergeben `ValueError`.
+#[pyclass(
+    name = "Statistics",
+    module = "rust_project_complete",
+    skip_from_py_object
+)]
+#[derive(Debug, Clone, Default)]
+pub struct PyStatistics {
+    inner: Statistics,
+    /// Operationen des Calculators, von dem die Statistiken stammen
+    operations: OperationRegistry,
+}
+
+impl PyStatistics {
+    /// Bekannt sind registrierte und bereits erfasste Operationsarten
+    fn operation_type(&self, name: &str) -> PyResult<OperationType> {
+        parse_operation_type(name, &self.operations).or_else(|error| {
+            let operation_type = OperationType::from_name(name.trim());
+            match self.inner.by_operation.contains_key(&operation_type) {
+                true => Ok(operation_type),
+                false => Err(error),
+            }
+        })
+    }
+}
+
+#[pymethods]
+impl PyStatistics {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Berechnet die Statistiken für eine Liste von `Operation`
+    #[staticmethod]
+    fn from_history(history: Vec<PyRef<'_, PyOperation>>) -> Self {
+        let mut statistics = Self::default();
+        for operation in history {
+            // Einträge stammen aus einem Verlauf, ihre Art ist daher gültig
+            let operation_type = OperationType::from_name(&operation.operation_type);
+            statistics.inner.add_operation(operation_type, operation.result);
+        }
+        statistics
+    }
+
+    /// Nimmt ein Ergebnis auf, z. B. `add_operation("Add", 5.0)`
+    fn add_operation(&mut self, operation_type: &str, result: f64) -> PyResult<()> {
+        let operation_type = self.operation_type(operation_type)?;
+        self.inner.add_operation(operation_type, result);
+        Ok(())
+    }
+
+    fn operation_count(&self, operation_type: &str) -> PyResult<u64> {
+        Ok(self.inner.get_operation_count(&self.operation_type(operation_type)?))
+    }
+
+    #[getter]
+    fn total_operations(&self) -> u64 {
+        self.inner.total_operations
+    }
+
+    #[getter]
+    fn average_result(&self) -> f64 {
+        self.inner.average_result
+    }
+
+    #[getter]
+    fn min_result(&self) -> f64 {
+        self.inner.min_result
+    }
+
+    #[getter]
+    fn max_result(&self) -> f64 {
+        self.inner.max_result
+    }
+
+    /// Gibt die Statistiken als `dict` mit Einträgen je Operationsart zurück
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let by_operation = PyDict::new(py);
+        for (operation_type, stats) in self.inner.operations() {
+            let entry = PyDict::new(py);
+            entry.set_item("count", stats.count)?;
+            entry.set_item("average_result", stats.average_result)?;
+            entry.set_item("min_result", stats.min_result)?;
+            entry.set_item("max_result", stats.max_result)?;
+            by_operation.set_item(operation_type_name(operation_type), entry)?;
+        }
+
+        let dict = PyDict::new(py);
+        dict.set_item("total_operations", self.inner.total_operations)?;
+        dict.set_item("average_result", self.inner.average_result)?;
+        dict.set_item("min_result", self.inner.min_result)?;
+        dict.set_item("max_result", self.inner.max_result)?;
+        dict.set_item("by_operation", by_operation)?;
+        Ok(dict)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Statistics(total_operations={}, average_result={})",
+            self.inner.total_operations, self.inner.average_result
+        )
+    }
+}
+
+/// Zahlentheoretische Hilfsfunktionen
+#[pyclass(name = "MathUtils", module = "rust_project_complete", frozen)]
+pub struct PyMathUtils;
+
+#[pymethods]
+impl PyMathUtils {
+    #[staticmethod]
+    fn gcd(a: u64, b: u64) -> u64 {
+        MathUtils::gcd(a, b)
+    }
+
+    #[staticmethod]
+    fn lcm(a: u64, b: u64) -> PyResult<u64> {
+        Ok(MathUtils::lcm(a, b)?)
+    }
+
+    /// Gibt während der Prüfung den GIL frei
+    #[staticmethod]
+    fn is_prime(py: Python<'_>, n: u64) -> bool {
+        py.detach(|| MathUtils::is_prime(n))
+    }
+
+    #[staticmethod]
+    fn fibonacci(n: u64) -> PyResult<u64> {
+        Ok(MathUtils::fibonacci(n)?)
+    }
+
+    /// Fakultät als Python-`int` beliebiger Größe
+    #[staticmethod]
+    fn big_factorial(py: Python<'_>, n: u64) -> PyResult<Bound<'_, PyAny>> {
+        let digits = py.detach(|| MathUtils::big_factorial(n));
+        py.get_type::<PyInt>().call1((digits,))
+    }
+}
+
+/// Durchschnitt einer Liste von Zahlen
+#[pyfunction]
+fn calculate_average(numbers: Vec<f64>) -> PyResult<f64> {
+    Ok(utils::calculate_average(&numbers)?)
+}
+
+/// Kleinste Zahl einer Liste
+#[pyfunction]
+fn find_minimum(numbers: Vec<f64>) -> PyResult<f64> {
+    Ok(utils::find_minimum(&numbers)?)
+}
+
+/// Größte Zahl einer Liste
+#[pyfunction]
+fn find_maximum(numbers: Vec<f64>) -> PyResult<f64> {
+    Ok(utils::find_maximum(&numbers)?)
+}
+
+/// Standardabweichung einer Liste von Zahlen
+#[pyfunction]
+fn calculate_standard_deviation(numbers: Vec<f64>) -> PyResult<f64> {
+    Ok(utils::calculate_standard_deviation(&numbers)?)
+}
+
+/// Gibt die Zahlen aufsteigend sortiert als neue Liste zurück
+#[pyfunction]
+fn sort_numbers(mut numbers: Vec<f64>) -> PyResult<Vec<f64>> {
+    // NaN lässt sich nicht einordnen
+    if numbers.iter().any(|n| n.is_nan()) {
+        return Err(ProjectError::invalid_input(Message::new("math.list_contains_nan", [])).into());
+    }
+    utils::sort_numbers(&mut numbers);
+    Ok(numbers)
+}
+
+#[pymodule]
+fn rust_project_complete(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add_class::<PyCalculator>()?;
+    m.add_class::<PyOperation>()?;
+    m.add_class::<PyStatistics>()?;
+    m.add_class::<PyMathUtils>()?;
+
+    m.add_function(wrap_pyfunction!(calculate_average, m)?)?;
+    m.add_function(wrap_pyfunction!(find_minimum, m)?)?;
+    m.add_function(wrap_pyfunction!(find_maximum, m)?)?;
+    m.add_function(wrap_pyfunction!(calculate_standard_deviation, m)?)?;
+    m.add_function(wrap_pyfunction!(sort_numbers, m)?)?;
+
+    m.add("CalculatorError", py.get_type::<CalculatorError>())?;
+    m.add("DivisionByZeroError", py.get_type::<DivisionByZeroError>())?;
+    m.add(
+        "CalculationOverflowError",
+        py.get_type::<CalculationOverflowError>(),
+    )?;
+    m.add("InvalidInputError", py.get_type::<InvalidInputError>())?;
+    m.add("StorageError", py.get_type::<StorageError>())?;
+    m.add("ParseError", py.get_type::<ParseError>())?;
+    m.add("CancelledError", py.get_type::<CancelledError>())?;
+    m.add("AuditViolationError", py.get_type::<AuditViolationError>())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    /// Führt Python-Code mit dem Modul als `lib` aus
+    fn run(code: &CStr) {
+        Python::initialize();
+        Python::attach(|py| {
+            let module = PyModule::new(py, "rust_project_complete").unwrap();
+            rust_project_complete(&module).unwrap();
+            let globals = PyDict::new(py);
+            globals.set_item("lib", module).unwrap();
+            if let Err(error) = py.run(code, Some(&globals), None) {
+                error.display(py);
+                panic!("Python-Code fehlgeschlagen: {}", error);
+            }
+        });
+    }
+
+    #[test]
+    fn test_calculator() {
+        run(c"
+calc = lib.Calculator()
+assert calc.add(2, 3) == 5.0
+calc.set_variable('miete', 900)
+assert calc.evaluate('miete * 2') == 1800.0
+assert len(calc) == 2
+
+history = calc.history()
+assert history[0] == {'id': 1, 'operation_type': 'Add', 'operands': [2.0, 3.0],
+                      'result': 5.0, 'timestamp': history[0]['timestamp']}
+assert calc.get_history()[1].operation_type == 'Multiply'
+
+stats = calc.statistics()
+assert stats.total_operations == 2
+assert stats.operation_count('Add') == 1
+assert stats.to_dict()['by_operation']['Multiply']['count'] == 1
+assert lib.Statistics.from_history(calc.get_history()).max_result == 1800.0
+");
+    }
+
+    #[test]
+    fn test_defined_operations_and_unknown_names() {
+        run(c"
+calc = lib.Calculator()
+calc.define_operation('mean(a, b) = (a + b) / 2')
+assert calc.apply('mean', [1, 4]) == 2.5
+assert calc.evaluate('mean(2, 4) * 2') == 6.0
+
+stats = calc.statistics()
+assert stats.operation_count('mean') == 2
+assert stats.operation_count('factorial') == 0
+assert lib.Statistics.from_history(calc.get_history()).operation_count('mean') == 2
+
+for call in [
+    lambda: stats.operation_count('Mean'),
+    lambda: lib.Statistics().add_operation('Addition', 1.0),
+    lambda: lib.Statistics().operation_count('mean'),
+]:
+    try:
+        call()
+        raise AssertionError('keine Ausnahme')
+    except ValueError:
+        pass
+
+try:
+    calc.apply('median', [1, 2])
+    raise AssertionError('keine Ausnahme')
+except lib.InvalidInputError:
+    pass
+");
+    }
+
+    #[test]
+    fn test_errors_are_exceptions() {
+        run(c"
+calc = lib.Calculator()
+try:
+    calc.divide(1, 0)
+    raise AssertionError('keine Ausnahme')
+except lib.DivisionByZeroError as error:
+    assert isinstance(error, lib.CalculatorError)
+    assert 'Division durch Null' in str(error)
+
+for call, error in [
+    (lambda: calc.evaluate('2 +'), lib.ParseError),
+    (lambda: lib.calculate_average([]), lib.InvalidInputError),
+    (lambda: lib.MathUtils.fibonacci(100), lib.CalculationOverflowError),
+    (lambda: lib.sort_numbers([1.0, float('nan')]), lib.InvalidInputError),
+]:
+    try:
+        call()
+        raise AssertionError('keine Ausnahme')
+    except error:
+        pass
+");
+    }
+
+    #[test]
+    fn test_math_utils_and_helpers() {
+        run(c"
+import math
+assert lib.MathUtils.gcd(12, 18) == 6
+assert lib.MathUtils.lcm(4, 6) == 12
+assert lib.MathUtils.is_prime(97)
+assert lib.MathUtils.big_factorial(30) == math.factorial(30)
+assert lib.calculate_average([1, 2, 3]) == 2.0
+assert lib.find_minimum([3, 1, 2]) == 1.0
+assert lib.find_maximum([3, 1, 2]) == 3.0
+assert lib.sort_numbers([3, 1, 2]) == [1.0, 2.0, 3.0]
+assert abs(lib.calculate_standard_deviation([2, 4, 4, 4, 5, 5, 7, 9]) - 2.0) < 1e-12
+");
+    }
+}
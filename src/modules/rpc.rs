@@ -0,0 +1,846 @@
+//! JSON-RPC 2.0 über zeilenweise getrennte Nachrichten (z. B. stdin/stdout)
+
+use crate::modules::core::MathUtils;
+use crate::modules::diagnostic::Diagnostic;
+use crate::modules::error::ProjectError;
+use crate::modules::history::HistoryQuery;
+use crate::modules::i18n::{Locale, Message};
+use crate::modules::logging;
+use crate::modules::numeric::lossless::{self, Lossless};
+use crate::modules::observer::{CalculatorObserver, HistoryEvent, ObserverHandle};
+use crate::modules::shared::SharedCalculator;
+use crate::modules::utils;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, Write};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Fehlerobjekt einer JSON-RPC-Antwort
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+
+    fn new(code: i64, message: String) -> Self {
+        Self {
+            code,
+            message,
+            data: None,
+        }
+    }
+
+    fn localized(code: i64, key: &'static str, detail: Message, locale: Locale) -> Self {
+        Self::new(code, Message::new(key, [detail.into()]).render(locale))
+    }
+
+    fn invalid_request(key: &'static str, locale: Locale) -> Self {
+        let detail = Message::new(key, []);
+        Self::localized(Self::INVALID_REQUEST, "rpc.invalid_request", detail, locale)
+    }
+
+    fn invalid_params(detail: impl Into<Message>, locale: Locale) -> Self {
+        Self::localized(Self::INVALID_PARAMS, "rpc.invalid_params", detail.into(), locale)
+    }
+}
+
+/// Anwendungsfehler erhalten Codes aus dem für Server reservierten Bereich
+///
+/// Die Art des Fehlers steht zusätzlich als [`ProjectError::code`] in
+/// `data.kind`, der vollständige Fehler in `data.error`.
+impl From<ProjectError> for RpcError {
+    fn from(error: ProjectError) -> Self {
+        let code = match error {
+            ProjectError::DivisionByZero { .. } => -32001,
+            ProjectError::OverflowError { .. } => -32002,
+            ProjectError::InvalidInput { .. } => -32003,
+            ProjectError::IoError { .. } => -32004,
+            ProjectError::ParseError { .. } => -32005,
+            ProjectError::Cancelled => -32006,
+            ProjectError::AuditViolation { .. } => -32007,
+        };
+        Self {
+            code,
+            message: error.to_string(),
+            data: Some(json!({ "kind": error.code(), "error": error })),
+        }
+    }
+}
+
+/// Stellt den ursprünglichen Fehler wieder her, z. B. auf Seiten eines Clients
+///
+/// Lässt sich `data.error` nicht lesen, entsteht der Fehler aus `data.kind`
+/// bzw. dem Code und der Meldung, ohne deren Überschrift zu wiederholen.
+impl From<RpcError> for ProjectError {
+    fn from(error: RpcError) -> Self {
+        let mut data = error.data.unwrap_or_default();
+        let entry = data["error"]["entry"].as_u64().unwrap_or_default();
+        if let Ok(restored) = serde_json::from_value(data["error"].take()) {
+            return restored;
+        }
+        let kind = match (data["kind"].as_str(), error.code) {
+            (Some(kind), _) => kind,
+            (None, -32001) => "division_by_zero",
+            (None, -32002) => "overflow",
+            (None, -32004) => "io_error",
+            (None, -32005) => "parse_error",
+            (None, -32006) => "cancelled",
+            (None, -32007) => "audit_violation",
+            (None, _) => "invalid_input",
+        };
+        let message = error.message.as_str();
+        match kind {
+            "division_by_zero" => ProjectError::division_by_zero(),
+            "cancelled" => ProjectError::Cancelled,
+            "overflow" => ProjectError::overflow(detail(message, ProjectError::overflow)),
+            "io_error" => {
+                let wrap = |detail: Message| io::Error::other(detail.to_string()).into();
+                ProjectError::from(io::Error::other(detail(message, wrap).to_string()))
+            }
+            "parse_error" => {
+                let wrap = |detail: Message| Diagnostic::new(detail).into();
+                ProjectError::from(Diagnostic::new(detail(message, wrap)))
+            }
+            "audit_violation" => {
+                let wrap = |reason| ProjectError::AuditViolation { entry, reason };
+                wrap(detail(message, wrap))
+            }
+            _ => ProjectError::invalid_input(detail(message, ProjectError::invalid_input)),
+        }
+    }
+}
+
+/// Meldung ohne die Überschrift, die `wrap` um sie legt
+///
+/// Aus `"Ungültige Eingabe: leer"` wird so mit [`ProjectError::invalid_input`]
+/// wieder `"leer"`; andere Meldungen bleiben unverändert.
+fn detail(message: &str, wrap: impl Fn(Message) -> ProjectError) -> Message {
+    const MARKER: &str = "\u{0}";
+    let template = wrap(Message::text(MARKER)).to_string();
+    let (prefix, suffix) = template.split_once(MARKER).unwrap_or_default();
+    message
+        .strip_prefix(prefix)
+        .and_then(|rest| rest.strip_suffix(suffix))
+        .unwrap_or(message)
+        .into()
+}
+
+/// Verarbeitet JSON-RPC-Anfragen für einen [`SharedCalculator`]
+///
+/// Jede Zeile der Eingabe ist eine Anfrage oder ein Stapel von Anfragen.
+/// Änderungen am Verlauf werden als Benachrichtigung `history_changed` vor der
+/// Antwort der auslösenden Anfrage geschrieben.
+///
+/// # Methoden
+///
+/// `add`, `subtract`, `multiply`, `divide` (`a`, `b`), `factorial` (`n`),
+/// `apply` (`name`, `operands`), `define_operation` (`definition`, z. B.
+/// `"mean(a, b) = (a + b) / 2"`), `operations`,
+/// `evaluate` (`expression`), `set_variable` (`name`, `value`),
+/// `get_variable`, `remove_variable` (`name`), `variables`, `history_count`,
+/// `get_history`, `query_history` (Felder von [`HistoryQuery`]), `statistics`,
+/// `clear_history` sowie `math.gcd`, `math.lcm` (`a`, `b`), `math.is_prime`,
+/// `math.fibonacci`, `math.big_factorial` (`n`, höchstens
+/// [`MathUtils::BIG_FACTORIAL_MAX`]). Parameter können benannt oder in dieser
+/// Reihenfolge als Array übergeben werden.
+///
+/// # Beispiel
+///
+/// ```rust
+/// use rust_project_complete::modules::rpc::RpcServer;
+/// use rust_project_complete::modules::shared::SharedCalculator;
+///
+/// let server = RpcServer::new(SharedCalculator::new());
+/// let output = server.handle(r#"{"jsonrpc": "2.0", "method": "add", "params": [2, 3], "id": 1}"#);
+///
+/// assert!(output[0].contains("history_changed"));
+/// assert_eq!(output[1], r#"{"id":1,"jsonrpc":"2.0","result":5.0}"#);
+/// ```
+#[derive(Debug)]
+pub struct RpcServer {
+    calculator: SharedCalculator,
+    notifications: Arc<Mutex<Notifications>>,
+    observer: ObserverHandle,
+    locale: Locale,
+}
+
+impl RpcServer {
+    /// Höchstzahl der Benachrichtigungen, die bis zur nächsten Anfrage gesammelt werden
+    ///
+    /// Weitere werden verworfen und vor der nächsten Antwort als
+    /// `{"event": "dropped", "count": n}` gemeldet; der Client sollte den
+    /// Verlauf dann mit `query_history` neu lesen.
+    pub const NOTIFICATION_LIMIT: usize = 1000;
+
+    /// Erstellt einen Server und meldet ihn als Beobachter des Verlaufs an
+    pub fn new(calculator: SharedCalculator) -> Self {
+        let notifications = Arc::new(Mutex::new(Notifications::default()));
+        let observer = calculator.add_observer(Arc::new(HistoryNotifier {
+            queue: notifications.clone(),
+        }));
+        Self {
+            calculator,
+            notifications,
+            observer,
+            locale: Locale::default(),
+        }
+    }
+
+    /// Sprache der Protokollfehler, z. B. bei ungültigem JSON; Standard ist Deutsch
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// Liest Anfragen zeilenweise aus `input`, bis die Eingabe endet
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn nicht gelesen oder geschrieben werden kann.
+    pub fn run(&self, input: impl BufRead, mut output: impl Write) -> Result<(), ProjectError> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            for message in self.handle(&line) {
+                writeln!(output, "{}", message)?;
+            }
+            output.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Verarbeitet eine Zeile und gibt die zu sendenden Nachrichten zurück
+    pub fn handle(&self, line: &str) -> Vec<String> {
+        let response = match serde_json::from_str::<Value>(line) {
+            Err(error) => Some(error_response(
+                Value::Null,
+                RpcError::localized(
+                    RpcError::PARSE_ERROR,
+                    "rpc.invalid_json",
+                    error.to_string().into(),
+                    self.locale,
+                ),
+            )),
+            Ok(Value::Array(batch)) if batch.is_empty() => Some(error_response(
+                Value::Null,
+                RpcError::invalid_request("rpc.empty_batch", self.locale),
+            )),
+            Ok(Value::Array(batch)) => {
+                let responses: Vec<Value> = batch
+                    .into_iter()
+                    .filter_map(|request| self.handle_request(request))
+                    .collect();
+                (!responses.is_empty()).then_some(Value::Array(responses))
+            }
+            Ok(request) => self.handle_request(request),
+        };
+
+        let mut messages: Vec<String> = self
+            .take_notifications()
+            .into_iter()
+            .map(|notification| notification.to_string())
+            .collect();
+        messages.extend(response.map(|response| response.to_string()));
+        messages
+    }
+
+    /// Prüft, ob `method` den Calculator verändert
+    pub fn is_mutating(method: &str) -> bool {
+        matches!(
+            method,
+            "add"
+                | "subtract"
+                | "multiply"
+                | "divide"
+                | "factorial"
+                | "apply"
+                | "define_operation"
+                | "evaluate"
+                | "set_variable"
+                | "remove_variable"
+                | "clear_history"
+        )
+    }
+
+    fn take_notifications(&self) -> Vec<Value> {
+        let mut notifications = self
+            .notifications
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let mut messages = std::mem::take(&mut notifications.queue);
+        if notifications.dropped > 0 {
+            let params = json!({ "event": "dropped", "count": notifications.dropped });
+            messages.push(json!({ "jsonrpc": "2.0", "method": "history_changed", "params": params }));
+            notifications.dropped = 0;
+        }
+        messages
+    }
+
+    /// Beantwortet eine einzelne Anfrage; Benachrichtigungen erhalten keine Antwort
+    fn handle_request(&self, request: Value) -> Option<Value> {
+        let Value::Object(mut request) = request else {
+            return Some(error_response(
+                Value::Null,
+                RpcError::invalid_request("rpc.object_expected", self.locale),
+            ));
+        };
+
+        let id = request.remove("id");
+        if let Some(id) = &id {
+            if !(id.is_string() || id.is_number() || id.is_null()) {
+                return Some(error_response(
+                    Value::Null,
+                    RpcError::invalid_request("rpc.invalid_id", self.locale),
+                ));
+            }
+        }
+
+        let valid_version = matches!(request.remove("jsonrpc"), Some(Value::String(v)) if v == "2.0");
+        let result = match request.remove("method") {
+            _ if !valid_version => Err(RpcError::invalid_request("rpc.invalid_version", self.locale)),
+            Some(Value::String(method)) => {
+                match request.remove("params").unwrap_or(Value::Null) {
+                    params @ (Value::Array(_) | Value::Object(_) | Value::Null) => {
+                        self.traced_dispatch(&method, params)
+                    }
+                    _ => Err(RpcError::invalid_request(
+                        "rpc.invalid_params_type",
+                        self.locale,
+                    )),
+                }
+            }
+            _ => Err(RpcError::invalid_request("rpc.missing_method", self.locale)),
+        };
+
+        let id = id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "result": result, "id": id }),
+            Err(error) => error_response(id, error),
+        })
+    }
+
+    /// Führt eine Methode mit eigener Korrelations-ID in einem Span aus
+    fn traced_dispatch(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        logging::with_correlation_id(&utils::generate_id(), || {
+            let span = tracing::debug_span!(
+                "rpc_request",
+                correlation_id = %logging::correlation_id(),
+                method,
+                params = %params,
+                error = tracing::field::Empty,
+            );
+            let _entered = span.enter();
+            let result = self.dispatch(method, params);
+            if let Err(error) = &result {
+                span.record("error", error.message.as_str());
+            }
+            result
+        })
+    }
+
+    fn dispatch(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        let calc = &self.calculator;
+        match method {
+            "add" => {
+                let Operands { a, b } = self.parse(params, &["a", "b"])?;
+                respond(Lossless(calc.add(a, b)?))
+            }
+            "subtract" => {
+                let Operands { a, b } = self.parse(params, &["a", "b"])?;
+                respond(Lossless(calc.subtract(a, b)?))
+            }
+            "multiply" => {
+                let Operands { a, b } = self.parse(params, &["a", "b"])?;
+                respond(Lossless(calc.multiply(a, b)?))
+            }
+            "divide" => {
+                let Operands { a, b } = self.parse(params, &["a", "b"])?;
+                respond(Lossless(calc.divide(a, b)?))
+            }
+            "factorial" => {
+                let Number { n } = self.parse(params, &["n"])?;
+                respond(calc.factorial(n)?)
+            }
+            "apply" => {
+                let Apply { name, operands } = self.parse(params, &["name", "operands"])?;
+                respond(Lossless(calc.apply(&name, &operands)?))
+            }
+            "define_operation" => {
+                let Definition { definition } = self.parse(params, &["definition"])?;
+                respond(calc.define_operation(definition.parse()?)?)
+            }
+            "operations" => {
+                self.parse::<NoParams>(params, &[])?;
+                respond(calc.read(|c| {
+                    c.operations()
+                        .iter()
+                        .map(|op| OperationInfo {
+                            name: op.name().to_string(),
+                            arity: op.arity(),
+                            symbol: op.symbol().map(str::to_string),
+                        })
+                        .collect::<Vec<_>>()
+                }))
+            }
+            "evaluate" => {
+                let ExpressionParams { expression } = self.parse(params, &["expression"])?;
+                respond(Lossless(calc.evaluate(&expression)?))
+            }
+            "set_variable" => {
+                let Variable { name, value } = self.parse(params, &["name", "value"])?;
+                respond(calc.write(|c| c.set_variable(&name, value)).map(Lossless))
+            }
+            "get_variable" => {
+                let VariableName { name } = self.parse(params, &["name"])?;
+                respond(calc.read(|c| c.get_variable(&name)).map(Lossless))
+            }
+            "remove_variable" => {
+                let VariableName { name } = self.parse(params, &["name"])?;
+                respond(calc.write(|c| c.remove_variable(&name)).map(Lossless))
+            }
+            "variables" => {
+                self.parse::<NoParams>(params, &[])?;
+                respond(calc.read(|c| {
+                    c.variables()
+                        .iter()
+                        .map(|(name, value)| (name.clone(), Lossless(*value)))
+                        .collect::<BTreeMap<_, _>>()
+                }))
+            }
+            "history_count" => {
+                self.parse::<NoParams>(params, &[])?;
+                respond(calc.history_count())
+            }
+            "get_history" => {
+                self.parse::<NoParams>(params, &[])?;
+                respond(calc.query_history(&HistoryQuery::new())?)
+            }
+            "query_history" => {
+                let query: HistoryQuery = self.parse(
+                    params,
+                    &["operation_type", "since", "until", "after_id", "offset", "limit"],
+                )?;
+                respond(calc.query_history(&query)?)
+            }
+            "statistics" => {
+                self.parse::<NoParams>(params, &[])?;
+                respond(calc.statistics())
+            }
+            "clear_history" => {
+                self.parse::<NoParams>(params, &[])?;
+                respond(calc.clear_history()?)
+            }
+            "math.gcd" => {
+                let IntegerOperands { a, b } = self.parse(params, &["a", "b"])?;
+                respond(MathUtils::gcd(a, b))
+            }
+            "math.lcm" => {
+                let IntegerOperands { a, b } = self.parse(params, &["a", "b"])?;
+                respond(MathUtils::lcm(a, b)?)
+            }
+            "math.is_prime" => {
+                let Number { n } = self.parse(params, &["n"])?;
+                respond(MathUtils::is_prime(n))
+            }
+            "math.fibonacci" => {
+                let Number { n } = self.parse(params, &["n"])?;
+                respond(MathUtils::fibonacci(n)?)
+            }
+            "math.big_factorial" => {
+                let Number { n } = self.parse(params, &["n"])?;
+                MathUtils::check_big_factorial(n)?;
+                respond(MathUtils::big_factorial(n))
+            }
+            _ => Err(RpcError::localized(
+                RpcError::METHOD_NOT_FOUND,
+                "rpc.unknown_method",
+                method.into(),
+                self.locale,
+            )),
+        }
+    }
+
+    /// Liest benannte oder positionsbezogene Parameter
+    fn parse<T: DeserializeOwned>(&self, params: Value, names: &[&str]) -> Result<T, RpcError> {
+        let params = match params {
+            Value::Array(values) if values.len() > names.len() => {
+                let detail = Message::new(
+                    "rpc.too_many_params",
+                    [names.len().into(), values.len().into()],
+                );
+                return Err(RpcError::invalid_params(detail, self.locale));
+            }
+            Value::Array(values) => Value::Object(
+                names
+                    .iter()
+                    .map(|name| name.to_string())
+                    .zip(values)
+                    .collect::<Map<_, _>>(),
+            ),
+            Value::Null => Value::Object(Map::new()),
+            other => other,
+        };
+        serde_json::from_value(params)
+            .map_err(|error| RpcError::invalid_params(error.to_string(), self.locale))
+    }
+}
+
+impl Drop for RpcServer {
+    fn drop(&mut self) {
+        self.calculator.remove_observer(self.observer);
+    }
+}
+
+/// Noch nicht gesendete Benachrichtigungen, höchstens [`RpcServer::NOTIFICATION_LIMIT`]
+#[derive(Debug, Default)]
+struct Notifications {
+    queue: Vec<Value>,
+    dropped: u64,
+}
+
+/// Sammelt Verlaufsänderungen als JSON-RPC-Benachrichtigungen
+struct HistoryNotifier {
+    queue: Arc<Mutex<Notifications>>,
+}
+
+impl CalculatorObserver for HistoryNotifier {
+    fn history_changed(&self, event: &HistoryEvent<'_>) {
+        let params = match event {
+            HistoryEvent::Appended(operation) => {
+                json!({ "event": "appended", "operation": operation })
+            }
+            HistoryEvent::Cleared => json!({ "event": "cleared" }),
+        };
+        let mut notifications = self.queue.lock().unwrap_or_else(PoisonError::into_inner);
+        if notifications.queue.len() >= RpcServer::NOTIFICATION_LIMIT {
+            notifications.dropped += 1;
+            return;
+        }
+        notifications
+            .queue
+            .push(json!({ "jsonrpc": "2.0", "method": "history_changed", "params": params }));
+    }
+}
+
+#[derive(Deserialize)]
+struct Operands {
+    #[serde(with = "lossless")]
+    a: f64,
+    #[serde(with = "lossless")]
+    b: f64,
+}
+
+#[derive(Deserialize)]
+struct IntegerOperands {
+    a: u64,
+    b: u64,
+}
+
+#[derive(Deserialize)]
+struct Number {
+    n: u64,
+}
+
+#[derive(Deserialize)]
+struct Apply {
+    name: String,
+    #[serde(with = "lossless::vec")]
+    operands: Vec<f64>,
+}
+
+#[derive(Deserialize)]
+struct Definition {
+    definition: String,
+}
+
+/// Beschreibung einer Operation für `operations`
+#[derive(Serialize)]
+struct OperationInfo {
+    name: String,
+    arity: usize,
+    symbol: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ExpressionParams {
+    expression: String,
+}
+
+#[derive(Deserialize)]
+struct Variable {
+    name: String,
+    #[serde(with = "lossless")]
+    value: f64,
+}
+
+#[derive(Deserialize)]
+struct VariableName {
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NoParams {}
+
+fn respond(value: impl Serialize) -> Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(|e| ProjectError::from(e).into())
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({ "jsonrpc": "2.0", "error": error, "id": id })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(server: &RpcServer, request: Value) -> Vec<Value> {
+        server
+            .handle(&request.to_string())
+            .iter()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    fn response(server: &RpcServer, request: Value) -> Value {
+        call(server, request).pop().unwrap()
+    }
+
+    #[test]
+    fn test_named_and_positional_params() {
+        let server = RpcServer::new(SharedCalculator::new());
+
+        let named = response(&server, json!({"jsonrpc": "2.0", "method": "multiply", "params": {"a": 3, "b": 4}, "id": 1}));
+        assert_eq!(named, json!({"jsonrpc": "2.0", "result": 12.0, "id": 1}));
+
+        let positional = response(&server, json!({"jsonrpc": "2.0", "method": "math.gcd", "params": [12, 8], "id": "x"}));
+        assert_eq!(positional["result"], 4);
+        assert_eq!(positional["id"], "x");
+
+        // Positionen folgen den Feldern von `HistoryQuery`, `offset` vor `limit`
+        response(&server, json!({"jsonrpc": "2.0", "method": "add", "params": [1, 2], "id": 2}));
+        let page = response(&server, json!({"jsonrpc": "2.0", "method": "query_history", "params": [null, null, null, null, 1, 1], "id": 3}));
+        let page = page["result"].as_array().unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0]["result"], 3.0);
+    }
+
+    #[test]
+    fn test_non_finite_numbers_round_trip() {
+        let server = RpcServer::new(SharedCalculator::new());
+
+        let product = response(&server, json!({"jsonrpc": "2.0", "method": "multiply", "params": [1e200, 1e200], "id": 1}));
+        assert_eq!(product["result"], "inf");
+
+        let sum = response(&server, json!({"jsonrpc": "2.0", "method": "add", "params": ["-inf", 1], "id": 2}));
+        assert_eq!(sum["result"], "-inf");
+
+        let difference = response(&server, json!({"jsonrpc": "2.0", "method": "subtract", "params": ["inf", "inf"], "id": 3}));
+        assert_eq!(difference["result"], "NaN");
+
+        response(&server, json!({"jsonrpc": "2.0", "method": "set_variable", "params": ["x", "inf"], "id": 4}));
+        let variables = response(&server, json!({"jsonrpc": "2.0", "method": "variables", "id": 5}));
+        assert_eq!(variables["result"], json!({"x": "inf"}));
+
+        let statistics = response(&server, json!({"jsonrpc": "2.0", "method": "statistics", "id": 6}));
+        let statistics: crate::modules::core::Statistics = serde_json::from_value(statistics["result"].clone()).unwrap();
+        assert_eq!(statistics.max_result, f64::INFINITY);
+        assert_eq!(statistics.min_result, f64::NEG_INFINITY);
+
+        let history = response(&server, json!({"jsonrpc": "2.0", "method": "query_history", "params": {"limit": 1}, "id": 7}));
+        assert_eq!(history["result"][0]["result"], "inf");
+    }
+
+    #[test]
+    fn test_history_notifications_precede_response() {
+        let server = RpcServer::new(SharedCalculator::new());
+
+        let messages = call(&server, json!({"jsonrpc": "2.0", "method": "evaluate", "params": ["1 + 2 * 3"], "id": 7}));
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0]["method"], "history_changed");
+        assert_eq!(messages[0]["params"]["operation"]["operation_type"], "Multiply");
+        assert_eq!(messages[1]["params"]["operation"]["result"], 7.0);
+        assert_eq!(messages[2]["result"], 7.0);
+
+        let messages = call(&server, json!({"jsonrpc": "2.0", "method": "clear_history", "id": 8}));
+        assert_eq!(messages[0]["params"], json!({"event": "cleared"}));
+    }
+
+    #[test]
+    fn test_notification_queue_is_bounded() {
+        let calculator = SharedCalculator::new();
+        let server = RpcServer::new(calculator.clone());
+        for i in 0..RpcServer::NOTIFICATION_LIMIT + 5 {
+            calculator.add(i as f64, 1.0).unwrap();
+        }
+
+        let messages = call(&server, json!({"jsonrpc": "2.0", "method": "history_count", "id": 1}));
+        assert_eq!(messages.len(), RpcServer::NOTIFICATION_LIMIT + 2);
+        assert_eq!(messages[RpcServer::NOTIFICATION_LIMIT]["params"], json!({"event": "dropped", "count": 5}));
+        assert_eq!(messages[RpcServer::NOTIFICATION_LIMIT + 1]["result"], RpcServer::NOTIFICATION_LIMIT + 5);
+
+        let messages = call(&server, json!({"jsonrpc": "2.0", "method": "history_count", "id": 2}));
+        assert_eq!(messages.len(), 1);
+
+        drop(server);
+        assert_eq!(calculator.read(|c| c.observer_count()), 0);
+    }
+
+    #[test]
+    fn test_project_errors() {
+        let server = RpcServer::new(SharedCalculator::new());
+
+        let error = response(&server, json!({"jsonrpc": "2.0", "method": "divide", "params": [1, 0], "id": 1}));
+        assert_eq!(error["error"]["code"], -32001);
+        assert_eq!(error["error"]["data"]["kind"], "division_by_zero");
+        assert_eq!(error["id"], 1);
+
+        let error: RpcError = serde_json::from_value(error["error"].clone()).unwrap();
+        assert_eq!(ProjectError::from(error), ProjectError::division_by_zero().with_operation("divide", &[1.0, 0.0]));
+
+        let n = MathUtils::BIG_FACTORIAL_MAX + 1;
+        let error = response(&server, json!({"jsonrpc": "2.0", "method": "math.big_factorial", "params": [n], "id": 2}));
+        assert_eq!(error["error"]["code"], -32003);
+        assert_eq!(error["error"]["data"]["kind"], "invalid_input");
+    }
+
+    #[test]
+    fn test_forwarded_policy_error() {
+        use crate::modules::numeric::NumericPolicy;
+        use crate::Calculator;
+
+        let mut calculator = Calculator::new();
+        calculator.set_numeric_policy(NumericPolicy::Strict);
+        let server = RpcServer::new(SharedCalculator::from(calculator));
+
+        let error = response(&server, json!({"jsonrpc": "2.0", "method": "add", "params": ["NaN", 1], "id": 1}));
+        assert_eq!(error["error"]["code"], -32003);
+        assert_eq!(error["error"]["data"]["error"]["operation"]["operands"], json!(["NaN", 1.0]));
+
+        let error: RpcError = serde_json::from_value(error["error"].clone()).unwrap();
+        let message = error.message.clone();
+        let restored = ProjectError::from(error.clone());
+        assert_eq!(restored.code(), "invalid_input");
+        assert_eq!(restored.to_string(), message);
+        assert!(restored.operation().unwrap().operands[0].is_nan());
+
+        // Ohne lesbares `data.error` bleiben Art und Meldung erhalten
+        let mut damaged = error;
+        damaged.data.as_mut().unwrap()["error"] = json!("kaputt");
+        let restored = ProjectError::from(damaged);
+        assert_eq!(restored.code(), "invalid_input");
+        assert_eq!(restored.to_string(), message);
+
+        let overflow = ProjectError::from(RpcError::new(-32002, "Overflow-Fehler: zu groß".to_string()));
+        assert_eq!(overflow, ProjectError::overflow("zu groß"));
+    }
+
+    #[test]
+    fn test_custom_operations() {
+        use crate::modules::operations::FnOperator;
+
+        let calculator = SharedCalculator::new();
+        calculator
+            .register_operation(Arc::new(
+                FnOperator::new("power", 2, |x| Ok(x[0].powf(x[1]))).symbol("^"),
+            ))
+            .unwrap();
+        let server = RpcServer::new(calculator);
+
+        let result = response(&server, json!({"jsonrpc": "2.0", "method": "apply", "params": ["power", [2, 8]], "id": 1}));
+        assert_eq!(result["result"], 256.0);
+        let result = response(&server, json!({"jsonrpc": "2.0", "method": "evaluate", "params": {"expression": "2 ^ 3"}, "id": 2}));
+        assert_eq!(result["result"], 8.0);
+
+        let error = response(&server, json!({"jsonrpc": "2.0", "method": "apply", "params": {"name": "wurzel", "operands": [4]}, "id": 3}));
+        assert_eq!(error["error"]["data"]["kind"], "invalid_input");
+
+        let operations = response(&server, json!({"jsonrpc": "2.0", "method": "operations", "id": 4}));
+        assert_eq!(operations["result"][0], json!({"name": "add", "arity": 2, "symbol": "+"}));
+        assert_eq!(operations["result"][3], json!({"name": "power", "arity": 2, "symbol": "^"}));
+    }
+
+    #[test]
+    fn test_protocol_errors() {
+        let server = RpcServer::new(SharedCalculator::new());
+        let code = |line: &str| {
+            let messages = server.handle(line);
+            let response: Value = serde_json::from_str(messages.last().unwrap()).unwrap();
+            response["error"]["code"].as_i64().unwrap()
+        };
+
+        assert_eq!(code("{kein json"), RpcError::PARSE_ERROR);
+        assert_eq!(code("[]"), RpcError::INVALID_REQUEST);
+        assert_eq!(code("42"), RpcError::INVALID_REQUEST);
+        assert_eq!(code(r#"{"jsonrpc": "1.0", "method": "add", "id": 1}"#), RpcError::INVALID_REQUEST);
+        assert_eq!(code(r#"{"jsonrpc": "2.0", "method": "wurzel", "id": 1}"#), RpcError::METHOD_NOT_FOUND);
+        assert_eq!(code(r#"{"jsonrpc": "2.0", "method": "add", "params": [1], "id": 1}"#), RpcError::INVALID_PARAMS);
+        assert_eq!(code(r#"{"jsonrpc": "2.0", "method": "add", "params": [1, 2, 3], "id": 1}"#), RpcError::INVALID_PARAMS);
+        assert_eq!(code(r#"{"jsonrpc": "2.0", "method": "add", "params": 5, "id": 1}"#), RpcError::INVALID_REQUEST);
+    }
+
+    #[test]
+    fn test_localized_protocol_errors() {
+        let message = |server: &RpcServer, line: &str| {
+            let response: Value = serde_json::from_str(&server.handle(line).pop().unwrap()).unwrap();
+            response["error"]["message"].as_str().unwrap().to_string()
+        };
+        let german = RpcServer::new(SharedCalculator::new());
+        let english = RpcServer::new(SharedCalculator::new()).locale(Locale::En);
+
+        let line = r#"{"jsonrpc": "2.0", "method": "wurzel", "id": 1}"#;
+        assert_eq!(message(&german, line), "Unbekannte Methode 'wurzel'");
+        assert_eq!(message(&english, line), "Unknown method 'wurzel'");
+        assert_eq!(message(&english, "[]"), "Invalid request: empty batch");
+        assert_eq!(message(&english, r#"{"jsonrpc": "2.0", "id": 1}"#), "Invalid request: 'method' is missing");
+
+        let line = r#"{"jsonrpc": "2.0", "method": "add", "params": [1, 2, 3], "id": 1}"#;
+        assert_eq!(message(&german, line), "Ungültige Parameter: höchstens 2 Parameter erwartet, 3 erhalten");
+        assert_eq!(message(&english, line), "Invalid params: at most 2 parameters expected, got 3");
+        assert!(message(&english, "{kein json").starts_with("Invalid JSON: "));
+    }
+
+    #[test]
+    fn test_notifications_and_batches() {
+        let calc = SharedCalculator::new();
+        let server = RpcServer::new(calc.clone());
+
+        // Anfragen ohne `id` werden ausgeführt, aber nicht beantwortet
+        let messages = call(&server, json!({"jsonrpc": "2.0", "method": "set_variable", "params": ["x", 2]}));
+        assert!(messages.is_empty());
+
+        let messages = call(&server, json!([
+            {"jsonrpc": "2.0", "method": "get_variable", "params": ["x"], "id": 1},
+            {"jsonrpc": "2.0", "method": "add", "params": [1, 1]},
+            {"jsonrpc": "2.0", "method": "history_count", "id": 2}
+        ]));
+        let batch = messages.last().unwrap().as_array().unwrap();
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch[0]["result"], 2.0);
+        assert_eq!(batch[1]["result"], 1);
+    }
+
+    #[test]
+    fn test_run() {
+        let server = RpcServer::new(SharedCalculator::new());
+        let input = "{\"jsonrpc\":\"2.0\",\"method\":\"add\",\"params\":[1,2],\"id\":1}\n\n";
+        let mut output = Vec::new();
+        server.run(input.as_bytes(), &mut output).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().count(), 2);
+        assert!(output.ends_with("\"result\":3.0}\n"));
+    }
+}
@@ -0,0 +1,395 @@
+//! HTTP-JSON-Schnittstelle für den Calculator
+
+use crate::modules::async_api::{AsyncCalculator, CancellationToken};
+use crate::modules::core::{MathUtils, Statistics};
+use crate::modules::diagnostic::Diagnostic;
+use crate::modules::error::ProjectError;
+use crate::modules::history::HistoryQuery;
+use crate::modules::metrics::{self, Metrics};
+use crate::modules::numeric::lossless::{self, Lossless};
+use crate::Operation;
+use axum::extract::rejection::{JsonRejection, QueryRejection};
+use axum::extract::{FromRequest, FromRequestParts, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use tokio::net::TcpListener;
+
+/// Zwei Operanden einer Grundrechenart
+///
+/// NaN und ±Unendlich werden als `"NaN"`, `"inf"` und `"-inf"` angenommen.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Operands {
+    #[serde(with = "lossless")]
+    pub a: f64,
+    #[serde(with = "lossless")]
+    pub b: f64,
+}
+
+/// Zwei ganzzahlige Operanden, z. B. für `gcd`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct IntegerOperands {
+    pub a: u64,
+    pub b: u64,
+}
+
+/// Eine einzelne natürliche Zahl, z. B. für `factorial`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Number {
+    pub n: u64,
+}
+
+/// Auszuwertender Ausdruck
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpressionRequest {
+    pub expression: String,
+}
+
+/// Ergebnis einer Berechnung; Zahlen als [`Lossless`], damit ±Unendlich erhalten bleibt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Answer<T> {
+    pub result: T,
+}
+
+/// Fehlerantwort mit maschinenlesbarem Code
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorBody {
+    pub code: String,
+    pub message: String,
+    /// Vollständiger Fehler, z. B. mit `operation` oder `diagnostic`
+    pub details: ProjectError,
+}
+
+/// HTTP-Status, unter dem eine Fehlerart gemeldet wird
+///
+/// Die Zuordnung ist Teil der Schnittstelle und ändert sich nicht.
+pub fn status_code(error: &ProjectError) -> StatusCode {
+    match error {
+        ProjectError::InvalidInput { .. } | ProjectError::ParseError { .. } => StatusCode::BAD_REQUEST,
+        ProjectError::DivisionByZero { .. } | ProjectError::OverflowError { .. } => {
+            StatusCode::UNPROCESSABLE_ENTITY
+        }
+        ProjectError::Cancelled => StatusCode::SERVICE_UNAVAILABLE,
+        ProjectError::IoError { .. } | ProjectError::AuditViolation { .. } => {
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// [`ProjectError`] als HTTP-Antwort
+#[derive(Debug)]
+pub struct ApiError(pub ProjectError);
+
+impl From<ProjectError> for ApiError {
+    fn from(error: ProjectError) -> Self {
+        Self(error)
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        Self(Diagnostic::new(rejection.body_text()).into())
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(rejection: QueryRejection) -> Self {
+        Self(Diagnostic::new(rejection.body_text()).into())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = ErrorBody {
+            code: self.0.code().to_string(),
+            message: self.0.to_string(),
+            details: self.0,
+        };
+        (status_code(&body.details), Json(body)).into_response()
+    }
+}
+
+/// JSON-Körper, dessen Fehler ebenfalls als [`ErrorBody`] gemeldet werden
+#[derive(FromRequest)]
+#[from_request(via(axum::Json), rejection(ApiError))]
+struct ApiJson<T>(T);
+
+#[derive(FromRequestParts)]
+#[from_request(via(axum::extract::Query), rejection(ApiError))]
+struct ApiQuery<T>(T);
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+/// Erstellt die Routen der Schnittstelle mit eigenen [`Metrics`]
+///
+/// | Methode | Pfad                 | Körper / Parameter          |
+/// |---------|----------------------|-----------------------------|
+/// | POST    | `/add`, `/subtract`, `/multiply`, `/divide` | `{"a": 1, "b": 2}` |
+/// | POST    | `/factorial`         | `{"n": 5}`                  |
+/// | POST    | `/evaluate`          | `{"expression": "2 * 3"}`   |
+/// | POST    | `/math/gcd`, `/math/lcm` | `{"a": 12, "b": 8}`     |
+/// | POST    | `/math/is-prime`, `/math/fibonacci`, `/math/big-factorial` | `{"n": 7}` |
+/// | GET     | `/history`           | Felder von [`HistoryQuery`] |
+/// | GET     | `/statistics`        | –                           |
+/// | GET     | `/metrics`           | – (Prometheus-Textformat)   |
+pub fn router(calculator: AsyncCalculator) -> Router {
+    router_with_metrics(calculator, &Metrics::new())
+}
+
+/// Erstellt die Routen der Schnittstelle; `metrics` wird an den Calculator gebunden
+pub fn router_with_metrics(calculator: AsyncCalculator, metrics: &Metrics) -> Router {
+    metrics.attach(calculator.shared());
+    let metrics_route = Router::new()
+        .route("/metrics", get(render_metrics))
+        .with_state(metrics.clone());
+    Router::new()
+        .route("/add", post(add))
+        .route("/subtract", post(subtract))
+        .route("/multiply", post(multiply))
+        .route("/divide", post(divide))
+        .route("/factorial", post(factorial))
+        .route("/evaluate", post(evaluate))
+        .route("/math/gcd", post(gcd))
+        .route("/math/lcm", post(lcm))
+        .route("/math/is-prime", post(is_prime))
+        .route("/math/fibonacci", post(fibonacci))
+        .route("/math/big-factorial", post(big_factorial))
+        .route("/history", get(history))
+        .route("/statistics", get(statistics))
+        .with_state(calculator)
+        .merge(metrics_route)
+}
+
+/// Beantwortet Anfragen auf `listener`, bis `shutdown` abgeschlossen ist
+///
+/// # Errors
+///
+/// Gibt einen Fehler zurück, wenn der Server nicht betrieben werden kann.
+pub async fn serve(
+    listener: TcpListener,
+    calculator: AsyncCalculator,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Result<(), ProjectError> {
+    axum::serve(listener, router(calculator))
+        .with_graceful_shutdown(shutdown)
+        .await?;
+    Ok(())
+}
+
+async fn add(
+    State(calc): State<AsyncCalculator>,
+    ApiJson(Operands { a, b }): ApiJson<Operands>,
+) -> ApiResult<Answer<Lossless>> {
+    answer(calc.add(a, b).await.map(Lossless))
+}
+
+async fn subtract(
+    State(calc): State<AsyncCalculator>,
+    ApiJson(Operands { a, b }): ApiJson<Operands>,
+) -> ApiResult<Answer<Lossless>> {
+    answer(calc.subtract(a, b).await.map(Lossless))
+}
+
+async fn multiply(
+    State(calc): State<AsyncCalculator>,
+    ApiJson(Operands { a, b }): ApiJson<Operands>,
+) -> ApiResult<Answer<Lossless>> {
+    answer(calc.multiply(a, b).await.map(Lossless))
+}
+
+async fn divide(
+    State(calc): State<AsyncCalculator>,
+    ApiJson(Operands { a, b }): ApiJson<Operands>,
+) -> ApiResult<Answer<Lossless>> {
+    answer(calc.divide(a, b).await.map(Lossless))
+}
+
+async fn factorial(
+    State(calc): State<AsyncCalculator>,
+    ApiJson(Number { n }): ApiJson<Number>,
+) -> ApiResult<Answer<u64>> {
+    answer(calc.factorial(n).await)
+}
+
+async fn evaluate(
+    State(calc): State<AsyncCalculator>,
+    ApiJson(request): ApiJson<ExpressionRequest>,
+) -> ApiResult<Answer<Lossless>> {
+    answer(calc.evaluate(&request.expression).await.map(Lossless))
+}
+
+async fn gcd(ApiJson(IntegerOperands { a, b }): ApiJson<IntegerOperands>) -> ApiResult<Answer<u64>> {
+    answer(Ok(MathUtils::gcd(a, b)))
+}
+
+async fn lcm(ApiJson(IntegerOperands { a, b }): ApiJson<IntegerOperands>) -> ApiResult<Answer<u64>> {
+    answer(MathUtils::lcm(a, b))
+}
+
+async fn fibonacci(ApiJson(Number { n }): ApiJson<Number>) -> ApiResult<Answer<u64>> {
+    answer(MathUtils::fibonacci(n))
+}
+
+// Ein eigenes Token ist nicht nötig: Bricht der Client die Verbindung ab,
+// verwirft axum das Future und die Berechnung endet über dessen Drop
+async fn is_prime(
+    State(calc): State<AsyncCalculator>,
+    ApiJson(Number { n }): ApiJson<Number>,
+) -> ApiResult<Answer<bool>> {
+    answer(calc.is_prime(n, &CancellationToken::new()).await)
+}
+
+async fn big_factorial(
+    State(calc): State<AsyncCalculator>,
+    ApiJson(Number { n }): ApiJson<Number>,
+) -> ApiResult<Answer<String>> {
+    MathUtils::check_big_factorial(n)?;
+    answer(calc.big_factorial(n, &CancellationToken::new()).await)
+}
+
+async fn history(
+    State(calc): State<AsyncCalculator>,
+    ApiQuery(query): ApiQuery<HistoryQuery>,
+) -> ApiResult<Vec<Operation>> {
+    Ok(Json(calc.query_history(&query).await?))
+}
+
+async fn statistics(State(calc): State<AsyncCalculator>) -> ApiResult<Statistics> {
+    Ok(Json(calc.statistics().await?))
+}
+
+async fn render_metrics(State(metrics): State<Metrics>) -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, metrics::CONTENT_TYPE)], metrics.render())
+}
+
+fn answer<T>(result: Result<T, ProjectError>) -> ApiResult<Answer<T>> {
+    Ok(Json(Answer { result: result? }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    async fn call(app: &Router, method: &str, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(body.map_or_else(Body::empty, |b| Body::from(b.to_string())))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_operations() {
+        let app = router(AsyncCalculator::new());
+
+        let (status, body) = call(&app, "POST", "/add", Some(json!({"a": 2, "b": 3}))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, json!({"result": 5.0}));
+
+        let (_, body) = call(&app, "POST", "/evaluate", Some(json!({"expression": "(1 + 2) * 4"}))).await;
+        assert_eq!(body["result"], 12.0);
+
+        let (_, body) = call(&app, "POST", "/factorial", Some(json!({"n": 5}))).await;
+        assert_eq!(body["result"], 120);
+    }
+
+    #[tokio::test]
+    async fn test_non_finite_numbers() {
+        let app = router(AsyncCalculator::new());
+
+        let (status, body) = call(&app, "POST", "/multiply", Some(json!({"a": 1e200, "b": 1e200}))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, json!({"result": "inf"}));
+
+        let (_, body) = call(&app, "POST", "/add", Some(json!({"a": "-inf", "b": 1}))).await;
+        assert_eq!(body["result"], "-inf");
+        let (_, body) = call(&app, "POST", "/subtract", Some(json!({"a": "inf", "b": "inf"}))).await;
+        assert_eq!(body["result"], "NaN");
+
+        let (_, body) = call(&app, "GET", "/history?limit=1", None).await;
+        assert_eq!(body[0]["result"], "inf");
+    }
+
+    #[tokio::test]
+    async fn test_math_utils() {
+        let app = router(AsyncCalculator::new());
+
+        let (_, body) = call(&app, "POST", "/math/gcd", Some(json!({"a": 12, "b": 8}))).await;
+        assert_eq!(body["result"], 4);
+        let (_, body) = call(&app, "POST", "/math/is-prime", Some(json!({"n": 97}))).await;
+        assert_eq!(body["result"], true);
+        let (_, body) = call(&app, "POST", "/math/big-factorial", Some(json!({"n": 25}))).await;
+        assert_eq!(body["result"], "15511210043330985984000000");
+    }
+
+    #[tokio::test]
+    async fn test_history_and_statistics() {
+        let app = router(AsyncCalculator::new());
+        call(&app, "POST", "/add", Some(json!({"a": 1, "b": 1}))).await;
+        call(&app, "POST", "/divide", Some(json!({"a": 9, "b": 3}))).await;
+
+        let (_, body) = call(&app, "GET", "/history?operation_type=Divide", None).await;
+        assert_eq!(body.as_array().unwrap().len(), 1);
+        assert_eq!(body[0]["result"], 3.0);
+
+        let (_, body) = call(&app, "GET", "/statistics", None).await;
+        assert_eq!(body["total_operations"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_metrics() {
+        let metrics = Metrics::new();
+        let app = router_with_metrics(AsyncCalculator::new(), &metrics);
+        call(&app, "POST", "/add", Some(json!({"a": 1, "b": 1}))).await;
+        call(&app, "POST", "/divide", Some(json!({"a": 1, "b": 0}))).await;
+
+        let request = Request::get("/metrics").body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], metrics::CONTENT_TYPE);
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let text = String::from_utf8(bytes.to_vec()).unwrap();
+        assert!(text.contains("calculator_operations_total{operation=\"add\"} 1"));
+        assert!(text.contains("calculator_history_size 1"));
+        assert_eq!(metrics.error_count("division_by_zero"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_errors_map_to_status_codes() {
+        let app = router(AsyncCalculator::new());
+
+        let (status, body) = call(&app, "POST", "/divide", Some(json!({"a": 1, "b": 0}))).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["code"], "division_by_zero");
+        assert_eq!(body["details"]["operation"]["name"], "divide");
+        assert_eq!(body["details"]["operation"]["operands"], json!([1.0, 0.0]));
+
+        let (status, body) = call(&app, "POST", "/evaluate", Some(json!({"expression": "2 +"}))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "parse_error");
+
+        let (status, body) = call(&app, "POST", "/add", Some(json!({"a": "x"}))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "parse_error");
+
+        let (status, _) = call(&app, "GET", "/history?limit=viele", None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let n = MathUtils::BIG_FACTORIAL_MAX + 1;
+        let (status, body) = call(&app, "POST", "/math/big-factorial", Some(json!({"n": n}))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "invalid_input");
+    }
+}
@@ -0,0 +1,398 @@
+//! Verwaltung benannter Calculator-Sitzungen
+
+use crate::modules::core::Config;
+use crate::modules::error::ProjectError;
+use crate::modules::i18n::Message;
+use crate::modules::utils;
+use crate::Calculator;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Dateiendung der gespeicherten Sitzungen
+const SESSION_EXTENSION: &str = "json";
+
+/// Benannte Sitzung mit eigenem Verlauf, eigenen Variablen und eigener Konfiguration
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Session {
+    pub name: String,
+    #[serde(default)]
+    pub config: Config,
+    pub calculator: Calculator,
+}
+
+impl Session {
+    /// Erstellt eine leere Sitzung mit Standardkonfiguration
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            config: Config::default(),
+            calculator: Calculator::new(),
+        }
+    }
+}
+
+/// Geliehene Sicht auf eine [`Session`] mit demselben Dateiformat
+#[derive(Serialize)]
+struct SessionRef<'a> {
+    name: &'a str,
+    config: &'a Config,
+    calculator: &'a Calculator,
+}
+
+/// Legt Sitzungen als JSON-Dateien in einem Verzeichnis ab
+///
+/// # Beispiel
+///
+/// ```rust
+/// use rust_project_complete::modules::session::SessionManager;
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let manager = SessionManager::new(dir.path().to_str().unwrap()).unwrap();
+///
+/// let mut budget = manager.create("budget").unwrap();
+/// budget.calculator.add(1200.0, 300.0).unwrap();
+/// manager.save(&budget).unwrap();
+///
+/// manager.fork("budget", "budget-2027").unwrap();
+/// assert_eq!(manager.list().unwrap(), vec!["budget", "budget-2027"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SessionManager {
+    directory: PathBuf,
+}
+
+impl SessionManager {
+    /// Erstellt einen Manager für `directory` und legt das Verzeichnis bei Bedarf an
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn das Verzeichnis nicht angelegt werden kann.
+    pub fn new(directory: &str) -> Result<Self, ProjectError> {
+        utils::create_directory(directory)?;
+        Ok(Self {
+            directory: PathBuf::from(directory),
+        })
+    }
+
+    /// Standardverzeichnis für Sitzungen im Home-Verzeichnis des Benutzers
+    pub fn default_directory() -> String {
+        let base = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+        Path::new(&base)
+            .join(".rust_project_complete")
+            .join("sessions")
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    /// Gibt das Verzeichnis der Sitzungen zurück
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Listet die Namen aller gespeicherten Sitzungen alphabetisch auf
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn das Verzeichnis nicht gelesen werden kann.
+    pub fn list(&self) -> Result<Vec<String>, ProjectError> {
+        let mut names = Vec::new();
+        let entries = fs::read_dir(&self.directory).map_err(|e| ProjectError::io(e, &self.directory))?;
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(SESSION_EXTENSION) {
+                continue;
+            }
+            if let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) {
+                if validate_name(name).is_ok() {
+                    names.push(name.to_string());
+                }
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    /// Prüft, ob eine Sitzung existiert
+    pub fn exists(&self, name: &str) -> bool {
+        validate_name(name).is_ok() && utils::file_exists(&self.session_path(name))
+    }
+
+    /// Erstellt und speichert eine neue, leere Sitzung
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn der Name ungültig ist oder bereits existiert.
+    pub fn create(&self, name: &str) -> Result<Session, ProjectError> {
+        self.ensure_available(name)?;
+        let session = Session::new(name);
+        self.save(&session)?;
+        Ok(session)
+    }
+
+    /// Lädt eine gespeicherte Sitzung
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn die Sitzung nicht existiert oder beschädigt ist.
+    pub fn open(&self, name: &str) -> Result<Session, ProjectError> {
+        self.ensure_exists(name)?;
+        let path = self.session_path(name);
+        let content = utils::read_file_content(&path)?;
+        let mut session: Session = utils::parse_json(&content, &path)?;
+        // Der Dateiname ist maßgeblich, falls die Datei von Hand umbenannt wurde
+        session.name = name.to_string();
+        Ok(session)
+    }
+
+    /// Lädt eine Sitzung oder erstellt sie, falls sie noch nicht existiert
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn der Name ungültig ist oder die Datei nicht
+    /// gelesen werden kann.
+    pub fn open_or_create(&self, name: &str) -> Result<Session, ProjectError> {
+        if self.exists(name) {
+            self.open(name)
+        } else {
+            self.create(name)
+        }
+    }
+
+    /// Speichert eine Sitzung unter ihrem Namen
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn der Name ungültig ist oder die Datei nicht
+    /// geschrieben werden kann.
+    pub fn save(&self, session: &Session) -> Result<(), ProjectError> {
+        self.save_parts(&session.name, &session.config, &session.calculator)
+    }
+
+    /// Speichert eine Sitzung aus ihren Bestandteilen, z. B. wenn der Calculator
+    /// gerade von einem Daemon genutzt wird
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`SessionManager::save`].
+    pub fn save_parts(
+        &self,
+        name: &str,
+        config: &Config,
+        calculator: &Calculator,
+    ) -> Result<(), ProjectError> {
+        validate_name(name)?;
+        let content = serde_json::to_string_pretty(&SessionRef {
+            name,
+            config,
+            calculator,
+        })?;
+        utils::write_file_content(&self.session_path(name), &content)
+    }
+
+    /// Benennt eine Sitzung um
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn die Quelle fehlt oder das Ziel bereits existiert.
+    pub fn rename(&self, old_name: &str, new_name: &str) -> Result<Session, ProjectError> {
+        let mut session = self.open(old_name)?;
+        self.ensure_available(new_name)?;
+
+        session.name = new_name.to_string();
+        self.save(&session)?;
+        let old_path = self.session_path(old_name);
+        fs::remove_file(&old_path).map_err(|e| ProjectError::io(e, &old_path))?;
+        Ok(session)
+    }
+
+    /// Kopiert eine Sitzung samt Verlauf, Variablen und Konfiguration unter neuem Namen
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn die Quelle fehlt oder das Ziel bereits existiert.
+    pub fn fork(&self, source: &str, target: &str) -> Result<Session, ProjectError> {
+        let mut session = self.open(source)?;
+        self.ensure_available(target)?;
+
+        session.name = target.to_string();
+        self.save(&session)?;
+        Ok(session)
+    }
+
+    /// Löscht eine Sitzung
+    ///
+    /// # Errors
+    ///
+    /// Gibt einen Fehler zurück, wenn die Sitzung nicht existiert.
+    pub fn delete(&self, name: &str) -> Result<(), ProjectError> {
+        self.ensure_exists(name)?;
+        let path = self.session_path(name);
+        fs::remove_file(&path).map_err(|e| ProjectError::io(e, &path))?;
+        Ok(())
+    }
+
+    fn session_path(&self, name: &str) -> String {
+        self.directory
+            .join(format!("{}.{}", name, SESSION_EXTENSION))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn ensure_exists(&self, name: &str) -> Result<(), ProjectError> {
+        validate_name(name)?;
+        if !self.exists(name) {
+            return Err(ProjectError::invalid_input(Message::new(
+                "session.not_found",
+                [name.into()],
+            )));
+        }
+        Ok(())
+    }
+
+    fn ensure_available(&self, name: &str) -> Result<(), ProjectError> {
+        validate_name(name)?;
+        if self.exists(name) {
+            return Err(ProjectError::invalid_input(Message::new(
+                "session.exists",
+                [name.into()],
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Erlaubt nur Namen, die gefahrlos als Dateiname verwendet werden können
+fn validate_name(name: &str) -> Result<(), ProjectError> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(ProjectError::invalid_input(Message::new(
+            "session.invalid_name",
+            [name.into()],
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn manager() -> (TempDir, SessionManager) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sessions");
+        let manager = SessionManager::new(path.to_str().unwrap()).unwrap();
+        (dir, manager)
+    }
+
+    #[test]
+    fn test_new_creates_directory() {
+        let (_dir, manager) = manager();
+        assert!(manager.directory().is_dir());
+        assert!(manager.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_create_and_open() {
+        let (_dir, manager) = manager();
+        let mut session = manager.create("budget").unwrap();
+        session.calculator.add(1.0, 2.0).unwrap();
+        session.calculator.set_variable("miete", 900.0);
+        session.config.precision = 4;
+        manager.save(&session).unwrap();
+
+        let loaded = manager.open("budget").unwrap();
+        assert_eq!(loaded.name, "budget");
+        assert_eq!(loaded.calculator.history_count(), 1);
+        assert_eq!(loaded.calculator.get_variable("miete"), Some(900.0));
+        assert_eq!(loaded.config.precision, 4);
+
+        assert!(manager.create("budget").is_err());
+    }
+
+    #[test]
+    fn test_sessions_are_independent() {
+        let (_dir, manager) = manager();
+        let mut a = manager.create("a").unwrap();
+        let b = manager.create("b").unwrap();
+        a.calculator.multiply(2.0, 2.0).unwrap();
+        manager.save(&a).unwrap();
+
+        assert_eq!(manager.open("a").unwrap().calculator.history_count(), 1);
+        assert_eq!(manager.open("b").unwrap().calculator.history_count(), 0);
+        assert_eq!(b.calculator.history_count(), 0);
+    }
+
+    #[test]
+    fn test_open_or_create() {
+        let (_dir, manager) = manager();
+        let session = manager.open_or_create("neu").unwrap();
+        assert_eq!(session.calculator.history_count(), 0);
+        assert!(manager.exists("neu"));
+    }
+
+    #[test]
+    fn test_rename() {
+        let (_dir, manager) = manager();
+        manager.create("alt").unwrap();
+        manager.create("belegt").unwrap();
+
+        assert!(manager.rename("alt", "belegt").is_err());
+        let renamed = manager.rename("alt", "neu").unwrap();
+
+        assert_eq!(renamed.name, "neu");
+        assert_eq!(manager.list().unwrap(), vec!["belegt", "neu"]);
+        assert!(manager.rename("alt", "wieder").is_err());
+    }
+
+    #[test]
+    fn test_fork_copies_state() {
+        let (_dir, manager) = manager();
+        let mut source = manager.create("quelle").unwrap();
+        source.calculator.add(1.0, 1.0).unwrap();
+        source.calculator.set_variable("x", 5.0);
+        manager.save(&source).unwrap();
+
+        let mut fork = manager.fork("quelle", "kopie").unwrap();
+        fork.calculator.add(2.0, 2.0).unwrap();
+        manager.save(&fork).unwrap();
+
+        assert_eq!(manager.open("quelle").unwrap().calculator.history_count(), 1);
+        let fork = manager.open("kopie").unwrap();
+        assert_eq!(fork.calculator.history_count(), 2);
+        assert_eq!(fork.calculator.get_variable("x"), Some(5.0));
+    }
+
+    #[test]
+    fn test_delete() {
+        let (_dir, manager) = manager();
+        manager.create("weg").unwrap();
+        manager.delete("weg").unwrap();
+
+        assert!(!manager.exists("weg"));
+        assert!(manager.delete("weg").is_err());
+        assert!(manager.open("weg").is_err());
+    }
+
+    #[test]
+    fn test_invalid_names_are_rejected() {
+        let (_dir, manager) = manager();
+        for name in ["", "../etc", "mit leerzeichen", "a/b"] {
+            assert!(matches!(manager.create(name), Err(ProjectError::InvalidInput { .. })));
+        }
+    }
+
+    #[test]
+    fn test_list_ignores_foreign_files() {
+        let (_dir, manager) = manager();
+        manager.create("echt").unwrap();
+        fs::write(manager.directory().join("notizen.txt"), "x").unwrap();
+
+        assert_eq!(manager.list().unwrap(), vec!["echt"]);
+    }
+}
@@ -0,0 +1,285 @@
+//! Thread-sicherer, gemeinsam genutzter Calculator
+
+use crate::modules::core::Statistics;
+use crate::modules::error::ProjectError;
+use crate::modules::history::HistoryQuery;
+use crate::modules::observer::{CalculatorObserver, ObserverHandle};
+use crate::modules::operations::{OperationDefinition, Operator};
+use crate::{Calculator, Operation};
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Calculator, der von vielen Threads oder Tasks gleichzeitig genutzt werden kann
+///
+/// Schreibende Operationen werden nacheinander ausgeführt, sodass der Verlauf
+/// eine eindeutige Reihenfolge hat (siehe [`Operation::id`]). Lesezugriffe wie
+/// [`SharedCalculator::get_history`] laufen parallel zueinander.
+///
+/// Beobachter werden unter der Schreibsperre aufgerufen und dürfen daher nicht
+/// selbst auf denselben `SharedCalculator` zugreifen.
+///
+/// # Beispiel
+///
+/// ```rust
+/// use rust_project_complete::modules::shared::SharedCalculator;
+/// use std::thread;
+///
+/// let calc = SharedCalculator::new();
+/// let worker = calc.clone();
+/// thread::spawn(move || worker.add(1.0, 2.0).unwrap()).join().unwrap();
+///
+/// assert_eq!(calc.history_count(), 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SharedCalculator {
+    inner: Arc<RwLock<Calculator>>,
+}
+
+impl From<Calculator> for SharedCalculator {
+    fn from(calculator: Calculator) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(calculator)),
+        }
+    }
+}
+
+impl SharedCalculator {
+    /// Erstellt einen neuen, leeren gemeinsamen Calculator
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Addiert zwei Zahlen
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`Calculator::add`].
+    pub fn add(&self, a: f64, b: f64) -> Result<f64, ProjectError> {
+        self.write_lock().add(a, b)
+    }
+
+    /// Subtrahiert zwei Zahlen
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`Calculator::subtract`].
+    pub fn subtract(&self, a: f64, b: f64) -> Result<f64, ProjectError> {
+        self.write_lock().subtract(a, b)
+    }
+
+    /// Multipliziert zwei Zahlen
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`Calculator::multiply`].
+    pub fn multiply(&self, a: f64, b: f64) -> Result<f64, ProjectError> {
+        self.write_lock().multiply(a, b)
+    }
+
+    /// Dividiert zwei Zahlen
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`Calculator::divide`].
+    pub fn divide(&self, a: f64, b: f64) -> Result<f64, ProjectError> {
+        self.write_lock().divide(a, b)
+    }
+
+    /// Führt eine registrierte Operation aus
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`Calculator::apply`].
+    pub fn apply(&self, name: &str, operands: &[f64]) -> Result<f64, ProjectError> {
+        self.write_lock().apply(name, operands)
+    }
+
+    /// Registriert eine eigene Operation
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`Calculator::register_operation`].
+    pub fn register_operation(&self, operator: Arc<dyn Operator>) -> Result<(), ProjectError> {
+        self.write_lock().register_operation(operator)
+    }
+
+    /// Definiert eine Operation als Ausdruck über ihre Parameter
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`Calculator::define_operation`].
+    pub fn define_operation(&self, definition: OperationDefinition) -> Result<(), ProjectError> {
+        self.write_lock().define_operation(definition)
+    }
+
+    /// Berechnet die Fakultät einer Zahl
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`Calculator::factorial`].
+    pub fn factorial(&self, n: u64) -> Result<u64, ProjectError> {
+        self.write_lock().factorial(n)
+    }
+
+    /// Wertet einen arithmetischen Ausdruck unter einer einzigen Schreibsperre aus
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`Calculator::evaluate`].
+    pub fn evaluate(&self, expression: &str) -> Result<f64, ProjectError> {
+        self.write_lock().evaluate(expression)
+    }
+
+    /// Gibt die Anzahl der Operationen im Verlauf zurück
+    pub fn history_count(&self) -> usize {
+        self.read_lock().history_count()
+    }
+
+    /// Gibt eine Momentaufnahme des Verlaufs zurück
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`Calculator::get_history`].
+    #[deprecated(note = "lädt den gesamten Verlauf; `scan_history` oder `query_history` verwenden")]
+    pub fn get_history(&self) -> Result<Vec<Operation>, ProjectError> {
+        self.query_history(&HistoryQuery::new())
+    }
+
+    /// Übergibt alle Operationen unter der Lesesperre der Reihe nach an `visit`
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`Calculator::scan_history`].
+    pub fn scan_history(&self, visit: &mut dyn FnMut(&Operation)) -> Result<(), ProjectError> {
+        self.read_lock().scan_history(visit)
+    }
+
+    /// Gibt alle Operationen zurück, deren ID größer als `id` ist
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`Calculator::query_history`].
+    pub fn history_since(&self, id: u64) -> Result<Vec<Operation>, ProjectError> {
+        self.query_history(&HistoryQuery::new().after_id(id))
+    }
+
+    /// Gibt die Operationen zurück, die `query` erfüllen
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`Calculator::query_history`].
+    pub fn query_history(&self, query: &HistoryQuery) -> Result<Vec<Operation>, ProjectError> {
+        self.read_lock().query_history(query)
+    }
+
+    /// Gibt eine Momentaufnahme der Statistiken zurück
+    pub fn statistics(&self) -> Statistics {
+        self.read_lock().statistics().clone()
+    }
+
+    /// Löscht den Verlauf
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`Calculator::clear_history`].
+    pub fn clear_history(&self) -> Result<(), ProjectError> {
+        self.write_lock().clear_history()
+    }
+
+    /// Registriert einen Beobachter
+    pub fn add_observer(&self, observer: Arc<dyn CalculatorObserver>) -> ObserverHandle {
+        self.write_lock().add_observer(observer)
+    }
+
+    /// Entfernt einen Beobachter
+    pub fn remove_observer(&self, handle: ObserverHandle) -> bool {
+        self.write_lock().remove_observer(handle)
+    }
+
+    /// Führt eine lesende Funktion mit gemeinsamer Sperre aus
+    pub fn read<R>(&self, f: impl FnOnce(&Calculator) -> R) -> R {
+        f(&self.read_lock())
+    }
+
+    /// Führt mehrere Schritte atomar unter der Schreibsperre aus
+    pub fn write<R>(&self, f: impl FnOnce(&mut Calculator) -> R) -> R {
+        f(&mut self.write_lock())
+    }
+
+    /// Gibt eine unabhängige Kopie des aktuellen Zustands zurück
+    ///
+    /// # Errors
+    ///
+    /// Siehe [`Calculator::snapshot`].
+    pub fn snapshot(&self) -> Result<Calculator, ProjectError> {
+        self.read_lock().snapshot()
+    }
+
+    // Eine Panik in einem Beobachter darf den Calculator nicht dauerhaft sperren
+    fn read_lock(&self) -> RwLockReadGuard<'_, Calculator> {
+        self.inner.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write_lock(&self) -> RwLockWriteGuard<'_, Calculator> {
+        self.inner.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OperationType;
+
+    #[test]
+    fn test_shared_operations() {
+        let calc = SharedCalculator::new();
+        assert_eq!(calc.add(2.0, 3.0).unwrap(), 5.0);
+        assert_eq!(calc.subtract(5.0, 3.0).unwrap(), 2.0);
+        assert_eq!(calc.multiply(4.0, 3.0).unwrap(), 12.0);
+        assert_eq!(calc.divide(10.0, 2.0).unwrap(), 5.0);
+        assert_eq!(calc.divide(1.0, 0.0), Err(ProjectError::division_by_zero().with_operation("divide", &[1.0, 0.0])));
+        assert_eq!(calc.factorial(5).unwrap(), 120);
+
+        assert_eq!(calc.history_count(), 5);
+        assert_eq!(calc.statistics().get_operation_count(&OperationType::Divide), 1);
+
+        calc.clear_history().unwrap();
+        assert_eq!(calc.history_count(), 0);
+    }
+
+    #[test]
+    fn test_clones_share_state() {
+        let calc = SharedCalculator::from(Calculator::new());
+        let other = calc.clone();
+        other.add(1.0, 1.0).unwrap();
+
+        assert_eq!(calc.history_count(), 1);
+
+        let snapshot = calc.snapshot().unwrap();
+        calc.add(1.0, 1.0).unwrap();
+        assert_eq!(snapshot.history_count(), 1);
+    }
+
+    #[test]
+    fn test_history_since() {
+        let calc = SharedCalculator::new();
+        for i in 0..5 {
+            calc.add(i as f64, 0.0).unwrap();
+        }
+
+        let ids: Vec<u64> = calc.history_since(3).unwrap().iter().map(|op| op.id).collect();
+        assert_eq!(ids, vec![4, 5]);
+        assert!(calc.history_since(5).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_write_is_atomic() {
+        let calc = SharedCalculator::new();
+        let result = calc.write(|c| {
+            let sum = c.add(1.0, 2.0)?;
+            c.multiply(sum, 2.0)
+        });
+
+        assert_eq!(result.unwrap(), 6.0);
+        assert_eq!(calc.read(|c| c.history_count()), 2);
+    }
+}
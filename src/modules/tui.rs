@@ -0,0 +1,501 @@
+//! Vollbild-Oberfläche im Terminal
+//!
+//! Zeigt eine Eingabezeile, den Verlauf aus [`Calculator::query_history`], die
+//! Statistiken aus [`Calculator::statistics`] und die Variablen. Mit den
+//! Pfeiltasten wird ein Eintrag im Verlauf gewählt, Tab übernimmt sein
+//! Ergebnis in die Eingabe. Gelesen wird je Bild nur der sichtbare Ausschnitt
+//! des Verlaufs.
+
+use crate::modules::error::ProjectError;
+use crate::modules::i18n::{Locale, Message};
+use crate::modules::history::HistoryQuery;
+use crate::{Calculator, Operation};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::BTreeMap;
+
+/// Variable, die das letzte Ergebnis der Eingabezeile enthält
+///
+/// Sie gilt nur in der Oberfläche und wird nicht mit den Variablen der
+/// Sitzung gespeichert.
+pub const LAST_RESULT: &str = "ans";
+
+/// Einträge, um die Bild auf/Bild ab im Verlauf springen
+const PAGE: usize = 10;
+
+/// Startet die Oberfläche im Terminal und kehrt zurück, wenn sie beendet wird
+///
+/// # Errors
+///
+/// Gibt [`ProjectError::Io`] zurück, wenn das Terminal nicht bedient werden kann.
+pub fn run(calculator: &mut Calculator, locale: Locale) -> Result<(), ProjectError> {
+    let mut terminal = ratatui::try_init()?;
+    let outcome = App::new(calculator, locale).run(&mut terminal);
+    ratatui::restore();
+    outcome
+}
+
+/// Zustand der Oberfläche
+///
+/// Enter wertet die Eingabe mit [`Calculator::evaluate`] aus; `name = ausdruck`
+/// belegt zusätzlich eine Variable. Das Ergebnis steht danach auch in
+/// [`LAST_RESULT`], das nur bis zum Beenden gilt. Fehler erscheinen in der
+/// Statuszeile, die Eingabe bleibt zum Korrigieren stehen.
+///
+/// | Taste                  | Wirkung                                         |
+/// |------------------------|-------------------------------------------------|
+/// | Enter                  | Eingabe auswerten                               |
+/// | ↑ / ↓, Bild ↑ / Bild ↓ | Eintrag im Verlauf wählen                       |
+/// | Pos1 / Ende            | Ältesten Eintrag wählen / Auswahl aufheben      |
+/// | Tab                    | Ergebnis des gewählten Eintrags übernehmen      |
+/// | Strg+U                 | Eingabe leeren                                  |
+/// | Esc                    | Auswahl aufheben, ohne Auswahl beenden          |
+/// | Strg+C, Strg+D         | Beenden                                         |
+pub struct App<'a> {
+    calculator: &'a mut Calculator,
+    locale: Locale,
+    input: String,
+    /// Index des gewählten Eintrags im Verlauf
+    selected: Option<usize>,
+    /// Wert von [`LAST_RESULT`]
+    last_result: Option<f64>,
+    status: Status,
+    running: bool,
+}
+
+enum Status {
+    Ready,
+    Result(String),
+    Error(String),
+}
+
+impl<'a> App<'a> {
+    /// Erstellt die Oberfläche für einen Calculator
+    pub fn new(calculator: &'a mut Calculator, locale: Locale) -> Self {
+        Self {
+            calculator,
+            locale,
+            input: String::new(),
+            selected: None,
+            last_result: None,
+            status: Status::Ready,
+            running: true,
+        }
+    }
+
+    /// Aktueller Inhalt der Eingabezeile
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Index des gewählten Eintrags im Verlauf
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Letztes Ergebnis der Eingabezeile, siehe [`LAST_RESULT`]
+    pub fn last_result(&self) -> Option<f64> {
+        self.last_result
+    }
+
+    /// Prüft, ob die Oberfläche noch läuft
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Verarbeitet einen Tastendruck
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('c' | 'd') if control => self.running = false,
+            KeyCode::Char('u') if control => self.input.clear(),
+            KeyCode::Char(c) if !control => self.input.push(c),
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Enter => self.submit(),
+            KeyCode::Up => self.select_older(1),
+            KeyCode::PageUp => self.select_older(PAGE),
+            KeyCode::Down => self.select_newer(1),
+            KeyCode::PageDown => self.select_newer(PAGE),
+            KeyCode::Home if self.calculator.history_count() > 0 => self.selected = Some(0),
+            KeyCode::End => self.selected = None,
+            KeyCode::Tab => self.insert_selected(),
+            KeyCode::Esc if self.selected.is_some() => self.selected = None,
+            KeyCode::Esc => self.running = false,
+            _ => {}
+        }
+    }
+
+    /// Zeichnet die Oberfläche
+    pub fn draw(&self, frame: &mut Frame) {
+        let [main, input, status, help] = Layout::vertical([
+            Constraint::Min(3),
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [history, side] =
+            Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .areas(main);
+        let [statistics, variables] =
+            Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(side);
+
+        self.draw_history(frame, history);
+        self.draw_statistics(frame, statistics);
+        self.draw_variables(frame, variables);
+
+        let block = Block::bordered().title(self.text("tui.input"));
+        frame.render_widget(Paragraph::new(self.input.as_str()).block(block), input);
+        let cursor = input.x + 1 + self.input.chars().count() as u16;
+        frame.set_cursor_position((cursor.min(input.right().saturating_sub(2)), input.y + 1));
+
+        let status_line = match &self.status {
+            Status::Ready => Line::default(),
+            Status::Result(text) => Line::styled(text.as_str(), Style::new().fg(Color::Green)),
+            Status::Error(text) => Line::styled(text.as_str(), Style::new().fg(Color::Red)),
+        };
+        frame.render_widget(status_line, status);
+        let help_line = self.text("tui.help");
+        frame.render_widget(
+            Line::styled(help_line, Style::new().add_modifier(Modifier::DIM)),
+            help,
+        );
+    }
+
+    fn run(mut self, terminal: &mut DefaultTerminal) -> Result<(), ProjectError> {
+        while self.running {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    self.handle_key(key);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn submit(&mut self) {
+        let input = self.input.trim().to_string();
+        if input.is_empty() {
+            return;
+        }
+        let (variable, expression) = match input.split_once('=') {
+            Some((name, expression)) if is_identifier(name.trim()) => {
+                (Some(name.trim()), expression.trim())
+            }
+            _ => (None, input.as_str()),
+        };
+
+        match self.evaluate(expression) {
+            Ok(value) => {
+                if let Some(name) = variable {
+                    self.calculator.set_variable(name, value);
+                }
+                self.last_result = Some(value);
+                let label = variable.unwrap_or(expression);
+                self.status = Status::Result(format!("{} = {}", label, value));
+                self.input.clear();
+                self.selected = None;
+            }
+            Err(error) => self.status = Status::Error(error.localized(self.locale)),
+        }
+    }
+
+    /// Wertet `expression` aus, [`LAST_RESULT`] nur für die Dauer der Auswertung
+    fn evaluate(&mut self, expression: &str) -> Result<f64, ProjectError> {
+        let Some(last_result) = self.last_result else {
+            return self.calculator.evaluate(expression);
+        };
+        let shadowed = self.calculator.set_variable(LAST_RESULT, last_result);
+        let outcome = self.calculator.evaluate(expression);
+        match shadowed {
+            Some(value) => self.calculator.set_variable(LAST_RESULT, value),
+            None => self.calculator.remove_variable(LAST_RESULT),
+        };
+        outcome
+    }
+
+    fn select_older(&mut self, steps: usize) {
+        let count = self.calculator.history_count();
+        if count > 0 {
+            let index = self.selected.unwrap_or(count);
+            self.selected = Some(index.saturating_sub(steps));
+        }
+    }
+
+    fn select_newer(&mut self, steps: usize) {
+        let count = self.calculator.history_count();
+        self.selected = self
+            .selected
+            .map(|index| index + steps)
+            .filter(|index| *index < count);
+    }
+
+    fn insert_selected(&mut self) {
+        let Some(index) = self.selected else {
+            return;
+        };
+        let query = HistoryQuery::new().offset(index).limit(1);
+        let result = match self.calculator.query_history(&query) {
+            Ok(history) => history.first().map(|operation| operation.result),
+            Err(error) => {
+                self.status = Status::Error(error.localized(self.locale));
+                return;
+            }
+        };
+        if let Some(result) = result {
+            // Negative Zahlen in Klammern, damit `2 *` gefolgt von `-3` gültig bleibt
+            if result < 0.0 {
+                self.input.push_str(&format!("({})", result));
+            } else {
+                self.input.push_str(&result.to_string());
+            }
+            self.selected = None;
+        }
+    }
+
+    fn draw_history(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::bordered().title(self.text("tui.history"));
+        // Ohne Auswahl sind die neuesten Einträge sichtbar, sonst ab dem gewählten
+        let height = area.height.saturating_sub(2) as usize;
+        let mut offset = self.calculator.history_count().saturating_sub(height);
+        if let Some(selected) = self.selected {
+            offset = offset.min(selected);
+        }
+        let query = HistoryQuery::new().offset(offset).limit(height);
+        let items: Vec<ListItem> = match self.calculator.query_history(&query) {
+            Ok(history) => history.iter().map(|op| ListItem::new(entry(op))).collect(),
+            Err(error) => vec![ListItem::new(error.localized(self.locale))],
+        };
+        let mut state =
+            ListState::default().with_selected(self.selected.map(|selected| selected - offset));
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("> ");
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+
+    fn draw_statistics(&self, frame: &mut Frame, area: Rect) {
+        let stats = self.calculator.statistics();
+        let lines: Vec<Line> = if stats.is_empty() {
+            vec![Line::from(self.text("history.empty"))]
+        } else {
+            let mut lines = vec![
+                Message::new("statistics.total", [stats.total_operations.into()]),
+                Message::new("statistics.average", [stats.average_result.into()]),
+                Message::new("statistics.minimum", [stats.min_result.into()]),
+                Message::new("statistics.maximum", [stats.max_result.into()]),
+            ];
+            lines.extend(
+                stats
+                    .operations()
+                    .into_iter()
+                    .map(|(operation_type, op_stats)| {
+                        Message::new(
+                            "statistics.operation_count",
+                            [operation_type.into(), op_stats.count.into()],
+                        )
+                    }),
+            );
+            lines
+                .iter()
+                .map(|line| Line::from(line.render(self.locale)))
+                .collect()
+        };
+        let block = Block::bordered().title(self.text("tui.statistics"));
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn draw_variables(&self, frame: &mut Frame, area: Rect) {
+        let mut variables: BTreeMap<&str, f64> = self
+            .calculator
+            .variables()
+            .iter()
+            .map(|(name, value)| (name.as_str(), *value))
+            .collect();
+        if let Some(value) = self.last_result {
+            variables.insert(LAST_RESULT, value);
+        }
+        let lines: Vec<Line> = if variables.is_empty() {
+            vec![Line::from(self.text("tui.no_variables"))]
+        } else {
+            variables
+                .iter()
+                .map(|(name, value)| Line::from(format!("{} = {}", name, value)))
+                .collect()
+        };
+        let block = Block::bordered().title(self.text("tui.variables"));
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    /// Meldung ohne Werte aus dem Katalog
+    fn text(&self, key: &'static str) -> String {
+        Message::new(key, []).render(self.locale)
+    }
+}
+
+/// Eintrag im Verlauf, z. B. `1: add(2, 3) = 5`
+fn entry(operation: &Operation) -> String {
+    let operands: Vec<String> = operation.operands.iter().map(f64::to_string).collect();
+    format!(
+        "{}: {}({}) = {}",
+        operation.id,
+        operation.operation_type.name(),
+        operands.join(", "),
+        operation.result
+    )
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_alphabetic() || first == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn press(app: &mut App, code: KeyCode) {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    fn type_line(app: &mut App, text: &str) {
+        for c in text.chars() {
+            press(app, KeyCode::Char(c));
+        }
+        press(app, KeyCode::Enter);
+    }
+
+    fn screen(app: &App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(90, 24)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_evaluate_and_assign() {
+        let mut calc = Calculator::new();
+        let mut app = App::new(&mut calc, Locale::De);
+        type_line(&mut app, "miete = 900");
+        type_line(&mut app, "miete * 12");
+        assert_eq!(app.input(), "");
+
+        // Fehler lassen die Eingabe zum Korrigieren stehen
+        type_line(&mut app, "1 / 0");
+        assert_eq!(app.input(), "1 / 0");
+        assert!(screen(&app).contains("Division durch Null ist nicht erlaubt"));
+
+        assert_eq!(app.last_result(), Some(10800.0));
+        app.handle_key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL));
+        type_line(&mut app, "ans / 12");
+        assert_eq!(app.last_result(), Some(900.0));
+        assert!(screen(&app).contains("ans = 900"));
+
+        // `ans` gehört nicht zu den gespeicherten Variablen
+        assert_eq!(calc.get_variable("miete"), Some(900.0));
+        assert_eq!(calc.get_variable(LAST_RESULT), None);
+        assert_eq!(calc.history_count(), 2);
+    }
+
+    #[test]
+    fn test_reuse_selected_result() {
+        let mut calc = Calculator::new();
+        calc.add(2.0, 3.0).unwrap();
+        calc.subtract(1.0, 4.0).unwrap();
+        let mut app = App::new(&mut calc, Locale::De);
+
+        press(&mut app, KeyCode::Up);
+        assert_eq!(app.selected(), Some(1));
+        press(&mut app, KeyCode::PageUp);
+        assert_eq!(app.selected(), Some(0));
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.selected(), None);
+
+        press(&mut app, KeyCode::Up);
+        press(&mut app, KeyCode::Char('2'));
+        press(&mut app, KeyCode::Char('*'));
+        press(&mut app, KeyCode::Tab);
+        assert_eq!(app.input(), "2*(-3)");
+        assert_eq!(app.selected(), None);
+
+        press(&mut app, KeyCode::Char('+'));
+        press(&mut app, KeyCode::Home);
+        press(&mut app, KeyCode::Tab);
+        press(&mut app, KeyCode::Enter);
+        assert!(screen(&app).contains("2*(-3)+5 = -1"));
+
+        press(&mut app, KeyCode::Up);
+        press(&mut app, KeyCode::Esc);
+        assert!(app.is_running());
+        press(&mut app, KeyCode::Esc);
+        assert!(!app.is_running());
+    }
+
+    #[test]
+    fn test_draw_panes() {
+        let mut calc = Calculator::new();
+        calc.add(2.0, 3.0).unwrap();
+        calc.set_variable("miete", 900.0);
+        let screen_de = screen(&App::new(&mut calc, Locale::De));
+        assert!(screen_de.contains("Verlauf"));
+        assert!(screen_de.contains("1: add(2, 3) = 5"));
+        assert!(screen_de.contains("Operationen gesamt: 1"));
+        assert!(screen_de.contains("Addition: 1"));
+        assert!(screen_de.contains("miete = 900"));
+
+        let screen_en = screen(&App::new(&mut calc, Locale::En));
+        assert!(screen_en.contains("History"));
+        assert!(screen_en.contains("Total operations: 1"));
+        assert!(screen_en.contains("Variables"));
+        assert!(screen_en.contains("Tab: insert result"));
+    }
+
+    #[test]
+    fn test_history_window_follows_selection() {
+        let mut calc = Calculator::new();
+        for i in 1..=100 {
+            calc.add(i as f64, 0.0).unwrap();
+        }
+        let mut app = App::new(&mut calc, Locale::De);
+        let visible = screen(&app);
+        assert!(visible.contains("100: add(100, 0) = 100"));
+        assert!(!visible.contains("50: add(50, 0) = 50"));
+
+        press(&mut app, KeyCode::Home);
+        let visible = screen(&app);
+        assert!(visible.contains("> 1: add(1, 0) = 1"));
+        assert!(!visible.contains("100: add(100, 0) = 100"));
+
+        press(&mut app, KeyCode::PageDown);
+        press(&mut app, KeyCode::Tab);
+        assert_eq!(app.input(), "11");
+    }
+
+    #[test]
+    fn test_is_identifier() {
+        assert!(is_identifier("miete_2024"));
+        assert!(!is_identifier("2x"));
+        assert!(!is_identifier(""));
+    }
+}
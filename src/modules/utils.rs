@@ -1,8 +1,13 @@
 //! Hilfsfunktionen für das Projekt
 
+use crate::modules::diagnostic::Diagnostic;
 use crate::modules::error::ProjectError;
+use crate::modules::i18n::Message;
+use crate::modules::logging;
+use serde::de::DeserializeOwned;
 use std::fs;
 use std::path::Path;
+use tracing::field;
 use uuid::Uuid;
 
 /// Formatiert eine Zahl mit einer bestimmten Genauigkeit
@@ -15,14 +20,47 @@ pub fn generate_id() -> String {
     Uuid::new_v4().to_string()
 }
 
+/// Parst JSON, das aus `file_path` gelesen wurde
+///
+/// Fehler zeigen mit Datei, Zeile und Spalte auf die Stelle, siehe [`Diagnostic`].
+pub fn parse_json<T: DeserializeOwned>(content: &str, file_path: &str) -> Result<T, ProjectError> {
+    serde_json::from_str(content)
+        .map_err(|e| Diagnostic::from_json(&e, Some(content)).in_file(file_path).into())
+}
+
 /// Liest eine Datei und gibt den Inhalt zurück
 pub fn read_file_content(file_path: &str) -> Result<String, ProjectError> {
-    fs::read_to_string(file_path).map_err(ProjectError::from)
+    let span = tracing::debug_span!(
+        "file_read",
+        correlation_id = %logging::correlation_id(),
+        path = file_path,
+        bytes = field::Empty,
+        error = field::Empty,
+    );
+    let _entered = span.enter();
+    let content = fs::read_to_string(file_path).map_err(|e| ProjectError::io(e, file_path));
+    match &content {
+        Ok(content) => span.record("bytes", content.len()),
+        Err(error) => span.record("error", field::display(error)),
+    };
+    content
 }
 
 /// Schreibt Inhalt in eine Datei
 pub fn write_file_content(file_path: &str, content: &str) -> Result<(), ProjectError> {
-    fs::write(file_path, content).map_err(ProjectError::from)
+    let span = tracing::debug_span!(
+        "file_write",
+        correlation_id = %logging::correlation_id(),
+        path = file_path,
+        bytes = content.len(),
+        error = field::Empty,
+    );
+    let _entered = span.enter();
+    let written = fs::write(file_path, content).map_err(|e| ProjectError::io(e, file_path));
+    if let Err(error) = &written {
+        span.record("error", field::display(error));
+    }
+    written
 }
 
 /// Prüft, ob eine Datei existiert
@@ -33,20 +71,65 @@ pub fn file_exists(file_path: &str) -> bool {
 /// Erstellt ein Verzeichnis, falls es nicht existiert
 pub fn create_directory(dir_path: &str) -> Result<(), ProjectError> {
     if !Path::new(dir_path).exists() {
-        fs::create_dir_all(dir_path).map_err(ProjectError::from)?;
+        fs::create_dir_all(dir_path).map_err(|e| ProjectError::io(e, dir_path))?;
     }
     Ok(())
 }
 
+/// Setzt ein CSV-Feld in Anführungszeichen, falls es Trennzeichen enthält (RFC 4180)
+pub fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
 /// Validiert eine Email-Adresse (einfache Überprüfung)
 pub fn validate_email(email: &str) -> bool {
-    email.contains('@') && email.contains('.') && email.len() > 5
+    match email.split_once('@') {
+        Some((local, domain)) => !local.is_empty() && domain.contains('.') && email.len() > 5,
+        None => false,
+    }
+}
+
+/// Liest Zahlen, getrennt durch Leerraum, Kommas oder Semikolons
+///
+/// Ungültige Zahlen werden mit ihrer Position gemeldet.
+///
+/// # Beispiel
+///
+/// ```rust
+/// use rust_project_complete::modules::utils::parse_numbers;
+///
+/// assert_eq!(parse_numbers("1, 2.5\n-3;4").unwrap(), vec![1.0, 2.5, -3.0, 4.0]);
+/// assert!(parse_numbers("1 zwei").is_err());
+/// ```
+pub fn parse_numbers(input: &str) -> Result<Vec<f64>, ProjectError> {
+    let mut numbers = Vec::new();
+    let mut start = None;
+    // Ein abschließendes Trennzeichen beendet auch das letzte Feld
+    for (index, c) in input.char_indices().chain(std::iter::once((input.len(), ' '))) {
+        if !(c.is_whitespace() || c == ',' || c == ';') {
+            start.get_or_insert(index);
+            continue;
+        }
+        if let Some(start) = start.take() {
+            let field = &input[start..index];
+            let number = field.parse().map_err(|_| {
+                Diagnostic::new(Message::new("expression.invalid_number", [field.into()]))
+                    .with_span(input, start..index)
+            })?;
+            numbers.push(number);
+        }
+    }
+    Ok(numbers)
 }
 
 /// Berechnet den Durchschnitt einer Liste von Zahlen
 pub fn calculate_average(numbers: &[f64]) -> Result<f64, ProjectError> {
     if numbers.is_empty() {
-        return Err(ProjectError::InvalidInput("Liste ist leer".to_string()));
+        return Err(ProjectError::invalid_input(Message::new("math.empty_list", [])));
     }
     
     let sum: f64 = numbers.iter().sum();
@@ -56,7 +139,7 @@ pub fn calculate_average(numbers: &[f64]) -> Result<f64, ProjectError> {
 /// Findet das Minimum in einer Liste von Zahlen
 pub fn find_minimum(numbers: &[f64]) -> Result<f64, ProjectError> {
     if numbers.is_empty() {
-        return Err(ProjectError::InvalidInput("Liste ist leer".to_string()));
+        return Err(ProjectError::invalid_input(Message::new("math.empty_list", [])));
     }
     
     Ok(numbers.iter().fold(f64::INFINITY, |a, &b| a.min(b)))
@@ -65,7 +148,7 @@ pub fn find_minimum(numbers: &[f64]) -> Result<f64, ProjectError> {
 /// Findet das Maximum in einer Liste von Zahlen
 pub fn find_maximum(numbers: &[f64]) -> Result<f64, ProjectError> {
     if numbers.is_empty() {
-        return Err(ProjectError::InvalidInput("Liste ist leer".to_string()));
+        return Err(ProjectError::invalid_input(Message::new("math.empty_list", [])));
     }
     
     Ok(numbers.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b)))
@@ -79,7 +162,7 @@ pub fn sort_numbers(numbers: &mut [f64]) {
 /// Berechnet die Standardabweichung
 pub fn calculate_standard_deviation(numbers: &[f64]) -> Result<f64, ProjectError> {
     if numbers.is_empty() {
-        return Err(ProjectError::InvalidInput("Liste ist leer".to_string()));
+        return Err(ProjectError::invalid_input(Message::new("math.empty_list", [])));
     }
     
     let mean = calculate_average(numbers)?;
@@ -104,9 +187,28 @@ pub fn fahrenheit_to_celsius(fahrenheit: f64) -> f64 {
 mod tests {
     use super::*;
     use tempfile::NamedTempFile;
-    use std::io::Write;
 
     #[test]
+    fn test_csv_escape() {
+        assert_eq!(csv_escape("einfach"), "einfach");
+        assert_eq!(csv_escape("a, \"b\""), "\"a, \"\"b\"\"\"");
+    }
+
+    #[test]
+    fn test_parse_numbers() {
+        assert_eq!(parse_numbers("").unwrap(), Vec::<f64>::new());
+        assert_eq!(parse_numbers(" 1e3\t,,2 ").unwrap(), vec![1000.0, 2.0]);
+        let error = parse_numbers("1\n2,xy").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Parsing-Fehler: Ungültige Zahl 'xy' (Zeile 2, Spalte 3)"
+        );
+        assert_eq!(error.diagnostic().unwrap().span.as_ref().unwrap().length, 2);
+    }
+
+    #[test]
+    // 3.14159 ist hier nur ein Beispielwert, nicht π
+    #[allow(clippy::approx_constant)]
     fn test_format_number() {
         assert_eq!(format_number(3.14159, 2), "3.14");
         assert_eq!(format_number(10.0, 0), "10");
@@ -119,13 +221,13 @@ mod tests {
         let id2 = generate_id();
         
         assert_ne!(id1, id2);
-        assert!(id1.len() > 0);
-        assert!(id2.len() > 0);
+        assert!(!id1.is_empty());
+        assert!(!id2.is_empty());
     }
 
     #[test]
     fn test_file_operations() {
-        let mut temp_file = NamedTempFile::new().unwrap();
+        let temp_file = NamedTempFile::new().unwrap();
         let file_path = temp_file.path().to_str().unwrap();
         
         // Test schreiben
@@ -0,0 +1,734 @@
+//! Tests der Kommandozeilen-Anwendung
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn cli(dir: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_rust_project_complete"));
+    // Ein Daemon des Entwicklers darf die Tests nicht beeinflussen
+    command
+        .env("RUST_PROJECT_COMPLETE_SESSION_DIR", dir)
+        .env("RUST_PROJECT_COMPLETE_SOCKET", dir.join("daemon.sock"));
+    // Ebenso wenig seine Spracheinstellung; ohne Angabe wird Deutsch ausgegeben
+    for name in ["LC_ALL", "LC_MESSAGES", "LANG", "RUST_PROJECT_COMPLETE_LANG"] {
+        command.env_remove(name);
+    }
+    command
+}
+
+fn run_cli(dir: &Path, args: &[&str]) -> Output {
+    cli(dir)
+        .args(args)
+        .output()
+        .expect("CLI konnte nicht gestartet werden")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn test_cli_add() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run_cli(dir.path(), &["add", "2", "3"]);
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output).trim(), "2 + 3 = 5");
+}
+
+#[test]
+fn test_cli_divide_by_zero_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run_cli(dir.path(), &["divide", "1", "0"]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Division durch Null"));
+}
+
+#[test]
+fn test_cli_sessions_keep_separate_histories() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(run_cli(dir.path(), &["--session", "budget", "add", "100", "50"]).status.success());
+    assert!(run_cli(dir.path(), &["--session", "budget", "add", "1", "1"]).status.success());
+    assert!(run_cli(dir.path(), &["--session", "privat", "multiply", "2", "2"]).status.success());
+
+    let stats = stdout(&run_cli(dir.path(), &["--session", "budget", "stats"]));
+    assert!(stats.contains("Operationen gesamt: 2"));
+
+    let list = stdout(&run_cli(dir.path(), &["sessions", "list"]));
+    assert_eq!(list.lines().collect::<Vec<_>>(), vec!["budget", "privat"]);
+
+    assert!(run_cli(dir.path(), &["sessions", "fork", "budget", "kopie"]).status.success());
+    assert!(run_cli(dir.path(), &["sessions", "delete", "privat"]).status.success());
+
+    let list = stdout(&run_cli(dir.path(), &["sessions", "list"]));
+    assert_eq!(list.lines().collect::<Vec<_>>(), vec!["budget", "kopie"]);
+
+    let stats = stdout(&run_cli(dir.path(), &["--session", "kopie", "stats"]));
+    assert!(stats.contains("Operationen gesamt: 2"));
+}
+
+#[test]
+fn test_cli_audit_log_detects_tampering() {
+    let dir = tempfile::tempdir().unwrap();
+    let log = dir.path().join("audit.jsonl");
+    let log = log.to_str().unwrap();
+    assert!(run_cli(dir.path(), &["--audit-log", log, "add", "1", "2"]).status.success());
+    assert!(run_cli(dir.path(), &["--audit-log", log, "multiply", "3", "4"]).status.success());
+
+    let output = run_cli(dir.path(), &["audit", "verify", log]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("2 Einträge"));
+
+    let content = std::fs::read_to_string(log).unwrap();
+    std::fs::write(log, content.replace("12.0", "13.0")).unwrap();
+
+    let output = run_cli(dir.path(), &["audit", "verify", log]);
+    assert_eq!(output.status.code(), Some(9));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("bei Eintrag 2"));
+}
+
+#[cfg(feature = "server")]
+#[test]
+fn test_cli_serve_answers_http_requests() {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpStream;
+    use std::process::Stdio;
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut child = cli(dir.path())
+        .args(["serve", "--address", "127.0.0.1:0"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("CLI konnte nicht gestartet werden");
+
+    let mut banner = String::new();
+    BufReader::new(child.stdout.take().unwrap())
+        .read_line(&mut banner)
+        .unwrap();
+    let address = banner.trim().rsplit("http://").next().unwrap().to_string();
+
+    let body = r#"{"a": 2, "b": 3}"#;
+    let mut stream = TcpStream::connect(&address).unwrap();
+    write!(
+        stream,
+        "POST /add HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        address,
+        body.len(),
+        body
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200"));
+    assert!(response.ends_with(r#"{"result":5.0}"#));
+}
+
+#[test]
+fn test_cli_stdio_rpc() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut child = cli(dir.path())
+        .args(["--session", "rpc", "--stdio-rpc"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("CLI konnte nicht gestartet werden");
+
+    let requests = concat!(
+        r#"{"jsonrpc": "2.0", "method": "add", "params": [2, 3], "id": 1}"#,
+        "\n",
+        r#"{"jsonrpc": "2.0", "method": "divide", "params": {"a": 1, "b": 0}, "id": 2}"#,
+        "\n",
+        "kein json\n",
+    );
+    child.stdin.take().unwrap().write_all(requests.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let messages: Vec<serde_json::Value> = stdout(&output)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(messages.len(), 4);
+    assert_eq!(messages[0]["method"], "history_changed");
+    assert_eq!(messages[1]["result"], 5.0);
+    assert_eq!(messages[2]["error"]["data"]["kind"], "division_by_zero");
+    assert_eq!(messages[3]["error"]["code"], -32700);
+
+    // Der Verlauf der Sitzung bleibt nach dem Ende der Verbindung erhalten
+    let stats = stdout(&run_cli(dir.path(), &["--session", "rpc", "stats"]));
+    assert!(stats.contains("Operationen gesamt: 1"));
+
+    // Protokollfehler folgen --lang
+    let mut child = cli(dir.path())
+        .args(["--lang", "en", "--stdio-rpc"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("CLI konnte nicht gestartet werden");
+    child.stdin.take().unwrap().write_all(b"kein json\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    let response: serde_json::Value = serde_json::from_str(stdout(&output).trim()).unwrap();
+    assert!(response["error"]["message"].as_str().unwrap().starts_with("Invalid JSON: "));
+}
+
+#[test]
+fn test_cli_batch() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut child = cli(dir.path())
+        .args(["batch", "--format", "jsonl", "--jobs", "2"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("CLI konnte nicht gestartet werden");
+    let records = concat!(
+        r#"{"op": "add", "a": 2, "b": 3}"#,
+        "\n",
+        r#"{"op": "divide", "a": 1, "b": 0}"#,
+        "\n",
+    );
+    child.stdin.take().unwrap().write_all(records.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let results: Vec<serde_json::Value> = stdout(&output)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(results[0]["result"], 5.0);
+    assert_eq!(results[1]["error"]["code"], "division_by_zero");
+
+    // CSV-Datei mit Verlaufsdatei: Ergebnisse landen in der Ausgabedatei
+    let input = dir.path().join("auftrag.csv");
+    let results = dir.path().join("ergebnis.csv");
+    let history = dir.path().join("verlauf.json");
+    std::fs::write(&input, "op,a,b\nmultiply,2,4\nfactorial,5\n").unwrap();
+    let output = run_cli(
+        dir.path(),
+        &[
+            "--history",
+            history.to_str().unwrap(),
+            "batch",
+            input.to_str().unwrap(),
+            "--output",
+            results.to_str().unwrap(),
+        ],
+    );
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("2 Datensätze"));
+
+    let results = std::fs::read_to_string(results).unwrap();
+    assert!(results.contains("2,multiply,2,4,8,,"));
+    assert!(results.contains("3,factorial,5,,120,,"));
+    let stats = stdout(&run_cli(
+        dir.path(),
+        &["--history", history.to_str().unwrap(), "stats"],
+    ));
+    assert!(stats.contains("Operationen gesamt: 2"));
+}
+
+#[test]
+fn test_cli_apply_and_operations() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run_cli(dir.path(), &["apply", "subtract", "-3", "-4.5"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output).trim(), "subtract(-3, -4.5) = 1.5");
+
+    let output = run_cli(dir.path(), &["apply", "multiply", "2"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("erwartet 2 Operanden, erhalten 1"));
+
+    let operations = stdout(&run_cli(dir.path(), &["operations"]));
+    assert!(operations.contains("divide (2 Operanden, Symbol /)"));
+}
+
+#[test]
+fn test_cli_defined_operations_are_persisted() {
+    let dir = tempfile::tempdir().unwrap();
+    let history = dir.path().join("verlauf.json");
+    let history = history.to_str().unwrap();
+
+    let output = run_cli(dir.path(), &["--history", history, "define", "mean(a, b) = (a + b) / 2"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(stdout(&output).trim(), "Operation mean(a, b) = (a + b) / 2 definiert");
+
+    let output = run_cli(dir.path(), &["--history", history, "apply", "mean", "1", "4"]);
+    assert_eq!(stdout(&output).trim(), "mean(1, 4) = 2.5");
+    let output = run_cli(dir.path(), &["--history", history, "eval", "mean(2, 4) * 2"]);
+    assert_eq!(stdout(&output).trim(), "mean(2, 4) * 2 = 6");
+    let operations = stdout(&run_cli(dir.path(), &["--history", history, "operations"]));
+    assert!(operations.contains("mean (2 Operanden)"));
+
+    // Sitzungen speichern ihre Definitionen ebenso
+    assert!(run_cli(dir.path(), &["--session", "s", "define", "double(x)", "=", "x * 2"]).status.success());
+    let output = run_cli(dir.path(), &["--session", "s", "apply", "double", "21"]);
+    assert_eq!(stdout(&output).trim(), "double(21) = 42");
+
+    // Ohne Speicherort ginge die Definition verloren
+    let output = run_cli(dir.path(), &["define", "triple(x) = x * 3"]);
+    assert!(!output.status.success());
+    let output = run_cli(dir.path(), &["apply", "mean", "1", "4"]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unbekannte Operation 'mean'"));
+}
+
+#[test]
+fn test_cli_output_formats() {
+    let dir = tempfile::tempdir().unwrap();
+    let history = dir.path().join("verlauf.json");
+    let history = history.to_str().unwrap();
+
+    let output = run_cli(dir.path(), &["--history", history, "--output-format", "json", "add", "2", "3"]);
+    let value: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(value["result"], 5.0);
+    run_cli(dir.path(), &["--history", history, "divide", "1", "4"]);
+
+    let csv = stdout(&run_cli(dir.path(), &["--history", history, "--output-format", "csv", "history"]));
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "id,operation,operands,result,timestamp");
+    assert!(lines[2].starts_with("2,divide,1.0 4.0,0.25,"));
+
+    let table = stdout(&run_cli(dir.path(), &["--history", history, "--output-format", "table", "stats"]));
+    assert!(table.starts_with("operation  count  min_result  max_result  average_result
+"));
+    assert!(table.contains("divide     1      0.25        0.25        0.25\n"));
+
+    let output = run_cli(dir.path(), &["--output-format", "json", "divide", "1", "0"]);
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(error["error"]["code"], "division_by_zero");
+
+    // Unendliche Ergebnisse gehen in keinem Format verloren
+    let output = run_cli(
+        dir.path(),
+        &["--history", history, "--output-format", "json", "multiply", "1e200", "1e200"],
+    );
+    let value: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(value["result"], "inf");
+    assert_eq!(value["operands"], serde_json::json!([1e200, 1e200]));
+
+    let csv = stdout(&run_cli(dir.path(), &["--history", history, "--output-format", "csv", "history"]));
+    assert!(csv.lines().nth(3).unwrap().contains(",inf,"), "{}", csv);
+    let table = stdout(&run_cli(dir.path(), &["--history", history, "--output-format", "table", "stats"]));
+    assert!(table.contains("inf"), "{}", table);
+}
+
+#[test]
+fn test_cli_english_messages() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = cli(dir.path())
+        .env("LANG", "en_US.UTF-8")
+        .args(["divide", "1", "0"])
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Error: Division by zero is not allowed\n"
+    );
+
+    // --lang hat Vorrang vor LANG
+    let output = cli(dir.path())
+        .env("LANG", "en_US.UTF-8")
+        .args(["--lang", "de", "apply", "wurzel", "4"])
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unbekannte Operation 'wurzel'"));
+
+    // Die Konfiguration der Sitzung hat Vorrang vor LANG
+    let session = dir.path().join("budget.json");
+    run_cli(dir.path(), &["--session", "budget", "subtract", "5", "3"]);
+    let mut content: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&session).unwrap()).unwrap();
+    content["config"]["locale"] = "en".into();
+    std::fs::write(&session, content.to_string()).unwrap();
+
+    let stats = stdout(&run_cli(dir.path(), &["--session", "budget", "stats"]));
+    assert!(stats.contains("Total operations: 1"));
+    assert!(stats.contains("Subtraction: 1 (min 2, max 2, average 2)"));
+
+    let output = run_cli(dir.path(), &["--lang", "en", "--output-format", "json", "apply", "wurzel", "4"]);
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(error["error"]["code"], "invalid_input");
+    assert_eq!(error["error"]["message"], "Invalid input: Unknown operation 'wurzel'");
+}
+
+#[test]
+fn test_cli_english_messages_keep_user_values() {
+    let dir = tempfile::tempdir().unwrap();
+    // Namen, die zufällig wie deutsche Texte aussehen, bleiben unverändert
+    let output = run_cli(dir.path(), &["--lang", "en", "sessions", "create", "Multiplikation"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "Session 'Multiplikation' created\n");
+
+    let output = run_cli(dir.path(), &["--lang", "en", "apply", "Subtraktion", "1", "2"]);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Error: Invalid input: Unknown operation 'Subtraktion'\n"
+    );
+
+    let history = dir.path().join("history.json");
+    let history = history.to_str().unwrap();
+    let output =
+        run_cli(dir.path(), &["--lang", "en", "--history", history, "define", "Division(a) = a / 2"]);
+    assert_eq!(stdout(&output), "Operation Division(a) = a / 2 defined\n");
+}
+
+#[test]
+fn test_cli_math_commands() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let dir = tempfile::tempdir().unwrap();
+    for (args, expected) in [
+        (vec!["gcd", "12", "18"], "ggT(12, 18) = 6"),
+        (vec!["lcm", "4", "6"], "kgV(4, 6) = 12"),
+        (vec!["is-prime", "17"], "17 ist eine Primzahl"),
+        (vec!["fibonacci", "10"], "fibonacci(10) = 55"),
+        (vec!["big-factorial", "25"], "25! = 15511210043330985984000000"),
+        (vec!["average", "1", "2", "-3", "4"], "Durchschnitt: 1"),
+        (vec!["stddev", "2", "4", "4", "4", "5", "5", "7", "9"], "Standardabweichung: 2"),
+        (vec!["min", "3", "-1.5", "2"], "Minimum: -1.5"),
+        (vec!["celsius-to-fahrenheit", "-40"], "-40 °C = -40 °F"),
+        (vec!["fahrenheit-to-celsius", "212"], "212 °F = 100 °C"),
+    ] {
+        let output = run_cli(dir.path(), &args);
+        assert!(output.status.success(), "{:?}", args);
+        assert_eq!(stdout(&output).trim(), expected);
+    }
+
+    let numbers = dir.path().join("zahlen.txt");
+    std::fs::write(&numbers, "1, 5\n9;3\n").unwrap();
+    let output = run_cli(dir.path(), &["--output-format", "json", "max", "--file", numbers.to_str().unwrap()]);
+    let value: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(value["count"], 4);
+    assert_eq!(value["result"], 9.0);
+
+    let mut child = cli(dir.path())
+        .arg("average")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"2\n4\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(stdout(&output).trim(), "Durchschnitt: 3");
+
+    let output = run_cli(dir.path(), &["average", "--file", numbers.to_str().unwrap(), "1"]);
+    assert_eq!(output.status.code(), Some(2));
+    let output = run_cli(dir.path(), &["lcm", "0", "3"]);
+    assert_eq!(output.status.code(), Some(5));
+}
+
+#[test]
+fn test_cli_completions_and_man_pages() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run_cli(dir.path(), &["completions", "bash", "--static"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("big-factorial"));
+
+    let output = run_cli(dir.path(), &["completions", "fish"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("RUST_PROJECT_COMPLETE_COMPLETION"));
+
+    // So ruft die Shell das Programm bei jeder Vervollständigung auf
+    run_cli(dir.path(), &["sessions", "create", "projekt"]);
+    let complete = |args: &[&str]| {
+        let output = cli(dir.path())
+            .env("RUST_PROJECT_COMPLETE_COMPLETION", "fish")
+            .args(["--", "rust_project_complete"])
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        stdout(&output)
+    };
+    assert_eq!(complete(&["--session", ""]).trim(), "projekt");
+    assert!(complete(&["apply", ""]).lines().any(|line| line == "divide"));
+
+    let output = run_cli(dir.path(), &["man"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains(".TH rust_project_complete 1"));
+
+    let man = dir.path().join("man");
+    let output = run_cli(dir.path(), &["man", "--dir", man.to_str().unwrap()]);
+    assert!(output.status.success());
+    assert!(man.join("rust_project_complete.1").exists());
+    assert!(man.join("rust_project_complete-sessions-create.1").exists());
+}
+
+#[test]
+fn test_cli_eval_reports_parse_diagnostics() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run_cli(dir.path(), &["eval", "2 * (3 + 4)"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output).trim(), "2 * (3 + 4) = 14");
+
+    let output = run_cli(dir.path(), &["eval", "2 * ad(1, 2)"]);
+    assert_eq!(output.status.code(), Some(7));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Unbekannte Operation 'ad' (Zeile 1, Spalte 5); meinten Sie 'add'?"));
+    // Ohne Terminal keine Farben
+    assert!(stderr.contains("1 | 2 * ad(1, 2)\n  |     ^^\n"));
+
+    let output = run_cli(dir.path(), &["--output-format", "json", "eval", "(1 + 2"]);
+    assert_eq!(output.status.code(), Some(7));
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(error["error"]["diagnostic"]["span"]["column"], 1);
+    assert_eq!(error["error"]["diagnostic"]["suggestion"], "fehlende ')' ergänzen");
+}
+
+#[test]
+fn test_cli_numeric_policy() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run_cli(dir.path(), &["multiply", "1e200", "1e200"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("inf"));
+
+    let output = run_cli(dir.path(), &["--numeric-policy", "strict", "multiply", "1e200", "1e200"]);
+    assert_eq!(output.status.code(), Some(4));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Ergebnis inf ist keine endliche Zahl"));
+
+    let output = cli(dir.path())
+        .env("RUST_PROJECT_COMPLETE_NUMERIC_POLICY", "warn")
+        .args(["add", "NaN", "1"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Operand ist keine endliche Zahl"));
+
+    // Die Sitzung legt die Richtlinie fest, --numeric-policy hat Vorrang
+    run_cli(dir.path(), &["sessions", "create", "streng"]);
+    let path = dir.path().join("streng.json");
+    let mut session: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    session["config"]["numeric_policy"] = "strict".into();
+    std::fs::write(&path, session.to_string()).unwrap();
+    let output = run_cli(dir.path(), &["--session", "streng", "add", "inf", "1"]);
+    assert_eq!(output.status.code(), Some(5));
+    let args = ["--session", "streng", "--numeric-policy", "permissive", "add", "inf", "1"];
+    assert!(run_cli(dir.path(), &args).status.success());
+
+    let output = cli(dir.path())
+        .args(["--numeric-policy", "strict", "batch"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .and_then(|mut child| {
+            use std::io::Write;
+            child.stdin.take().unwrap().write_all(b"multiply,1e200,1e200\n")?;
+            child.wait_with_output()
+        })
+        .unwrap();
+    assert!(stdout(&output).contains("overflow"));
+}
+
+#[test]
+fn test_cli_json_log() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = cli(dir.path())
+        .args(["--log-format", "json", "divide", "1", "0"])
+        .env("RUST_LOG", "debug")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+
+    let records: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    let span = |name: &str| {
+        records
+            .iter()
+            .map(|record| &record["span"])
+            .find(|span| span["name"] == name)
+            .cloned()
+            .unwrap_or_else(|| panic!("Kein Span '{}' in {:?}", name, records))
+    };
+    let (command, operation) = (span("command"), span("operation"));
+    assert_eq!(command["command"], "divide");
+    assert_eq!(operation["operation"], "divide");
+    assert_eq!(operation["error"], "Division durch Null ist nicht erlaubt");
+    assert_eq!(operation["correlation_id"], command["correlation_id"]);
+
+    // Ohne RUST_LOG werden nur Warnungen protokolliert
+    let output = cli(dir.path())
+        .args(["--log-format", "json", "add", "1", "2"])
+        .env_remove("RUST_LOG")
+        .output()
+        .unwrap();
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn test_cli_requires_command_or_stdio_rpc() {
+    let dir = tempfile::tempdir().unwrap();
+    assert_eq!(run_cli(dir.path(), &[]).status.code(), Some(2));
+    assert_eq!(run_cli(dir.path(), &["--stdio-rpc", "stats"]).status.code(), Some(2));
+}
+
+#[cfg(not(feature = "tui"))]
+#[test]
+fn test_cli_interactive_without_tui() {
+    let dir = tempfile::tempdir().unwrap();
+    for command in ["interactive", "tui"] {
+        let output = run_cli(dir.path(), &[command]);
+        assert_eq!(output.status.code(), Some(2));
+        assert!(String::from_utf8_lossy(&output.stderr).contains("ohne das Feature `tui` gebaut"));
+    }
+}
+
+#[test]
+fn test_cli_exit_codes() {
+    let dir = tempfile::tempdir().unwrap();
+    let broken = dir.path().join("kaputt.json");
+    std::fs::write(&broken, "{").unwrap();
+    let missing = dir.path().join("fehlt.csv");
+
+    for (args, code) in [
+        (vec!["add", "1", "2"], 0),
+        (vec!["add", "--unbekannt"], 2),
+        (vec!["--output-format", "xml", "add", "1", "2"], 2),
+        (vec!["divide", "1", "0"], 3),
+        (vec!["factorial", "21"], 4),
+        (vec!["apply", "wurzel", "4"], 5),
+        (vec!["batch", missing.to_str().unwrap()], 6),
+        (vec!["--history", broken.to_str().unwrap(), "stats"], 7),
+    ] {
+        let output = run_cli(dir.path(), &args);
+        assert_eq!(output.status.code(), Some(code), "{:?}", args);
+    }
+
+    // Skripte unterscheiden Fehler an der Art, nicht an der Meldung
+    let output = run_cli(dir.path(), &["--output-format", "json", "batch", missing.to_str().unwrap()]);
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(error["error"]["code"], "io_error");
+    assert_eq!(error["error"]["kind"], "not_found");
+    assert_eq!(error["error"]["path"], missing.to_str().unwrap());
+
+    let output = run_cli(dir.path(), &["--output-format", "json", "divide", "1", "0"]);
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(error["error"]["operation"]["name"], "divide");
+
+    // Auch Aufruffehler von clap erscheinen im gewählten Format
+    let output = run_cli(dir.path(), &["--output-format", "json", "add", "1", "--unbekannt"]);
+    assert_eq!(output.status.code(), Some(2));
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(error["error"]["code"], "invalid_input");
+    assert!(error["error"]["message"].as_str().unwrap().contains("--unbekannt"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_cli_forwards_to_daemon() {
+    let dir = tempfile::tempdir().unwrap();
+    let history = dir.path().join("daemon.json");
+    let history = history.to_str().unwrap();
+
+    let output = run_cli(dir.path(), &["--history", history, "daemon", "start"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(stdout(&output).contains("Daemon gestartet"));
+
+    assert_eq!(stdout(&run_cli(dir.path(), &["add", "2", "3"])).trim(), "2 + 3 = 5");
+    assert!(!run_cli(dir.path(), &["divide", "1", "0"]).status.success());
+    assert_eq!(stdout(&run_cli(dir.path(), &["multiply", "2", "4"])).trim(), "2 * 4 = 8");
+    // Unendliche Ergebnisse kommen unverändert vom Daemon zurück
+    let output = run_cli(dir.path(), &["multiply", "1e200", "1e200"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(stdout(&output).trim().ends_with("= inf"));
+
+    // Definitionen gelten für alle Clients des Daemons
+    assert!(run_cli(dir.path(), &["define", "half(x) = x / 2"]).status.success());
+    assert!(stdout(&run_cli(dir.path(), &["operations"])).contains("half (1 Operanden)"));
+    assert_eq!(stdout(&run_cli(dir.path(), &["apply", "half", "9"])).trim(), "half(9) = 4.5");
+
+    // --numeric-policy gilt auch bei laufendem Daemon und umgeht ihn
+    let output = run_cli(dir.path(), &["--numeric-policy", "strict", "multiply", "1e200", "1e200"]);
+    assert_eq!(output.status.code(), Some(4), "{:?}", output);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("keine endliche Zahl"));
+
+    let stats = stdout(&run_cli(dir.path(), &["stats"]));
+    assert!(stats.contains("Operationen gesamt: 4"));
+    let status = stdout(&run_cli(dir.path(), &["daemon", "status"]));
+    assert!(status.contains("Operationen im Verlauf: 4"));
+    // Der Daemon speichert nach jeder Operation, nicht erst beim Beenden
+    let stats = stdout(&run_cli(dir.path(), &["--history", history, "stats"]));
+    assert!(stats.contains("Operationen gesamt: 4"));
+
+    assert!(run_cli(dir.path(), &["daemon", "stop"]).status.success());
+    assert_eq!(run_cli(dir.path(), &["daemon", "status"]).status.code(), Some(1));
+
+    // Beim Beenden wurde der Verlauf samt Definitionen gespeichert
+    let stats = stdout(&run_cli(dir.path(), &["--history", history, "stats"]));
+    assert!(stats.contains("Operationen gesamt: 4"));
+    let output = run_cli(dir.path(), &["--history", history, "apply", "half", "1"]);
+    assert_eq!(stdout(&output).trim(), "half(1) = 0.5");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_cli_daemon_uses_numeric_policy() {
+    let dir = tempfile::tempdir().unwrap();
+    // Ohne Speicherort liegt der Verlauf des Daemons unter HOME
+    let run = |args: &[&str]| cli(dir.path()).env("HOME", dir.path()).args(args).output().unwrap();
+
+    let output = run(&["--numeric-policy", "strict", "daemon", "start"]);
+    assert!(output.status.success(), "{:?}", output);
+
+    let output = run(&["add", "nan", "1"]);
+    assert_eq!(output.status.code(), Some(5), "{:?}", output);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Fehler: Ungültige Eingabe: Operand NaN ist keine endliche Zahl\n"
+    );
+    let output = run(&["multiply", "1e200", "1e200"]);
+    assert_eq!(output.status.code(), Some(4), "{:?}", output);
+
+    assert!(run(&["daemon", "stop"]).status.success());
+    assert!(dir.path().join(".rust_project_complete/daemon-history.json").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_cli_daemon_flushes_history_on_sigterm() {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+
+    let dir = tempfile::tempdir().unwrap();
+    let history = dir.path().join("daemon.json");
+    let history = history.to_str().unwrap();
+
+    let mut daemon = cli(dir.path())
+        .args(["--history", history, "daemon", "run"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("CLI konnte nicht gestartet werden");
+    let mut banner = String::new();
+    BufReader::new(daemon.stdout.take().unwrap())
+        .read_line(&mut banner)
+        .unwrap();
+    assert!(banner.starts_with("Daemon läuft"));
+
+    assert!(run_cli(dir.path(), &["add", "1", "1"]).status.success());
+
+    let killed = Command::new("kill")
+        .args(["-TERM", &daemon.id().to_string()])
+        .status()
+        .unwrap();
+    assert!(killed.success());
+    assert!(daemon.wait().unwrap().success());
+
+    assert!(!dir.path().join("daemon.sock").exists());
+    let stats = stdout(&run_cli(dir.path(), &["--history", history, "stats"]));
+    assert!(stats.contains("Operationen gesamt: 1"));
+}
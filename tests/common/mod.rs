@@ -4,9 +4,9 @@ use rust_project_complete::Calculator;
 
 pub fn setup_calculator_with_history() -> Calculator {
     let mut calc = Calculator::new();
-    calc.add(1.0, 2.0);
-    calc.multiply(3.0, 4.0);
-    calc.subtract(10.0, 5.0);
+    calc.add(1.0, 2.0).unwrap();
+    calc.multiply(3.0, 4.0).unwrap();
+    calc.subtract(10.0, 5.0).unwrap();
     calc
 }
 
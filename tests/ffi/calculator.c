@@ -0,0 +1,54 @@
+/* Nutzt die C-Schnittstelle wie ein eingebettetes C-/C++-Programm. */
+#include <stdio.h>
+#include <string.h>
+
+#include "rust_project_complete.h"
+
+#define CHECK(condition)                                                  \
+    do {                                                                  \
+        if (!(condition)) {                                               \
+            fprintf(stderr, "%s:%d: %s\n", __FILE__, __LINE__, #condition); \
+            return 1;                                                     \
+        }                                                                 \
+    } while (0)
+
+int main(void) {
+    CalcCalculator *calc = calc_new();
+    double result = 0.0;
+    uint64_t factorial = 0;
+    CalcOperation entry;
+
+    CHECK(calc != NULL);
+    CHECK(calc_add(calc, 2.0, 3.0, &result) == CALC_STATUS_OK);
+    CHECK(result == 5.0);
+    CHECK(calc_multiply(calc, result, 4.0, &result) == CALC_STATUS_OK);
+    CHECK(result == 20.0);
+    CHECK(calc_evaluate(calc, "(1 + 2) * 3", &result) == CALC_STATUS_OK);
+    CHECK(result == 9.0);
+    CHECK(calc_factorial(calc, 5, &factorial) == CALC_STATUS_OK);
+    CHECK(factorial == 120);
+
+    CHECK(calc_divide(calc, 1.0, 0.0, &result) == CALC_STATUS_DIVISION_BY_ZERO);
+    CHECK(strcmp(calc_status_name(CALC_STATUS_DIVISION_BY_ZERO), "division_by_zero") == 0);
+    CHECK(strstr(calc_last_error_message(), "Division durch Null") != NULL);
+    CHECK(calc_evaluate(calc, "2 +", &result) == CALC_STATUS_PARSE_ERROR);
+    CHECK(calc_add(calc, 1.0, 1.0, NULL) == CALC_STATUS_INVALID_ARGUMENT);
+
+    CHECK(calc_history_count(calc) == 5);
+    CHECK(calc_history_entry(calc, 1, &entry) == CALC_STATUS_OK);
+    CHECK(entry.id == 2);
+    CHECK(entry.operation_type == CALC_OPERATION_TYPE_MULTIPLY);
+    CHECK(entry.operands[0] == 5.0 && entry.operands[1] == 4.0);
+    CHECK(entry.result == 20.0);
+    CHECK(calc_history_entry(calc, 4, &entry) == CALC_STATUS_OK);
+    CHECK(entry.operation_type == CALC_OPERATION_TYPE_CUSTOM);
+    CHECK(entry.result == 120.0);
+    CHECK(calc_history_entry(calc, 5, &entry) == CALC_STATUS_INVALID_INPUT);
+
+    CHECK(calc_clear_history(calc) == CALC_STATUS_OK);
+    CHECK(calc_history_count(calc) == 0);
+    calc_free(calc);
+
+    puts("ok");
+    return 0;
+}
@@ -0,0 +1,88 @@
+//! Tests der C-Schnittstelle
+//!
+//! Prüft, dass der eingecheckte Header aktuell ist, und übersetzt ein kleines
+//! C-Programm gegen die gebaute Bibliothek.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const HEADER: &str = "include/rust_project_complete.h";
+
+fn manifest_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Verzeichnis mit der `cdylib`
+///
+/// `cargo test` legt sie nur unter `deps/` ab, `cargo build` zusätzlich
+/// daneben.
+fn library_dir() -> PathBuf {
+    let target_dir = Path::new(env!("CARGO_BIN_EXE_rust_project_complete"))
+        .parent()
+        .unwrap();
+    let library = format!(
+        "{}rust_project_complete{}",
+        std::env::consts::DLL_PREFIX,
+        std::env::consts::DLL_SUFFIX
+    );
+    [target_dir.join("deps"), target_dir.to_path_buf()]
+        .into_iter()
+        .find(|dir| dir.join(&library).exists())
+        .expect("C-Bibliothek wurde nicht gebaut")
+}
+
+#[test]
+fn test_header_is_up_to_date() {
+    let config = cbindgen::Config::from_file(manifest_dir().join("cbindgen.toml")).unwrap();
+    let mut generated = Vec::new();
+    cbindgen::Builder::new()
+        .with_crate(manifest_dir())
+        .with_config(config)
+        .generate()
+        .expect("Header konnte nicht erzeugt werden")
+        .write(&mut generated);
+    let generated = String::from_utf8(generated).unwrap();
+
+    let path = manifest_dir().join(HEADER);
+    if std::env::var_os("UPDATE_HEADER").is_some() {
+        std::fs::write(&path, &generated).unwrap();
+    }
+    let committed = std::fs::read_to_string(&path).unwrap_or_default();
+    assert!(
+        committed == generated,
+        "{} ist veraltet; mit UPDATE_HEADER=1 cargo test --test ffi_tests neu erzeugen",
+        HEADER
+    );
+}
+
+#[test]
+#[cfg(unix)]
+fn test_c_program() {
+    let dir = tempfile::tempdir().unwrap();
+    let program = dir.path().join("calculator");
+    let library_dir = library_dir();
+    let compiler = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+
+    let status = Command::new(compiler)
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg(manifest_dir().join("tests/ffi/calculator.c"))
+        .arg("-I")
+        .arg(manifest_dir().join("include"))
+        .arg("-L")
+        .arg(&library_dir)
+        .arg(format!("-Wl,-rpath,{}", library_dir.display()))
+        .args(["-lrust_project_complete", "-o"])
+        .arg(&program)
+        .status()
+        .expect("C-Compiler konnte nicht gestartet werden");
+    assert!(status.success(), "C-Programm ließ sich nicht übersetzen");
+
+    let output = Command::new(&program).output().unwrap();
+    assert!(
+        output.status.success(),
+        "C-Programm fehlgeschlagen: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "ok");
+}
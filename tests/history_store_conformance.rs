@@ -0,0 +1,240 @@
+//! Gemeinsame Konformitätstests für alle Verlaufsspeicher
+
+use rust_project_complete::modules::history::{
+    FileHistoryStore, HistoryQuery, HistoryStore, MemoryHistoryStore,
+};
+use rust_project_complete::{Calculator, Operation, OperationType};
+use tempfile::TempDir;
+
+fn operation(id: u64, operation_type: OperationType, timestamp: u64) -> Operation {
+    Operation {
+        id,
+        operation_type,
+        operands: vec![id as f64, 2.0],
+        result: id as f64 * 10.0,
+        timestamp,
+    }
+}
+
+/// Füllt einen Speicher mit Operationen im Abstand von 100 Sekunden
+fn fill(store: &mut dyn HistoryStore) {
+    let types = [
+        OperationType::Add,
+        OperationType::Divide,
+        OperationType::Multiply,
+        OperationType::Divide,
+        OperationType::Subtract,
+        OperationType::Divide,
+    ];
+    for (index, operation_type) in types.into_iter().enumerate() {
+        let id = index as u64 + 1;
+        store.append(&operation(id, operation_type, id * 100)).unwrap();
+    }
+}
+
+fn ids(operations: &[Operation]) -> Vec<u64> {
+    operations.iter().map(|op| op.id).collect()
+}
+
+/// Erzeugt dieselbe Testreihe für jeden Speicher
+///
+/// `$open` erhält ein temporäres Verzeichnis und öffnet darin den Speicher;
+/// `$persistent` gibt an, ob erneutes Öffnen den Inhalt wiederherstellt.
+macro_rules! conformance_tests {
+    ($name:ident, $open:expr, $persistent:expr) => {
+        mod $name {
+            use super::*;
+
+            fn open(dir: &TempDir) -> Box<dyn HistoryStore> {
+                let open: fn(&TempDir) -> Box<dyn HistoryStore> = $open;
+                open(dir)
+            }
+
+            #[test]
+            fn test_starts_empty() {
+                let dir = tempfile::tempdir().unwrap();
+                let store = open(&dir);
+
+                assert!(store.is_empty());
+                assert_eq!(store.len(), 0);
+                assert!(store.operations().unwrap().is_empty());
+            }
+
+            #[test]
+            fn test_append_preserves_order() {
+                let dir = tempfile::tempdir().unwrap();
+                let mut store = open(&dir);
+                fill(store.as_mut());
+
+                assert_eq!(store.len(), 6);
+                let operations = store.operations().unwrap();
+                assert_eq!(ids(&operations), vec![1, 2, 3, 4, 5, 6]);
+                assert_eq!(operations[1], operation(2, OperationType::Divide, 200));
+
+                let mut scanned = Vec::new();
+                store.scan(&mut |op| scanned.push(op.id)).unwrap();
+                assert_eq!(scanned, vec![1, 2, 3, 4, 5, 6]);
+            }
+
+            #[test]
+            fn test_query_filters() {
+                let dir = tempfile::tempdir().unwrap();
+                let mut store = open(&dir);
+                fill(store.as_mut());
+
+                let query = |q: HistoryQuery| ids(&store.query(&q).unwrap());
+                assert_eq!(query(HistoryQuery::new()), vec![1, 2, 3, 4, 5, 6]);
+                assert_eq!(
+                    query(HistoryQuery::new().operation_type(OperationType::Divide)),
+                    vec![2, 4, 6]
+                );
+                assert_eq!(query(HistoryQuery::new().since(300)), vec![3, 4, 5, 6]);
+                assert_eq!(query(HistoryQuery::new().until(300)), vec![1, 2]);
+                assert_eq!(query(HistoryQuery::new().after_id(4)), vec![5, 6]);
+                assert_eq!(query(HistoryQuery::new().limit(2)), vec![1, 2]);
+                assert_eq!(query(HistoryQuery::new().offset(4)), vec![5, 6]);
+                assert_eq!(query(HistoryQuery::new().offset(2).limit(2)), vec![3, 4]);
+                assert!(query(HistoryQuery::new().offset(6)).is_empty());
+                assert_eq!(
+                    query(
+                        HistoryQuery::new()
+                            .operation_type(OperationType::Divide)
+                            .offset(1)
+                            .limit(1)
+                    ),
+                    vec![4]
+                );
+                assert_eq!(
+                    query(
+                        HistoryQuery::new()
+                            .operation_type(OperationType::Divide)
+                            .since(300)
+                            .limit(1)
+                    ),
+                    vec![4]
+                );
+                assert!(query(HistoryQuery::new().operation_type(OperationType::Add).since(200))
+                    .is_empty());
+            }
+
+            #[test]
+            fn test_clear() {
+                let dir = tempfile::tempdir().unwrap();
+                let mut store = open(&dir);
+                fill(store.as_mut());
+                store.clear().unwrap();
+
+                assert!(store.is_empty());
+                assert!(store.operations().unwrap().is_empty());
+
+                store.append(&operation(7, OperationType::Add, 700)).unwrap();
+                assert_eq!(ids(&store.operations().unwrap()), vec![7]);
+            }
+
+            #[test]
+            fn test_nan_result_round_trips() {
+                let dir = tempfile::tempdir().unwrap();
+                let mut store = open(&dir);
+                let mut op = operation(1, OperationType::Subtract, 100);
+                op.result = f64::NAN;
+                store.append(&op).unwrap();
+
+                assert!(store.operations().unwrap()[0].result.is_nan());
+            }
+
+            #[test]
+            fn test_infinite_values_round_trip() {
+                let dir = tempfile::tempdir().unwrap();
+                let mut store = open(&dir);
+                let mut op = operation(1, OperationType::Multiply, 100);
+                op.operands = vec![f64::INFINITY, f64::NEG_INFINITY];
+                op.result = f64::NEG_INFINITY;
+                store.append(&op).unwrap();
+                let mut op = operation(2, OperationType::Add, 200);
+                op.operands = vec![1e200, f64::NAN];
+                op.result = f64::INFINITY;
+                store.append(&op).unwrap();
+
+                let operations = store.operations().unwrap();
+                assert_eq!(operations[0].operands, vec![f64::INFINITY, f64::NEG_INFINITY]);
+                assert_eq!(operations[0].result, f64::NEG_INFINITY);
+                assert!(operations[1].operands[1].is_nan());
+                assert_eq!(operations[1].result, f64::INFINITY);
+            }
+
+            #[test]
+            fn test_calculator_with_store() {
+                let dir = tempfile::tempdir().unwrap();
+                let mut store = open(&dir);
+                fill(store.as_mut());
+
+                let mut calc = Calculator::with_store(store).unwrap();
+                assert_eq!(calc.history_count(), 6);
+                assert_eq!(calc.statistics().get_operation_count(&OperationType::Divide), 3);
+                assert_eq!(calc.statistics().max_result, 60.0);
+
+                calc.add(1.0, 1.0).unwrap();
+                let history = calc.query_history(&HistoryQuery::new()).unwrap();
+                assert_eq!(history.last().unwrap().id, 7);
+
+                calc.clear_history().unwrap();
+                assert_eq!(calc.history_count(), 0);
+                assert!(calc.statistics().is_empty());
+            }
+
+            #[test]
+            fn test_reopen() {
+                let dir = tempfile::tempdir().unwrap();
+                {
+                    let mut calc = Calculator::with_store(open(&dir)).unwrap();
+                    calc.add(1.0, 2.0).unwrap();
+                    calc.divide(8.0, 2.0).unwrap();
+                }
+
+                let calc = Calculator::with_store(open(&dir)).unwrap();
+                if $persistent {
+                    assert_eq!(calc.history_count(), 2);
+                    assert_eq!(calc.statistics().get_operation_count(&OperationType::Divide), 1);
+                } else {
+                    assert_eq!(calc.history_count(), 0);
+                }
+            }
+        }
+    };
+}
+
+conformance_tests!(
+    memory,
+    |_dir| Box::new(MemoryHistoryStore::new()),
+    false
+);
+
+conformance_tests!(
+    file,
+    |dir| {
+        let path = dir.path().join("history.jsonl");
+        Box::new(FileHistoryStore::open(path.to_str().unwrap()).unwrap())
+    },
+    true
+);
+
+#[cfg(feature = "sqlite")]
+conformance_tests!(
+    sqlite,
+    |dir| {
+        use rust_project_complete::modules::history::SqliteHistoryStore;
+        let path = dir.path().join("history.db");
+        Box::new(SqliteHistoryStore::open(path.to_str().unwrap()).unwrap())
+    },
+    true
+);
+
+conformance_tests!(
+    audit,
+    |dir| {
+        use rust_project_complete::modules::audit::AuditHistoryStore;
+        let path = dir.path().join("audit.jsonl");
+        Box::new(AuditHistoryStore::open(path.to_str().unwrap()).unwrap())
+    },
+    true
+);
@@ -1,19 +1,19 @@
 //! Integrationstests
 
-use rust_project_complete::Calculator;
+use rust_project_complete::{Calculator, OperationType};
 use rust_project_complete::modules::core::{Statistics, MathUtils};
+use rust_project_complete::modules::history::HistoryQuery;
 use rust_project_complete::modules::utils::*;
 use tempfile::NamedTempFile;
-use std::io::Write;
 
 #[test]
 fn test_calculator_integration() {
     let mut calc = Calculator::new();
     
     // Komplexe Berechnungssequenz
-    let result1 = calc.add(10.0, 5.0);
-    let result2 = calc.multiply(result1, 2.0);
-    let result3 = calc.subtract(result2, 5.0);
+    let result1 = calc.add(10.0, 5.0).unwrap();
+    let result2 = calc.multiply(result1, 2.0).unwrap();
+    let result3 = calc.subtract(result2, 5.0).unwrap();
     let result4 = calc.divide(result3, 5.0).unwrap();
     
     assert_eq!(result4, 5.0);
@@ -25,14 +25,14 @@ fn test_statistics_integration() {
     let mut stats = Statistics::new();
     
     // Füge verschiedene Operationen hinzu
-    stats.add_operation("add", 10.0);
-    stats.add_operation("multiply", 20.0);
-    stats.add_operation("subtract", 5.0);
-    stats.add_operation("add", 15.0);
+    stats.add_operation(OperationType::Add, 10.0);
+    stats.add_operation(OperationType::Multiply, 20.0);
+    stats.add_operation(OperationType::Subtract, 5.0);
+    stats.add_operation(OperationType::Add, 15.0);
     
     assert_eq!(stats.total_operations, 4);
-    assert_eq!(stats.get_operation_count("add"), 2);
-    assert_eq!(stats.get_operation_count("multiply"), 1);
+    assert_eq!(stats.get_operation_count(&OperationType::Add), 2);
+    assert_eq!(stats.get_operation_count(&OperationType::Multiply), 1);
     assert_eq!(stats.average_result, 12.5);
     assert_eq!(stats.min_result, 5.0);
     assert_eq!(stats.max_result, 20.0);
@@ -60,7 +60,7 @@ fn test_math_utils_integration() {
 
 #[test]
 fn test_file_operations_integration() {
-    let mut temp_file = NamedTempFile::new().unwrap();
+    let temp_file = NamedTempFile::new().unwrap();
     let file_path = temp_file.path().to_str().unwrap();
     
     // Schreibe JSON-Daten
@@ -88,7 +88,7 @@ fn test_utils_integration() {
     assert_eq!(avg, 3.0);
     assert_eq!(min, 1.0);
     assert_eq!(max, 5.0);
-    assert!((std_dev - 1.5811).abs() < 0.001);
+    assert!((std_dev - std::f64::consts::SQRT_2).abs() < 0.001);
 }
 
 #[test]
@@ -111,8 +111,8 @@ fn test_error_handling_integration() {
 #[test]
 fn test_serialization_integration() {
     let mut calc = Calculator::new();
-    calc.add(1.0, 2.0);
-    calc.multiply(3.0, 4.0);
+    calc.add(1.0, 2.0).unwrap();
+    calc.multiply(3.0, 4.0).unwrap();
     
     // Serialisiere zu JSON
     let json = serde_json::to_string(&calc).unwrap();
@@ -121,7 +121,7 @@ fn test_serialization_integration() {
     let deserialized: Calculator = serde_json::from_str(&json).unwrap();
     
     assert_eq!(deserialized.history_count(), 2);
-    assert_eq!(deserialized.get_history().len(), 2);
+    assert_eq!(deserialized.query_history(&HistoryQuery::new()).unwrap().len(), 2);
 }
 
 #[test]
@@ -137,7 +137,7 @@ fn test_concurrent_operations() {
         let calc_clone = Arc::clone(&calc);
         let handle = thread::spawn(move || {
             let mut calc = calc_clone.lock().unwrap();
-            calc.add(i as f64, i as f64);
+            calc.add(i as f64, i as f64).unwrap();
         });
         handles.push(handle);
     }
@@ -150,3 +150,137 @@ fn test_concurrent_operations() {
     let final_calc = calc.lock().unwrap();
     assert_eq!(final_calc.history_count(), 5);
 }
+
+#[test]
+fn test_observer_shared_across_threads() {
+    use rust_project_complete::modules::observer::{CalculatorObserver, HistoryEvent};
+    use rust_project_complete::Operation;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    #[derive(Default)]
+    struct Counter {
+        operations: AtomicUsize,
+        appended: AtomicUsize,
+    }
+
+    impl CalculatorObserver for Counter {
+        fn after_operation(&self, _operation: &Operation) {
+            self.operations.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn history_changed(&self, event: &HistoryEvent<'_>) {
+            if let HistoryEvent::Appended(_) = event {
+                self.appended.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+    }
+
+    let counter = Arc::new(Counter::default());
+    let calc = Arc::new(Mutex::new(Calculator::new()));
+    calc.lock().unwrap().add_observer(counter.clone());
+
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            let calc = Arc::clone(&calc);
+            thread::spawn(move || {
+                for _ in 0..10 {
+                    calc.lock().unwrap().multiply(i as f64, 2.0).unwrap();
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(counter.operations.load(Ordering::SeqCst), 40);
+    assert_eq!(counter.appended.load(Ordering::SeqCst), 40);
+}
+
+#[test]
+fn test_shared_calculator_stress_threads() {
+    use rust_project_complete::modules::shared::SharedCalculator;
+    use std::collections::HashSet;
+    use std::thread;
+
+    const THREADS: usize = 8;
+    const OPERATIONS: usize = 500;
+
+    let calc = SharedCalculator::new();
+
+    let writers: Vec<_> = (0..THREADS)
+        .map(|t| {
+            let calc = calc.clone();
+            thread::spawn(move || {
+                for i in 0..OPERATIONS {
+                    calc.add(t as f64, i as f64).unwrap();
+                }
+            })
+        })
+        .collect();
+
+    // Leser laufen parallel zu den Schreibern und sehen stets einen geordneten Verlauf
+    let readers: Vec<_> = (0..2)
+        .map(|_| {
+            let calc = calc.clone();
+            thread::spawn(move || {
+                for _ in 0..100 {
+                    let history = calc.query_history(&HistoryQuery::new()).unwrap();
+                    assert!(history.windows(2).all(|w| w[0].id < w[1].id));
+                }
+            })
+        })
+        .collect();
+
+    for handle in writers.into_iter().chain(readers) {
+        handle.join().unwrap();
+    }
+
+    let history = calc.query_history(&HistoryQuery::new()).unwrap();
+    assert_eq!(history.len(), THREADS * OPERATIONS);
+
+    let ids: Vec<u64> = history.iter().map(|op| op.id).collect();
+    let expected: Vec<u64> = (1..=(THREADS * OPERATIONS) as u64).collect();
+    assert_eq!(ids, expected);
+
+    // Jede einzelne Operation ist genau einmal im Verlauf
+    let operands: HashSet<(u64, u64)> = history
+        .iter()
+        .map(|op| (op.operands[0] as u64, op.operands[1] as u64))
+        .collect();
+    assert_eq!(operands.len(), THREADS * OPERATIONS);
+    assert_eq!(calc.statistics().total_operations, (THREADS * OPERATIONS) as u64);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_shared_calculator_stress_tokio_tasks() {
+    use rust_project_complete::modules::shared::SharedCalculator;
+
+    const TASKS: usize = 32;
+    const OPERATIONS: usize = 100;
+
+    let calc = SharedCalculator::new();
+
+    let tasks: Vec<_> = (0..TASKS)
+        .map(|t| {
+            let calc = calc.clone();
+            tokio::spawn(async move {
+                for i in 0..OPERATIONS {
+                    calc.multiply(t as f64, i as f64).unwrap();
+                    tokio::task::yield_now().await;
+                }
+            })
+        })
+        .collect();
+
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    let history = calc.query_history(&HistoryQuery::new()).unwrap();
+    assert_eq!(history.len(), TASKS * OPERATIONS);
+    assert!(history.windows(2).all(|w| w[0].id + 1 == w[1].id));
+}
@@ -0,0 +1,106 @@
+//! Tests gegen einen lokal gestarteten Server (`serve`)
+#![cfg(feature = "server")]
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::process::{Child, Command, Stdio};
+
+/// Beendet den Server auch, wenn ein Test fehlschlägt
+struct Server {
+    child: Child,
+    address: String,
+}
+
+impl Server {
+    fn start() -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_rust_project_complete"))
+            .args(["serve", "--address", "127.0.0.1:0"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Server konnte nicht gestartet werden");
+
+        let mut line = String::new();
+        BufReader::new(child.stdout.take().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        let address = line
+            .trim()
+            .rsplit("http://")
+            .next()
+            .expect("Adresse fehlt in der Ausgabe")
+            .to_string();
+        Self { child, address }
+    }
+
+    /// Sendet eine HTTP/1.1-Anfrage und gibt Kopfzeilen und Körper zurück
+    fn request(&self, method: &str, path: &str, body: &str) -> (String, String) {
+        let mut stream = TcpStream::connect(&self.address).unwrap();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            method,
+            path,
+            self.address,
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        (head.to_string(), body.to_string())
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Wert einer Kennzahl ohne Zeitstempel, z. B. `calculator_history_size`
+fn sample(metrics: &str, series: &str) -> Option<f64> {
+    metrics
+        .lines()
+        .find_map(|line| line.strip_prefix(series)?.strip_prefix(' '))
+        .map(|value| value.parse().unwrap())
+}
+
+#[test]
+fn test_scrape_metrics() {
+    let server = Server::start();
+    server.request("POST", "/add", r#"{"a": 1, "b": 2}"#);
+    server.request("POST", "/multiply", r#"{"a": 3, "b": 4}"#);
+    for _ in 0..3 {
+        let (head, _) = server.request("POST", "/divide", r#"{"a": 1, "b": 0}"#);
+        assert!(head.starts_with("HTTP/1.1 422"));
+    }
+
+    let (head, metrics) = server.request("GET", "/metrics", "");
+    assert!(head.starts_with("HTTP/1.1 200"));
+    assert!(head
+        .to_ascii_lowercase()
+        .contains("content-type: text/plain; version=0.0.4"));
+
+    assert_eq!(
+        sample(&metrics, r#"calculator_operations_total{operation="add"}"#),
+        Some(1.0)
+    );
+    assert_eq!(
+        sample(
+            &metrics,
+            r#"calculator_errors_total{operation="divide",kind="division_by_zero"}"#
+        ),
+        Some(3.0)
+    );
+    assert_eq!(
+        sample(
+            &metrics,
+            r#"calculator_operation_duration_seconds_count{operation="divide"}"#
+        ),
+        Some(3.0)
+    );
+    assert_eq!(sample(&metrics, "calculator_history_size"), Some(2.0));
+}